@@ -81,6 +81,16 @@ impl Comments {
         self.trailing.retain(f);
     }
 
+    /// Returns clones of all the leading and trailing comments, in no
+    /// particular order.
+    pub fn to_vec(&self) -> Vec<Comment> {
+        self.leading
+            .iter()
+            .chain(self.trailing.iter())
+            .flat_map(|entry| entry.value().clone())
+            .collect()
+    }
+
     /// Takes all the comments as (leading, trailing).
     pub fn take_all(self) -> (CommentMap, CommentMap) {
         (self.leading, self.trailing)
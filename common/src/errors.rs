@@ -273,6 +273,7 @@ pub struct Handler {
     pub flags: HandlerFlags,
 
     err_count: AtomicUsize,
+    warn_count: AtomicUsize,
     emitter: Lock<Box<dyn Emitter + Send>>,
     continue_after_error: LockCell<bool>,
     delayed_span_bugs: Lock<Vec<Diagnostic>>,
@@ -376,6 +377,7 @@ impl Handler {
         Handler {
             flags,
             err_count: AtomicUsize::new(0),
+            warn_count: AtomicUsize::new(0),
             emitter: Lock::new(e),
             continue_after_error: LockCell::new(true),
             delayed_span_bugs: Lock::new(Vec::new()),
@@ -617,6 +619,11 @@ impl Handler {
         self.err_count.load(SeqCst)
     }
 
+    /// Returns the number of emitted warnings.
+    pub fn warn_count(&self) -> usize {
+        self.warn_count.load(SeqCst)
+    }
+
     pub fn has_errors(&self) -> bool {
         self.err_count() > 0
     }
@@ -742,6 +749,8 @@ impl Handler {
             self.emitter.borrow_mut().emit(db);
             if db.is_error() {
                 self.bump_err_count();
+            } else if db.level == Warning {
+                self.warn_count.fetch_add(1, SeqCst);
             }
         }
     }
@@ -1,5 +1,5 @@
 use crate::syntax_pos::{BytePos, SourceFile};
-use std::str;
+use std::{ops::Range, str};
 
 #[derive(Clone)]
 pub struct SourceFileInput<'a> {
@@ -24,6 +24,25 @@ impl<'a> From<&'a SourceFile> for SourceFileInput<'a> {
     }
 }
 
+impl<'a> SourceFileInput<'a> {
+    /// Creates an input which reads only `range` of `fm`, e.g. to parse a
+    /// part of a file. Positions are still relative to the start of `fm`.
+    pub fn with_range(fm: &'a SourceFile, range: Range<BytePos>) -> Self {
+        let start = (range.start - fm.start_pos).0 as usize;
+        let end = (range.end - fm.start_pos).0 as usize;
+        // Positions are relative to `fm.start_pos`, so only the end is cut.
+        let orig = &fm.src[..end];
+
+        SourceFileInput {
+            start_pos: range.start,
+            last_pos: range.start,
+            orig,
+            iter: orig[start..].char_indices(),
+            fm,
+        }
+    }
+}
+
 impl<'a> Input for SourceFileInput<'a> {
     fn cur(&mut self) -> Option<char> {
         self.iter.clone().nth(0).map(|i| i.1)
@@ -247,6 +266,23 @@ mod tests {
         });
     }
 
+    #[test]
+    fn src_input_with_range() {
+        let cm = Arc::new(SourceMap::new(FilePathMapping::empty()));
+        let fm = cm.new_source_file(FileName::Real("testing".into()), "foo/d".into());
+        let start = fm.start_pos + BytePos(1);
+
+        let mut i = SourceFileInput::with_range(&fm, start..fm.start_pos + BytePos(4));
+        assert_eq!(i.cur_pos(), start);
+        assert!(!i.is_at_start());
+        assert_eq!(i.uncons_while(|_| true), "oo/");
+        assert_eq!(i.cur(), None);
+
+        i.reset_to(start);
+        assert_eq!(i.cur(), Some('o'));
+        assert_eq!(i.slice(start, start + BytePos(2)), "oo");
+    }
+
     //    #[test]
     //    fn src_input_smoke_02() {
     //        let _ = crate::with_test_sess("℘℘/℘℘", | mut i| {
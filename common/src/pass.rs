@@ -1,15 +1,114 @@
 #[cfg(feature = "fold")]
 use crate::{Fold, FoldWith};
+use scoped_tls::scoped_thread_local;
 use serde::export::PhantomData;
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    cell::RefCell,
+    time::{Duration, Instant},
+};
 
 pub trait CompilerPass {
     /// Name should follow hyphen-case
     ///
-    /// TODO: timing
+    /// Used to report timing of passes wrapped with [Timed].
     fn name() -> Cow<'static, str>;
 }
 
+scoped_thread_local!(
+    /// Time spent by [Timed] passes is recorded only if this is set.
+    pub static TIMINGS: Timings
+);
+
+/// Time spent by each pass, in the order of first invocation.
+#[derive(Debug, Default)]
+pub struct Timings {
+    passes: RefCell<Vec<(Cow<'static, str>, Duration)>>,
+}
+
+impl Timings {
+    /// Adds `time` to the time spent by the pass named `name`.
+    pub fn record(&self, name: Cow<'static, str>, time: Duration) {
+        let mut passes = self.passes.borrow_mut();
+
+        match passes.iter_mut().find(|(n, _)| *n == name) {
+            Some((_, total)) => *total += time,
+            None => passes.push((name, time)),
+        }
+    }
+
+    pub fn take(&self) -> Vec<(Cow<'static, str>, Duration)> {
+        self.passes.replace(Default::default())
+    }
+}
+
+/// Records time spent by `P` to [TIMINGS].
+#[derive(Debug, Clone)]
+pub struct Timed<P> {
+    name: Cow<'static, str>,
+    pass: P,
+}
+
+impl<P> Timed<P>
+where
+    P: CompilerPass,
+{
+    pub fn new(pass: P) -> Self {
+        Self::with_name(P::name(), pass)
+    }
+}
+
+impl<P> Timed<P> {
+    /// Records time spent by `pass` as `name`, for passes which don't
+    /// implement [CompilerPass].
+    pub fn with_name(name: impl Into<Cow<'static, str>>, pass: P) -> Self {
+        Self {
+            name: name.into(),
+            pass,
+        }
+    }
+}
+
+impl<P> CompilerPass for Timed<P>
+where
+    P: CompilerPass,
+{
+    fn name() -> Cow<'static, str> {
+        P::name()
+    }
+}
+
+impl<P> Repeated for Timed<P>
+where
+    P: Repeated,
+{
+    fn changed(&self) -> bool {
+        self.pass.changed()
+    }
+
+    fn reset(&mut self) {
+        self.pass.reset()
+    }
+}
+
+#[cfg(feature = "fold")]
+impl<P, At> Fold<At> for Timed<P>
+where
+    At: FoldWith<Self> + FoldWith<P>,
+{
+    fn fold(&mut self, node: At) -> At {
+        if !TIMINGS.is_set() {
+            return node.fold_with(&mut self.pass);
+        }
+
+        let start = Instant::now();
+        let node = node.fold_with(&mut self.pass);
+        TIMINGS.with(|timings| timings.record(self.name.clone(), start.elapsed()));
+
+        node
+    }
+}
+
 pub trait Repeated: CompilerPass {
     /// Should run again?
     fn changed(&self) -> bool;
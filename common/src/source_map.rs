@@ -1003,6 +1003,30 @@ impl SourceMap {
         &self,
         mappings: &mut Vec<(BytePos, LineCol)>,
         orig: Option<&sourcemap::SourceMap>,
+    ) -> sourcemap::SourceMap {
+        self.build_source_map_with_names(mappings, orig, &|name| name.to_string())
+    }
+
+    /// Creates a `.map` file, using `source_name` to get the name of a source
+    /// file in `sources`.
+    pub fn build_source_map_with_names(
+        &self,
+        mappings: &mut Vec<(BytePos, LineCol)>,
+        orig: Option<&sourcemap::SourceMap>,
+        source_name: &dyn Fn(&FileName) -> String,
+    ) -> sourcemap::SourceMap {
+        self.build_source_map_with_idents(mappings, orig, source_name, &|_| None)
+    }
+
+    /// Same as [SourceMap::build_source_map_with_names], but `ident_name` is
+    /// used to add the original name of an identifier starting at a position
+    /// to `names`, e.g. if the identifier is renamed.
+    pub fn build_source_map_with_idents(
+        &self,
+        mappings: &mut Vec<(BytePos, LineCol)>,
+        orig: Option<&sourcemap::SourceMap>,
+        source_name: &dyn Fn(&FileName) -> String,
+        ident_name: &dyn Fn(BytePos) -> Option<String>,
     ) -> sourcemap::SourceMap {
         let mut builder = SourceMapBuilder::new(None);
 
@@ -1010,10 +1034,7 @@ impl SourceMap {
         // mappings.sort_by_key(|v| v.0);
 
         let mut cur_file: Option<Arc<SourceFile>> = None;
-        let mut src_id;
-
-        let mut ch_start = 0;
-        let mut line_ch_start = 0;
+        let mut src_name = String::new();
 
         for (pos, lc) in mappings.iter() {
             let pos = *pos;
@@ -1029,11 +1050,10 @@ impl SourceMap {
                 Some(ref f) if f.start_pos <= pos && pos < f.end_pos => f,
                 _ => {
                     f = self.lookup_source_file(pos);
-                    src_id = builder.add_source(&f.name.to_string());
+                    src_name = source_name(&f.name);
+                    let src_id = builder.add_source(&src_name);
                     builder.set_source_contents(src_id, Some(&f.src));
                     cur_file = Some(f.clone());
-                    ch_start = 0;
-                    line_ch_start = 0;
                     &f
                 }
             };
@@ -1052,10 +1072,20 @@ impl SourceMap {
                 pos,
                 linebpos,
             );
-            let chpos = { self.calc_extra_bytes(&f, &mut ch_start, pos) };
-            let linechpos = { self.calc_extra_bytes(&f, &mut line_ch_start, linebpos) };
-
-            let mut col = max(chpos, linechpos) - min(chpos, linechpos);
+            // Column is the number of chars between the start of the line and `pos`.
+            let mut col = {
+                let start = f
+                    .multibyte_chars
+                    .binary_search_by_key(&linebpos, |mbc| mbc.pos)
+                    .unwrap_or_else(|idx| idx);
+                let extra_bytes: u32 = f.multibyte_chars[start..]
+                    .iter()
+                    .take_while(|mbc| mbc.pos < pos)
+                    .map(|mbc| mbc.bytes as u32 - 1)
+                    .sum();
+
+                (pos - linebpos).0 - extra_bytes
+            };
 
             if let Some(orig) = &orig {
                 if let Some(token) = orig.lookup_token(line, col) {
@@ -1064,7 +1094,15 @@ impl SourceMap {
                 }
             }
 
-            builder.add(lc.line, lc.col, line - 1, col, None, None);
+            let name = ident_name(pos);
+            builder.add(
+                lc.line,
+                lc.col,
+                line - 1,
+                col,
+                Some(&src_name),
+                name.as_deref(),
+            );
         }
 
         builder.into_sourcemap()
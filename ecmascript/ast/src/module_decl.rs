@@ -1,6 +1,6 @@
 use crate::{
     decl::Decl,
-    expr::{ClassExpr, Expr, FnExpr},
+    expr::{ClassExpr, Expr, FnExpr, ObjectLit},
     ident::Ident,
     lit::Str,
     typescript::{TsExportAssignment, TsImportEqualsDecl, TsInterfaceDecl, TsNamespaceExportDecl},
@@ -69,6 +69,10 @@ pub struct ImportDecl {
 
     #[serde(rename = "typeOnly")]
     pub type_only: bool,
+
+    /// `assert { type: 'json' }`
+    #[serde(default)]
+    pub asserts: Option<ObjectLit>,
 }
 
 /// `export * from 'mod'`
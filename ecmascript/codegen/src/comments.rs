@@ -12,19 +12,17 @@ macro_rules! write_comments {
             match cmt.kind {
                 CommentKind::Line => {
                     if $prefix_space {
-                        $e.wr.write_comment(cmt.span, " ")?;
+                        $e.wr.write_space()?;
                     }
-                    $e.wr.write_comment(cmt.span, "//")?;
-                    $e.wr.write_comment(cmt.span, &cmt.text)?;
+                    $e.wr.write_comment(cmt.span, &format!("//{}", cmt.text))?;
                     $e.wr.write_line()?;
                 }
                 CommentKind::Block => {
                     if $prefix_space {
-                        $e.wr.write_comment(cmt.span, " ")?;
+                        $e.wr.write_space()?;
                     }
-                    $e.wr.write_comment(cmt.span, "/*")?;
-                    $e.wr.write_comment(cmt.span, &cmt.text)?;
-                    $e.wr.write_comment(cmt.span, "*/")?;
+                    $e.wr
+                        .write_comment(cmt.span, &format!("/*{}*/", cmt.text))?;
                     $e.wr.write_line()?;
                 }
             }
@@ -54,6 +52,38 @@ impl<'a> Emitter<'a> {
         write_comments!(self, prefix_space, &cmts)
     }
 
+    /// Emits block comments created by transforms, which have a dummy span,
+    /// on the same line. Other comments at `pos` are left to the statement.
+    pub(super) fn emit_synthesized_trailing_comments_of_pos(&mut self, pos: BytePos) -> Result {
+        if pos == BytePos(0) {
+            return Ok(());
+        }
+
+        let comments = match self.comments {
+            Some(ref comments) => comments,
+            None => return Ok(()),
+        };
+
+        let cmts = match comments.take_trailing_comments(pos) {
+            Some(cmts) => cmts,
+            None => return Ok(()),
+        };
+        let (synthesized, others): (Vec<_>, Vec<_>) = cmts
+            .into_iter()
+            .partition(|cmt| cmt.kind == CommentKind::Block && cmt.span == DUMMY_SP);
+        for cmt in others {
+            comments.add_trailing(pos, cmt);
+        }
+
+        for cmt in synthesized {
+            self.wr.write_space()?;
+            self.wr
+                .write_comment(cmt.span, &format!("/*{}*/", cmt.text))?;
+        }
+
+        Ok(())
+    }
+
     pub(super) fn emit_leading_comments_of_pos(&mut self, pos: BytePos) -> Result {
         if pos == BytePos(0) {
             return Ok(());
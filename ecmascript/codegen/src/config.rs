@@ -1,4 +1,118 @@
-#[derive(Debug, Default, Clone, Copy)]
+use serde::{Deserialize, Serialize};
+use swc_atoms::JsWord;
+use swc_ecma_parser::JscTarget;
+
+#[derive(Debug, Default, Clone)]
 pub struct Config {
     pub minify: bool,
+
+    /// Quote style used for string literals.
+    ///
+    /// Ignored while minifying, as the quote which requires less escaping is
+    /// always used in that case.
+    pub quotes: QuoteStyle,
+
+    /// Preferred maximum length of a line.
+    ///
+    /// Comma-separated lists like arguments of a call or elements of an array
+    /// are wrapped before an element which would exceed this limit. Ignored
+    /// while minifying.
+    pub max_line_len: Option<usize>,
+
+    /// Controls trailing commas of array literals, object literals, named
+    /// imports / exports, arguments and parameters.
+    ///
+    /// If `None`, trailing commas of the original source are preserved.
+    /// Trailing commas are never added while minifying.
+    pub trailing_comma: Option<TrailingComma>,
+
+    /// Trailing commas of arguments are emitted only if the target supports
+    /// them. BigInt literals are reported to
+    /// [Handlers::on_unsupported_syntax](crate::Handlers::on_unsupported_syntax)
+    /// if the target doesn't support them.
+    ///
+    /// Numeric separators are emitted only if the target is `esnext` and
+    /// `number_format` is [NumberFormat::Preserve].
+    pub target: JscTarget,
+
+    /// Format of numeric literals.
+    ///
+    /// If `None`, [NumberFormat::Shortest] is used while minifying and numbers
+    /// are printed as decimals otherwise.
+    pub number_format: Option<NumberFormat>,
+
+    /// Controls shorthand properties like `{ a }` and methods like
+    /// `{ a() {} }`.
+    ///
+    /// If `Some(true)`, `{ a: a }` is collapsed to `{ a }`. Functions are not
+    /// turned into methods, as methods can't be constructed. If `Some(false)`,
+    /// shorthand properties and methods are expanded, except methods which may
+    /// use `super`. If `None`, shorthand is used while minifying and the form
+    /// of the source is kept otherwise.
+    ///
+    /// Properties with computed keys or named `__proto__` are never changed,
+    /// and nothing is collapsed if the target is older than es2015.
+    pub shorthand: Option<bool>,
+
+    /// Raw strings of tagged templates with these tags are emitted verbatim,
+    /// even while minifying. Libraries like styled-components parse them at
+    /// runtime.
+    ///
+    /// A tag matches if it's one of these identifiers, or a member access or a
+    /// call on one of them like `styled.div` or `styled(Button)`.
+    pub preserved_template_tags: Vec<JsWord>,
+
+    /// Omits the semicolon at the end of the output.
+    ///
+    /// The writer of the emitter should be wrapped with
+    /// [omit_trailing_semi](crate::text_writer::omit_trailing_semi). A
+    /// semicolon is emitted before the first statement if it could continue
+    /// the last statement of another output, so outputs can be concatenated
+    /// with line breaks between them.
+    pub omit_last_semi: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TrailingComma {
+    /// Never emit trailing commas.
+    #[serde(alias = "none")]
+    Never,
+    /// Emit trailing commas if the closing bracket is on its own line.
+    Multiline,
+    /// Always emit trailing commas of array literals, object literals and
+    /// named imports / exports, like `es5` of prettier.
+    Es5,
+    /// Always emit trailing commas, including ones of arguments and
+    /// parameters if the target supports them.
+    #[serde(alias = "all")]
+    Always,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NumberFormat {
+    /// Use the shortest of decimal, exponential and hexadecimal forms, like
+    /// `1e6` for `1000000`.
+    Shortest,
+    /// Keep the form of the source, like `1e3` or `0xff`, unless a transform
+    /// changed the value.
+    Preserve,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum QuoteStyle {
+    /// Always use single quotes.
+    Single,
+    /// Always use double quotes.
+    Double,
+    /// Use the quote which requires less escaping, preferring single quotes.
+    Preferred,
+}
+
+impl Default for QuoteStyle {
+    fn default() -> Self {
+        QuoteStyle::Preferred
+    }
 }
@@ -16,6 +16,25 @@ mod tests {
         assert_min(r#" 'foobar' "#, r#"'foobar';"#);
     }
 
+    #[test]
+    fn numbers() {
+        assert_min("1000", "1e3;");
+        assert_min("1200000", "12e5;");
+        assert_min("1099511627775", "0xffffffffff;");
+        assert_min("0.5", ".5;");
+        assert_min("0.00001", "1e-5;");
+        assert_min("1500", "1500;");
+        assert_min("0.125", ".125;");
+        assert_min("10", "10;");
+    }
+
+    #[test]
+    fn numbers_property_access() {
+        assert_min("1000..toString()", "1e3.toString();");
+        assert_min("0xff.toString()", "255..toString();");
+        assert_min("0.5.toFixed()", ".5.toFixed();");
+    }
+
     #[test]
     fn template_expression() {
         assert_min("``", "``;");
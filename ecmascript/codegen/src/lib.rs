@@ -1,7 +1,7 @@
 #![recursion_limit = "1024"]
 #![allow(unused_variables)]
 
-pub use self::config::Config;
+pub use self::config::{Config, NumberFormat, QuoteStyle, TrailingComma};
 use self::{
     list::ListFormat,
     text_writer::WriteJs,
@@ -12,6 +12,7 @@ use swc_atoms::JsWord;
 use swc_common::{comments::Comments, BytePos, SourceMap, Span, Spanned, SyntaxContext, DUMMY_SP};
 use swc_ecma_ast::*;
 use swc_ecma_codegen_macros::emitter;
+use swc_ecma_parser::JscTarget;
 
 #[macro_use]
 pub mod macros;
@@ -31,6 +32,15 @@ pub mod util;
 pub type Result = io::Result<()>;
 
 pub trait Handlers {
+    /// Called before a statement is emitted.
+    fn on_before_emit_stmt(&mut self, _node: &Stmt) {}
+
+    /// Called if a node is not supported by [Config::target], e.g. a BigInt
+    /// literal while targeting es2019. The node is emitted as is.
+    ///
+    /// [Config::target]: config::Config::target
+    fn on_unsupported_syntax(&mut self, _span: Span, _msg: &str) {}
+
     // fn on_before_emit_token(&mut self, _node: &Any) {}
     // fn on_after_emit_token(&mut self, _node: &Any) {}
 }
@@ -75,6 +85,9 @@ impl<'a> Emitter<'a> {
             self.wr.write_str_lit(DUMMY_SP, &*shebang)?;
             self.wr.write_line()?;
         }
+        if let Some(ModuleItem::Stmt(ref stmt)) = node.body.first() {
+            self.emit_asi_guard(stmt)?;
+        }
         for stmt in &node.body {
             emit!(stmt);
         }
@@ -87,11 +100,39 @@ impl<'a> Emitter<'a> {
             self.wr.write_str_lit(DUMMY_SP, &*shebang)?;
             self.wr.write_line()?;
         }
+        if let Some(stmt) = node.body.first() {
+            self.emit_asi_guard(stmt)?;
+        }
         for stmt in &node.body {
             emit!(stmt);
         }
     }
 
+    /// Writes a semicolon before `stmt`, the first statement of the output, if
+    /// it would continue the last statement of another output concatenated
+    /// before this one, e.g. `(function () {})()` after `a = b`.
+    ///
+    /// This is required only if the last semicolon is omitted.
+    fn emit_asi_guard(&mut self, stmt: &Stmt) -> Result {
+        if !self.cfg.omit_last_semi {
+            return Ok(());
+        }
+
+        match *stmt {
+            Stmt::Expr(ref e) => match *e.expr {
+                // A directive.
+                Expr::Lit(Lit::Str(..)) => {}
+                ref e if !e.starts_with_alpha_num() => {
+                    self.wr.write_punct(";")?;
+                }
+                _ => {}
+            },
+            _ => {}
+        }
+
+        Ok(())
+    }
+
     #[emitter]
     fn emit_module_item(&mut self, node: &ModuleItem) -> Result {
         match *node {
@@ -213,6 +254,20 @@ impl<'a> Emitter<'a> {
 
         formatting_space!();
         emit!(node.src);
+
+        if let Some(ref asserts) = node.asserts {
+            formatting_space!();
+            keyword!("assert");
+            formatting_space!();
+            punct!("{");
+            self.emit_list(
+                asserts.span,
+                Some(&asserts.props),
+                ListFormat::NamedImportsOrExportsElements,
+            )?;
+            punct!("}");
+        }
+
         semi!();
     }
 
@@ -335,20 +390,22 @@ impl<'a> Emitter<'a> {
         let value = escape(&node.value);
         // let value = node.value.replace("\n", "\\n");
 
-        if !node.value.contains('\'') {
-            punct!("'");
-            self.wr.write_str_lit(node.span, &value)?;
-            punct!("'");
-        } else if !node.value.contains('\"') {
-            punct!("\"");
-            self.wr.write_str_lit(node.span, &value)?;
-            punct!("\"");
+        let single_quote = match self.cfg.quotes {
+            QuoteStyle::Single if !self.cfg.minify => true,
+            QuoteStyle::Double if !self.cfg.minify => false,
+            _ => node.value.matches('\'').count() <= node.value.matches('"').count(),
+        };
+
+        // Quotes are written along with the value, so that the span maps to the
+        // whole literal.
+        let quoted = if single_quote {
+            format!("'{}'", value.replace("'", "\\'"))
         } else {
-            punct!("'");
-            self.wr
-                .write_str_lit(node.span, &value.replace("'", "\\'"))?;
-            punct!("'");
-        }
+            format!("\"{}\"", value.replace("\"", "\\\""))
+        };
+        self.wr.write_str_lit(node.span, &quoted)?;
+
+        self.emit_synthesized_trailing_comments_of_pos(node.span.hi())?;
     }
 
     #[emitter]
@@ -365,15 +422,58 @@ impl<'a> Emitter<'a> {
             if num.value.is_sign_negative() && num.value == 0.0 {
                 self.wr.write_str_lit(num.span, "-0.0")?;
             } else {
-                self.wr.write_str_lit(num.span, &format!("{}", num.value))?;
+                let text = self.format_number(num);
+                self.wr.write_str_lit(num.span, &text)?;
             }
         }
+
+        self.emit_synthesized_trailing_comments_of_pos(num.span.hi())?;
+    }
+
+    /// Returns the text of a finite numeric literal.
+    fn format_number(&self, num: &Number) -> String {
+        if self.cfg.number_format == Some(NumberFormat::Preserve) {
+            if let Some(raw) = self.raw_number(num) {
+                return raw;
+            }
+        }
+
+        if self.cfg.minify || self.cfg.number_format.is_some() {
+            minify_number(num.value)
+        } else {
+            format!("{}", num.value)
+        }
+    }
+
+    /// Returns the source text of `num` if it's a literal of the same value.
+    fn raw_number(&self, num: &Number) -> Option<String> {
+        if num.span.is_dummy() {
+            return None;
+        }
+
+        let raw = self.cm.span_to_snippet(num.span).ok()?;
+        let digits = raw.replace('_', "");
+        if parse_number(&digits)? != num.value {
+            return None;
+        }
+
+        // Numeric separators are es2021
+        if self.cfg.target < JscTarget::EsNext {
+            Some(digits)
+        } else {
+            Some(raw)
+        }
     }
 
     #[emitter]
     fn emit_big_lit(&mut self, v: &BigInt) -> Result {
         self.emit_leading_comments_of_pos(v.span.lo())?;
 
+        if self.cfg.target < JscTarget::EsNext {
+            self.handlers
+                .on_unsupported_syntax(v.span, "BigInt literals require the esnext target");
+        }
+
         self.wr.write_lit(v.span, &v.value.to_string())?;
         self.wr.write_lit(v.span, "n")?;
     }
@@ -550,7 +650,15 @@ impl<'a> Emitter<'a> {
         match *expr {
             ExprOrSuper::Expr(ref expr) => {
                 match **expr {
-                    Expr::Lit(Lit::Num(Number { span, value })) => {
+                    Expr::Lit(Lit::Num(ref num)) => {
+                        let Number { span, value } = *num;
+                        if self.cfg.minify || self.cfg.number_format.is_some() {
+                            // `1e3.toString` and `0xff.toString` are valid
+                            return self
+                                .format_number(num)
+                                .bytes()
+                                .all(|b| b.is_ascii_digit() || b == b'_');
+                        }
                         if value.fract() == 0.0 {
                             return true;
                         }
@@ -584,7 +692,11 @@ impl<'a> Emitter<'a> {
             punct!("*")
         }
         punct!("(");
-        self.emit_list(node.span, Some(&node.params), ListFormat::CommaListElements)?;
+        self.emit_list(
+            node.span,
+            Some(&node.params),
+            params_format(node.params.last()),
+        )?;
         punct!(")");
 
         punct!("=>");
@@ -759,7 +871,7 @@ impl<'a> Emitter<'a> {
             }
         }
 
-        self.emit_fn_trailing(&n.function)?;
+        self.emit_fn_trailing_with(&n.function, n.kind == MethodKind::Setter)?;
     }
 
     #[emitter]
@@ -809,12 +921,13 @@ impl<'a> Emitter<'a> {
             }
         }
 
+        let format = if n.kind == MethodKind::Setter {
+            ListFormat::CommaListElements
+        } else {
+            params_format(n.function.params.last().map(|p| &p.pat))
+        };
         punct!("(");
-        self.emit_list(
-            n.function.span,
-            Some(&n.function.params),
-            ListFormat::CommaListElements,
-        )?;
+        self.emit_list(n.function.span, Some(&n.function.params), format)?;
         punct!(")");
 
         if let Some(ty) = &n.function.return_type {
@@ -968,26 +1081,39 @@ impl<'a> Emitter<'a> {
     /// prints `(b){}` from `function a(b){}`
     #[emitter]
     fn emit_fn_trailing(&mut self, node: &Function) -> Result {
+        self.emit_fn_trailing_with(node, false)?;
+    }
+
+    /// Same as `emit_fn_trailing`, but a trailing comma is never emitted after
+    /// the parameter of a setter.
+    fn emit_fn_trailing_with(&mut self, node: &Function, is_setter: bool) -> Result {
         if let Some(type_params) = &node.type_params {
-            emit!(type_params);
+            emit!(self, type_params);
         }
 
-        punct!("(");
-        self.emit_list(node.span, Some(&node.params), ListFormat::CommaListElements)?;
-        punct!(")");
+        let format = if is_setter {
+            ListFormat::CommaListElements
+        } else {
+            params_format(node.params.last().map(|p| &p.pat))
+        };
+        punct!(self, "(");
+        self.emit_list(node.span, Some(&node.params), format)?;
+        punct!(self, ")");
 
         if let Some(ty) = &node.return_type {
-            punct!(":");
-            formatting_space!();
-            emit!(ty);
+            punct!(self, ":");
+            formatting_space!(self);
+            emit!(self, ty);
         }
 
         if let Some(body) = &node.body {
-            formatting_space!();
-            emit!(body);
+            formatting_space!(self);
+            emit!(self, body);
         } else {
-            semi!()
+            semi!(self)
         }
+
+        Ok(())
     }
 
     #[emitter]
@@ -1042,10 +1168,16 @@ impl<'a> Emitter<'a> {
         emit!(node.type_params);
         punct!("`");
         let i = 0;
+        let preserve = self.is_preserved_template_tag(&node.tag);
 
         for i in 0..(node.quasis.len() + node.exprs.len()) {
             if i % 2 == 0 {
-                emit!(node.quasis[i / 2]);
+                if preserve {
+                    let quasi = &node.quasis[i / 2];
+                    self.wr.write_str_lit(quasi.span, &quasi.raw.value)?;
+                } else {
+                    emit!(node.quasis[i / 2]);
+                }
             } else {
                 punct!("${");
                 emit!(node.exprs[i / 2]);
@@ -1056,6 +1188,21 @@ impl<'a> Emitter<'a> {
         punct!("`");
     }
 
+    fn is_preserved_template_tag(&self, tag: &Expr) -> bool {
+        match *tag {
+            Expr::Ident(ref i) => self.cfg.preserved_template_tags.contains(&i.sym),
+            Expr::Member(MemberExpr {
+                obj: ExprOrSuper::Expr(ref obj),
+                ..
+            }) => self.is_preserved_template_tag(obj),
+            Expr::Call(CallExpr {
+                callee: ExprOrSuper::Expr(ref callee),
+                ..
+            }) => self.is_preserved_template_tag(callee),
+            _ => false,
+        }
+    }
+
     #[emitter]
     fn emit_quasi(&mut self, node: &TplElement) -> Result {
         self.wr
@@ -1161,9 +1308,30 @@ impl<'a> Emitter<'a> {
             Some(&node.elems),
             ListFormat::ArrayLiteralExpressionElements,
         )?;
+        self.emit_trailing_hole(
+            node.span(),
+            &node.elems,
+            ListFormat::ArrayLiteralExpressionElements,
+        )?;
         punct!("]");
     }
 
+    /// `[a, ,]` requires a comma after the hole even if trailing commas are
+    /// disabled.
+    fn emit_trailing_hole<N: Node>(
+        &mut self,
+        parent_node: Span,
+        elems: &[Option<N>],
+        format: ListFormat,
+    ) -> Result {
+        match elems.last() {
+            Some(None) if !self.should_emit_trailing_comma(parent_node, elems, format) => {
+                self.wr.write_punct(",")
+            }
+            _ => Ok(()),
+        }
+    }
+
     #[emitter]
     fn emit_object_lit(&mut self, node: &ObjectLit) -> Result {
         self.emit_leading_comments_of_pos(node.span().lo())?;
@@ -1183,9 +1351,43 @@ impl<'a> Emitter<'a> {
         punct!("}");
     }
 
+    /// Returns `Some(true)` if properties should be collapsed into shorthand
+    /// and `Some(false)` if they should be expanded.
+    fn shorthand(&self) -> Option<bool> {
+        let shorthand = self
+            .cfg
+            .shorthand
+            .or(if self.cfg.minify { Some(true) } else { None });
+
+        match shorthand {
+            // Shorthand properties are es2015
+            Some(true) if self.cfg.target < JscTarget::Es2015 => None,
+            _ => shorthand,
+        }
+    }
+
+    /// Returns true if the source of `node` contains `word`, or if `node` is
+    /// not from the source.
+    fn may_contain(&self, node: &Function, word: &str) -> bool {
+        if node.span.is_dummy() {
+            return true;
+        }
+
+        match self.cm.span_to_snippet(node.span) {
+            Ok(src) => src.contains(word),
+            Err(_) => true,
+        }
+    }
+
     #[emitter]
     fn emit_prop(&mut self, node: &Prop) -> Result {
         match *node {
+            Prop::Shorthand(ref n) if self.shorthand() == Some(false) && !is_proto(n) => {
+                emit!(n);
+                punct!(":");
+                formatting_space!();
+                emit!(n);
+            }
             Prop::Shorthand(ref n) => emit!(n),
             Prop::KeyValue(ref n) => emit!(n),
             Prop::Assign(ref n) => emit!(n),
@@ -1199,6 +1401,18 @@ impl<'a> Emitter<'a> {
     fn emit_kv_prop(&mut self, node: &KeyValueProp) -> Result {
         self.emit_leading_comments_of_pos(node.span().lo())?;
 
+        // Functions are not turned into methods, as methods can't be
+        // constructed.
+        if self.shorthand() == Some(true) {
+            if let (&PropName::Ident(ref key), &Expr::Ident(ref value)) = (&node.key, &*node.value)
+            {
+                if key.sym == value.sym && !is_proto(key) {
+                    emit!(value);
+                    return Ok(());
+                }
+            }
+        }
+
         emit!(node.key);
         punct!(":");
         formatting_space!();
@@ -1248,19 +1462,48 @@ impl<'a> Emitter<'a> {
     fn emit_method_prop(&mut self, node: &MethodProp) -> Result {
         self.emit_leading_comments_of_pos(node.span().lo())?;
 
-        if node.function.is_async {
-            keyword!("async");
-            space!();
+        let is_proto_key = match node.key {
+            PropName::Ident(ref key) => is_proto(key),
+            PropName::Str(ref key) => key.value == *"__proto__",
+            _ => false,
+        };
+        if self.shorthand() == Some(false)
+            && !is_proto_key
+            && !self.may_contain(&node.function, "super")
+        {
+            emit!(node.key);
+            punct!(":");
+            formatting_space!();
+            if node.function.is_async {
+                keyword!("async");
+                space!();
+            }
+            keyword!("function");
+            if node.function.is_generator {
+                punct!("*");
+            }
+            self.emit_fn_trailing(&node.function)?;
+            return Ok(());
         }
 
-        if node.function.is_generator {
-            punct!("*");
+        self.emit_method(&node.key, &node.function)?;
+    }
+
+    /// prints `a(b){}` from `{ a(b){} }`
+    fn emit_method(&mut self, key: &PropName, function: &Function) -> Result {
+        if function.is_async {
+            keyword!(self, "async");
+            space!(self);
         }
 
-        emit!(node.key);
-        formatting_space!();
+        if function.is_generator {
+            punct!(self, "*");
+        }
+
+        emit!(self, key);
+        formatting_space!(self);
         // TODO
-        self.emit_fn_trailing(&node.function)?;
+        self.emit_fn_trailing(function)
     }
 
     #[emitter]
@@ -1448,6 +1691,15 @@ impl<'a> Emitter<'a> {
                             self.wr.write_line()?;
                         }
                         should_emit_intervening_comments = false;
+                    } else if self.should_wrap_before(child.span(), format) {
+                        if (format & (ListFormat::LinesMask | ListFormat::Indented))
+                            == ListFormat::SingleLine
+                        {
+                            self.wr.increase_indent()?;
+                            should_decrease_indent_after_emit = true;
+                        }
+
+                        self.wr.write_line()?;
                     } else if format.contains(ListFormat::SpaceBetweenSiblings) {
                         formatting_space!(self);
                     }
@@ -1472,22 +1724,12 @@ impl<'a> Emitter<'a> {
             }
 
             // Write a trailing comma, if requested.
-            let has_trailing_comma = format.contains(ListFormat::AllowTrailingComma) && {
-                match self.cm.span_to_snippet(parent_node) {
-                    Ok(snippet) => {
-                        if snippet.len() < 3 {
-                            false
-                        } else {
-                            snippet[..snippet.len() - 1].trim().ends_with(',')
-                        }
-                    }
-                    _ => false,
-                }
-            };
-
-            if has_trailing_comma && format.contains(ListFormat::CommaDelimited) {
+            if self.should_emit_trailing_comma(parent_node, children, format) {
                 self.wr.write_punct(",")?;
-                formatting_space!(self);
+                // A preserved trailing comma is followed by a space like other commas.
+                if self.cfg.trailing_comma.is_none() {
+                    formatting_space!(self);
+                }
             }
 
             {
@@ -1623,6 +1865,11 @@ impl<'a> Emitter<'a> {
             Some(&node.elems),
             ListFormat::ArrayBindingPatternElements,
         )?;
+        self.emit_trailing_hole(
+            node.span(),
+            &node.elems,
+            ListFormat::ArrayBindingPatternElements,
+        )?;
         punct!("]");
         if node.optional {
             punct!("?");
@@ -1710,10 +1957,70 @@ impl<'a> Emitter<'a> {
     }
 }
 
+/// Returns the shortest representation of a finite number, which is one of
+/// `0.5` without the leading zero, an exponential like `1e3` and a hexadecimal
+/// integer.
+///
+/// This is used while minifying and if `number_format` is
+/// [NumberFormat::Shortest]. It's also the fallback of
+/// [NumberFormat::Preserve] if the raw text of a number is not available.
+///
+/// Numeric separators are never emitted, so the result is valid for all
+/// targets.
+fn minify_number(value: f64) -> String {
+    if value.is_sign_negative() {
+        return format!("-{}", minify_number(-value));
+    }
+
+    let decimal = format!("{}", value);
+    let decimal = if decimal.starts_with("0.") {
+        decimal[1..].to_string()
+    } else {
+        decimal
+    };
+
+    let mut shortest = decimal;
+
+    // `1.2e6` is written as `12e5`.
+    let exp = format!("{:e}", value);
+    if let Some(idx) = exp.find('e') {
+        let digits = exp[..idx].replace('.', "");
+        let e = exp[idx + 1..].parse::<i32>().unwrap_or(0) - (digits.len() as i32 - 1);
+        let exp = format!("{}e{}", digits, e);
+        if exp.len() < shortest.len() {
+            shortest = exp;
+        }
+    }
+
+    // Integers above 2^53 may not be exact.
+    if value.fract() == 0.0 && value < 9007199254740992.0 {
+        let hex = format!("0x{:x}", value as u64);
+        if hex.len() < shortest.len() {
+            shortest = hex;
+        }
+    }
+
+    shortest
+}
+
+/// Parses a numeric literal without numeric separators.
+fn parse_number(s: &str) -> Option<f64> {
+    let radix = match s.get(..2) {
+        Some("0x") | Some("0X") => 16,
+        Some("0o") | Some("0O") => 8,
+        Some("0b") | Some("0B") => 2,
+        _ => return s.parse().ok(),
+    };
+
+    u64::from_str_radix(&s[2..], radix).ok().map(|v| v as f64)
+}
+
 /// Statements
 impl<'a> Emitter<'a> {
     #[emitter]
     fn emit_stmt(&mut self, node: &Stmt) -> Result {
+        self.handlers.on_before_emit_stmt(node);
+
         match *node {
             Stmt::Expr(ref e) => emit!(e),
             Stmt::Block(ref e) => {
@@ -1768,7 +2075,9 @@ impl<'a> Emitter<'a> {
     fn emit_empty_stmt(&mut self, node: &EmptyStmt) -> Result {
         self.emit_leading_comments_of_pos(node.span().lo())?;
 
-        punct!(";");
+        // Not `semi!()`, as the semicolon of an empty statement can't be
+        // omitted.
+        self.wr.write_punct(";")?;
     }
 
     #[emitter]
@@ -2063,6 +2372,89 @@ impl<'a> Emitter<'a> {
 }
 
 impl<'a> Emitter<'a> {
+    /// Returns true if a trailing comma should be written after `children`.
+    fn should_emit_trailing_comma<N: Node>(
+        &self,
+        parent_node: Span,
+        children: &[N],
+        format: ListFormat,
+    ) -> bool {
+        if children.is_empty() || !format.contains(ListFormat::CommaDelimited) {
+            return false;
+        }
+
+        let style = match self.cfg.trailing_comma {
+            Some(style) => style,
+            None => {
+                // Preserve the trailing comma of the original source.
+                return format.contains(ListFormat::AllowTrailingComma)
+                    && match self.cm.span_to_snippet(parent_node) {
+                        Ok(snippet) => {
+                            if snippet.len() < 3 {
+                                false
+                            } else {
+                                snippet[..snippet.len() - 1].trim().ends_with(',')
+                            }
+                        }
+                        _ => false,
+                    };
+            }
+        };
+        if self.cfg.minify {
+            return false;
+        }
+
+        // A trailing comma is not allowed after a rest element, so patterns are
+        // excluded. Parameters ending with a rest element use another format.
+        let allowed = if format == ListFormat::ArrayLiteralExpressionElements
+            || format == ListFormat::ObjectLiteralExpressionProperties
+            || format == ListFormat::NamedImportsOrExportsElements
+        {
+            true
+        } else if format == ListFormat::CallExpressionArguments
+            || format == ListFormat::NewExpressionArguments
+            || format == ListFormat::FunctionParameters
+        {
+            style != TrailingComma::Es5 && self.cfg.target >= JscTarget::Es2017
+        } else {
+            false
+        };
+
+        allowed
+            && match style {
+                TrailingComma::Never => false,
+                TrailingComma::Multiline => {
+                    self.cm
+                        .should_write_closing_line_terminator(parent_node, children, format)
+                }
+                TrailingComma::Es5 | TrailingComma::Always => true,
+            }
+    }
+
+    /// Returns true if a comma-separated list should be wrapped before `next`
+    /// to respect [Config::max_line_len].
+    ///
+    /// The width of `next` is estimated from its span, so synthesized nodes
+    /// are wrapped only if the current line is already too long.
+    fn should_wrap_before(&self, next: Span, format: ListFormat) -> bool {
+        if self.cfg.minify || format & ListFormat::DelimitersMask != ListFormat::CommaDelimited {
+            return false;
+        }
+
+        let max_line_len = match self.cfg.max_line_len {
+            Some(v) => v,
+            None => return false,
+        };
+        let width = if next.is_dummy() {
+            0
+        } else {
+            (next.hi() - next.lo()).0 as usize
+        };
+
+        // +1 for the space after the comma.
+        self.wr.current_col() + 1 + width > max_line_len
+    }
+
     fn write_delim(&mut self, f: ListFormat) -> Result {
         match f & ListFormat::DelimitersMask {
             ListFormat::None => {}
@@ -2094,6 +2486,22 @@ impl<'a> Emitter<'a> {
     }
 }
 
+/// `__proto__: value` sets the prototype, unlike the shorthand and method
+/// forms.
+fn is_proto(i: &Ident) -> bool {
+    i.sym == *"__proto__"
+}
+
+/// Returns the list format of parameters ending with `last`.
+///
+/// A trailing comma is not allowed after a rest element.
+fn params_format(last: Option<&Pat>) -> ListFormat {
+    match last {
+        Some(Pat::Rest(..)) => ListFormat::CommaListElements,
+        _ => ListFormat::FunctionParameters,
+    }
+}
+
 #[allow(dead_code)]
 fn get_text_of_node<T: Spanned>(
     cm: &Arc<SourceMap>,
@@ -78,6 +78,9 @@ add_bitflags!(
         /// If the literal is empty, do not add spaces between braces.
         NoSpaceIfEmpty: 1 << 18,
         SingleElement: 1 << 19,
+        /// The list is parameters of a function, which may have a trailing
+        /// comma.
+        Params: 1 << 20,
     },
     /// Precomputed Formats
     Values {
@@ -123,6 +126,8 @@ add_bitflags!(
             | Indented
             | SquareBrackets,
         CommaListElements: CommaDelimited | SpaceBetweenSiblings | SingleLine,
+        /// Parameters of a function which don't end with a rest element.
+        FunctionParameters: CommaDelimited | SpaceBetweenSiblings | SingleLine | Params,
         CallExpressionArguments: CommaDelimited | SpaceBetweenSiblings | SingleLine | Parenthesis,
         NewExpressionArguments: CommaDelimited
             | SpaceBetweenSiblings
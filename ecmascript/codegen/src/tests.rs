@@ -1,12 +1,12 @@
 use self::swc_ecma_parser::{Parser, Session, SourceFileInput, Syntax};
 use super::*;
-use crate::config::Config;
+use crate::config::{Config, NumberFormat, QuoteStyle, TrailingComma};
 use std::{
     fmt::{self, Debug, Display, Formatter},
     io::Write,
     sync::{Arc, RwLock},
 };
-use swc_common::{comments::Comments, FileName, SourceMap};
+use swc_common::{comments::Comments, BytePos, FileName, LineCol, Mark, SourceMap};
 use swc_ecma_parser;
 
 struct Noop;
@@ -23,10 +23,16 @@ impl Builder {
     where
         F: FnOnce(&mut Emitter<'_>) -> Ret,
     {
+        let wr = text_writer::JsWriter::new(self.cm.clone(), "\n", s, None);
+        let wr: Box<dyn WriteJs + '_> = if self.cfg.omit_last_semi {
+            Box::new(text_writer::omit_trailing_semi(wr))
+        } else {
+            Box::new(wr)
+        };
         let mut e = Emitter {
             cfg: self.cfg,
             cm: self.cm.clone(),
-            wr: Box::new(text_writer::JsWriter::new(self.cm.clone(), "\n", s, None)),
+            wr,
             comments: Some(&self.comments),
             handlers: Box::new(Noop),
         };
@@ -76,13 +82,25 @@ fn parse_then_emit(from: &str, cfg: Config) -> String {
 }
 
 pub(crate) fn assert_min(from: &str, to: &str) {
-    let out = parse_then_emit(from, Config { minify: true });
+    let out = parse_then_emit(
+        from,
+        Config {
+            minify: true,
+            ..Default::default()
+        },
+    );
 
     assert_eq!(DebugUsingDisplay(out.trim()), DebugUsingDisplay(to),);
 }
 
 pub(crate) fn assert_pretty(from: &str, to: &str) {
-    let out = parse_then_emit(from, Config { minify: false });
+    let out = parse_then_emit(
+        from,
+        Config {
+            minify: false,
+            ..Default::default()
+        },
+    );
 
     assert_eq!(DebugUsingDisplay(&out.trim()), DebugUsingDisplay(to),);
 }
@@ -203,6 +221,323 @@ fn issue_639() {
     test_from_to(r"`\x1b[33m Yellow \x1b[0m`;", r"`\x1b[33m Yellow \x1b[0m`;");
 }
 
+fn assert_quotes(quotes: QuoteStyle, minify: bool, from: &str, to: &str) {
+    let out = parse_then_emit(
+        from,
+        Config {
+            minify,
+            quotes,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(DebugUsingDisplay(out.trim()), DebugUsingDisplay(to),);
+}
+
+#[test]
+fn quotes_single() {
+    assert_quotes(
+        QuoteStyle::Single,
+        false,
+        r#""foo"; "it's";"#,
+        r#"'foo';
+'it\'s';"#,
+    );
+}
+
+#[test]
+fn quotes_double() {
+    assert_quotes(
+        QuoteStyle::Double,
+        false,
+        r#"'foo'; 'say "hi"';"#,
+        r#""foo";
+"say \"hi\"";"#,
+    );
+}
+
+#[test]
+fn quotes_preferred() {
+    assert_quotes(
+        QuoteStyle::Preferred,
+        false,
+        r#"'it\'s Bob\'s "car'; "foo";"#,
+        r#""it's Bob's \"car";
+'foo';"#,
+    );
+}
+
+#[test]
+fn quotes_minify_ignores_style() {
+    assert_quotes(
+        QuoteStyle::Single,
+        true,
+        r#"'it\'s'; "foo";"#,
+        r#""it's";'foo';"#,
+    );
+}
+
+fn assert_max_line_len(max_line_len: usize, minify: bool, from: &str, to: &str) {
+    let out = parse_then_emit(
+        from,
+        Config {
+            minify,
+            max_line_len: Some(max_line_len),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(DebugUsingDisplay(out.trim()), DebugUsingDisplay(to),);
+}
+
+#[test]
+fn max_line_len_call_args() {
+    assert_max_line_len(
+        20,
+        false,
+        "foo(aaaa, bbbb, cccc, dddd, eeee, ffff);",
+        "foo(aaaa, bbbb, cccc,
+    dddd, eeee, ffff);",
+    );
+}
+
+#[test]
+fn max_line_len_new_args() {
+    assert_max_line_len(
+        23,
+        false,
+        "new Foo(aaaa, bbbb, cccc, dddd);",
+        "new Foo(aaaa, bbbb,
+    cccc, dddd);",
+    );
+}
+
+#[test]
+fn max_line_len_not_exceeded() {
+    assert_max_line_len(80, false, "foo(a, b, c);", "foo(a, b, c);");
+}
+
+#[test]
+fn max_line_len_minify() {
+    assert_max_line_len(
+        10,
+        true,
+        "foo(aaaa, bbbb, cccc, dddd);",
+        "foo(aaaa,bbbb,cccc,dddd);",
+    );
+}
+
+#[test]
+fn max_line_len_object() {
+    assert_max_line_len(
+        20,
+        false,
+        "a = {aaaa: 1, bbbb: 2, cccc: 3};",
+        "a = {
+    aaaa: 1,
+    bbbb: 2,
+    cccc: 3
+};",
+    );
+}
+
+/// Elements of arrays created by transforms are emitted on a single line, so
+/// they are wrapped.
+#[test]
+fn max_line_len_array() {
+    let out = ::testing::run_test(false, |cm, handler| {
+        let src = cm.new_source_file(
+            FileName::Anon,
+            "[aaaa, bbbb, cccc, dddd, eeee, ffff];".into(),
+        );
+        let mut module = Parser::new(
+            Session { handler: &handler },
+            Syntax::default(),
+            SourceFileInput::from(&*src),
+            None,
+        )
+        .parse_module()
+        .map_err(|mut e| {
+            e.emit();
+        })?;
+
+        let mark = Mark::fresh(Mark::root());
+        match module.body[0] {
+            ModuleItem::Stmt(Stmt::Expr(ExprStmt { ref mut expr, .. })) => match **expr {
+                Expr::Array(ref mut array) => {
+                    for elem in array.elems.iter_mut().flatten() {
+                        match *elem.expr {
+                            Expr::Ident(ref mut i) => i.span = i.span.apply_mark(mark),
+                            _ => unreachable!(),
+                        }
+                    }
+                }
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        }
+
+        let cfg = Config {
+            max_line_len: Some(20),
+            ..Default::default()
+        };
+        Ok(Builder {
+            cfg,
+            cm,
+            comments: Default::default(),
+        }
+        .text("", |e| e.emit_module(&module).unwrap()))
+    })
+    .unwrap();
+
+    assert_eq!(
+        DebugUsingDisplay(out.trim()),
+        DebugUsingDisplay(
+            "[aaaa, bbbb, cccc,
+    dddd, eeee, ffff];"
+        ),
+    );
+}
+
+fn assert_trailing_comma(
+    trailing_comma: Option<TrailingComma>,
+    minify: bool,
+    from: &str,
+    to: &str,
+) {
+    let out = parse_then_emit(
+        from,
+        Config {
+            minify,
+            trailing_comma,
+            target: JscTarget::Es2017,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(DebugUsingDisplay(out.trim()), DebugUsingDisplay(to),);
+}
+
+#[test]
+fn trailing_comma_preserve() {
+    assert_trailing_comma(
+        None,
+        false,
+        "a = [1, 2,]; b = [1, 2];",
+        "a = [\n    1,\n    2, \n];\nb = [\n    1,\n    2\n];",
+    );
+}
+
+#[test]
+fn trailing_comma_never() {
+    assert_trailing_comma(
+        Some(TrailingComma::Never),
+        false,
+        "a = {b: 1, c: 2,}; foo(a, b,);",
+        "a = {
+    b: 1,
+    c: 2
+};
+foo(a, b);",
+    );
+}
+
+#[test]
+fn trailing_comma_multiline() {
+    assert_trailing_comma(
+        Some(TrailingComma::Multiline),
+        false,
+        "a = {b: 1, c: 2}; foo(a, b);",
+        "a = {
+    b: 1,
+    c: 2,
+};
+foo(a, b);",
+    );
+}
+
+#[test]
+fn trailing_comma_always() {
+    assert_trailing_comma(
+        Some(TrailingComma::Always),
+        false,
+        "a = {b: 1, c: 2}; foo(a, b); function bar(a, ...b) {}",
+        "a = {
+    b: 1,
+    c: 2,
+};
+foo(a, b,);
+function bar(a, ...b) {
+}",
+    );
+}
+
+#[test]
+fn trailing_comma_es5() {
+    assert_trailing_comma(
+        Some(TrailingComma::Es5),
+        false,
+        "a = [1, 2]; foo(a, b); function bar(a, b) {}",
+        "a = [
+    1,
+    2,
+];
+foo(a, b);
+function bar(a, b) {
+}",
+    );
+}
+
+#[test]
+fn trailing_comma_params() {
+    let out = parse_then_emit(
+        "function foo(a, b) {} const bar = (a) => a; function baz(a, ...b) {} class Foo { set \
+         x(v) {} }",
+        Config {
+            trailing_comma: Some(TrailingComma::Always),
+            target: JscTarget::Es2017,
+            ..Default::default()
+        },
+    );
+
+    assert!(out.contains("foo(a, b,)"), "{}", out);
+    assert!(out.contains("(a,)=>"), "{}", out);
+    assert!(out.contains("baz(a, ...b)"), "{}", out);
+    assert!(out.contains("x(v)"), "{}", out);
+}
+
+#[test]
+fn trailing_comma_minify() {
+    assert_trailing_comma(
+        Some(TrailingComma::Always),
+        true,
+        "a = {b: 1, c: 2}; foo(a, b);",
+        "a={b:1,c:2};foo(a,b);",
+    );
+}
+
+#[test]
+fn trailing_comma_target() {
+    let out = parse_then_emit(
+        "foo(a, b);",
+        Config {
+            trailing_comma: Some(TrailingComma::Always),
+            target: JscTarget::Es2016,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(
+        DebugUsingDisplay(out.trim()),
+        DebugUsingDisplay("foo(a, b);")
+    );
+}
+
+#[test]
+fn trailing_hole() {
+    assert_trailing_comma(Some(TrailingComma::Never), true, "a = [1, , ];", "a=[1,,];");
+}
+
 #[derive(Debug, Clone)]
 struct Buf(Arc<RwLock<Vec<u8>>>);
 impl Write for Buf {
@@ -223,3 +558,225 @@ impl<'a> Debug for DebugUsingDisplay<'a> {
         Display::fmt(self.0, f)
     }
 }
+
+/// Emitting without a source map should produce the same code, and mappings
+/// should be recorded only if a source map is requested.
+#[test]
+fn srcmap_disabled() {
+    let src = "function foo(a, b) {\n    return a + b;\n}\nconst c = foo(1, '2');\n".repeat(100);
+
+    ::testing::run_test(false, |cm, handler| {
+        let fm = cm.new_source_file(FileName::Real("custom.js".into()), src.clone());
+
+        let comments = Default::default();
+        let module = {
+            let mut parser = Parser::new(
+                Session { handler: &handler },
+                Syntax::default(),
+                SourceFileInput::from(&*fm),
+                Some(&comments),
+            );
+            parser.parse_module().map_err(|mut e| {
+                e.emit();
+            })?
+        };
+
+        let emit = |srcmap: Option<&mut Vec<(BytePos, LineCol)>>| {
+            let mut buf = vec![];
+            {
+                let mut e = Emitter {
+                    cfg: Default::default(),
+                    cm: cm.clone(),
+                    wr: Box::new(text_writer::JsWriter::new(
+                        cm.clone(),
+                        "\n",
+                        &mut buf,
+                        srcmap,
+                    )),
+                    comments: Some(&comments),
+                    handlers: Box::new(Noop),
+                };
+                e.emit_module(&module).unwrap();
+            }
+            String::from_utf8(buf).unwrap()
+        };
+
+        let mut mappings = vec![];
+        let with_map = emit(Some(&mut mappings));
+        let without_map = emit(None);
+
+        assert_eq!(with_map, without_map);
+        assert_ne!(mappings.len(), 0);
+
+        Ok(())
+    })
+    .unwrap()
+}
+
+#[test]
+fn preserved_template_tags() {
+    let out = parse_then_emit(
+        "css`\n  color: red;\\t\n`;\nstyled.div`\\n  a:  b;`;\nfoo`\\t`;",
+        Config {
+            minify: true,
+            preserved_template_tags: vec!["css".into(), "styled".into()],
+            ..Default::default()
+        },
+    );
+
+    assert!(out.contains("css`\n  color: red;\\t\n`"), "{}", out);
+    assert!(out.contains("styled.div`\\n  a:  b;`"), "{}", out);
+    assert!(!out.contains("foo`\\t`"), "{}", out);
+}
+
+#[test]
+fn number_format() {
+    let cfg = |number_format, target| Config {
+        number_format: Some(number_format),
+        target,
+        ..Default::default()
+    };
+
+    let out = parse_then_emit(
+        "x = [1000000, 1e3, 0xff, 1.5];",
+        cfg(NumberFormat::Shortest, JscTarget::EsNext),
+    );
+    assert_eq!(
+        DebugUsingDisplay(out.trim()),
+        DebugUsingDisplay(
+            "x = [
+    1e6,
+    1e3,
+    255,
+    1.5
+];"
+        )
+    );
+
+    let out = parse_then_emit(
+        "x = [1000000, 1e3, 0xff, 1.5];",
+        cfg(NumberFormat::Preserve, JscTarget::EsNext),
+    );
+    assert_eq!(
+        DebugUsingDisplay(out.trim()),
+        DebugUsingDisplay(
+            "x = [
+    1000000,
+    1e3,
+    0xff,
+    1.5
+];"
+        )
+    );
+
+    let out = parse_then_emit(
+        "x = 1000..toString();",
+        cfg(NumberFormat::Preserve, JscTarget::Es5),
+    );
+    assert_eq!(
+        DebugUsingDisplay(out.trim()),
+        DebugUsingDisplay("x = 1000..toString();")
+    );
+}
+
+/// Counts statements other than empty statements.
+fn count_stmts(src: &str) -> usize {
+    ::testing::run_test(false, |cm, handler| {
+        let src = cm.new_source_file(FileName::Real("concat.js".into()), src.to_string());
+        let mut parser = Parser::new(
+            Session { handler: &handler },
+            Syntax::default(),
+            SourceFileInput::from(&*src),
+            None,
+        );
+        let module = parser.parse_module().map_err(|mut e| {
+            e.emit();
+        })?;
+
+        Ok(module
+            .body
+            .iter()
+            .filter(|item| match item {
+                ModuleItem::Stmt(Stmt::Empty(..)) => false,
+                _ => true,
+            })
+            .count())
+    })
+    .unwrap()
+}
+
+#[test]
+fn omit_last_semi() {
+    for &omit_last_semi in &[false, true] {
+        let cfg = || Config {
+            minify: true,
+            omit_last_semi,
+            ..Default::default()
+        };
+
+        let first = parse_then_emit("a = b;", cfg());
+        assert_eq!(first.ends_with(';'), !omit_last_semi, "{}", first);
+        assert!(parse_then_emit("for (;;);", cfg()).ends_with(";"));
+
+        for src in &[
+            "(function () {})();",
+            "[1, 2].forEach(f);",
+            "`x`.length;",
+            "/re/.test(s);",
+            "-x;",
+            "c = d;",
+        ] {
+            let second = parse_then_emit(src, cfg());
+            let concatenated = format!("{}\n{}", first, second);
+
+            assert_eq!(count_stmts(&concatenated), 2, "{}", concatenated);
+        }
+    }
+}
+
+#[test]
+fn shorthand() {
+    let cfg = |shorthand| Config {
+        minify: true,
+        shorthand,
+        target: JscTarget::Es2015,
+        ..Default::default()
+    };
+    let src = "x = { a: a, b, [c]: c, d: function() {}, e() {}, async *f() {}, g: function() { \
+               this; }, h() { super.h; } };";
+
+    let out = parse_then_emit(src, cfg(Some(true)));
+    println!("{}", out);
+    assert!(out.contains("{a,b,[c]:c,d:function(){},e(){},async *f(){},g:function(){"));
+    assert!(out.contains("h(){"));
+
+    let out = parse_then_emit(src, cfg(Some(false)));
+    println!("{}", out);
+    assert!(out.contains(
+        "{a:a,b:b,[c]:c,d:function(){},e:function(){},f:async function*(){},g:function(){"
+    ));
+    assert!(out.contains("h(){"));
+
+    let out = parse_then_emit(src, cfg(None));
+    println!("{}", out);
+    assert!(out.contains("{a,b,[c]:c,d:function(){},e(){}"));
+
+    let src = "x = { __proto__: __proto__ }; y = { __proto__ }; z = { __proto__() {} };";
+    let out = parse_then_emit(src, cfg(Some(true)));
+    println!("{}", out);
+    assert!(out.contains("x={__proto__:__proto__}"));
+    let out = parse_then_emit(src, cfg(Some(false)));
+    println!("{}", out);
+    assert!(out.contains("y={__proto__}"));
+    assert!(out.contains("z={__proto__(){}}"));
+
+    let out = parse_then_emit(
+        src,
+        Config {
+            target: JscTarget::Es5,
+            ..cfg(Some(true))
+        },
+    );
+    println!("{}", out);
+    assert!(out.contains("{a:a,b,[c]:c,d:function(){},e(){}"));
+}
@@ -35,6 +35,9 @@ pub trait WriteJs {
     fn write_symbol(&mut self, span: Span, s: &str) -> Result;
 
     fn write_punct(&mut self, s: &'static str) -> Result;
+
+    /// Returns the column of the current line, in chars.
+    fn current_col(&self) -> usize;
 }
 
 impl<W> WriteJs for Box<W>
@@ -93,4 +96,8 @@ where
     fn write_punct(&mut self, s: &'static str) -> Result {
         (**self).write_punct(s)
     }
+
+    fn current_col(&self) -> usize {
+        (**self).current_col()
+    }
 }
@@ -45,7 +45,7 @@ impl<'a, W: Write> JsWriter<'a, W> {
     }
 
     fn write_indent_string(&mut self) -> io::Result<usize> {
-        const INDENT: &[u8] = b"    ";
+        const INDENT: &str = "    ";
 
         let mut cnt = 0;
         for _ in 0..self.indent {
@@ -55,33 +55,42 @@ impl<'a, W: Write> JsWriter<'a, W> {
         Ok(cnt)
     }
 
-    fn raw_write(&mut self, data: &[u8]) -> io::Result<usize> {
-        let written = self.wr.write(data)?;
-        self.written_bytes += written;
-        self.line_pos += written;
-        Ok(written)
+    fn raw_write(&mut self, data: &str) -> io::Result<usize> {
+        self.wr.write_all(data.as_bytes())?;
+        self.written_bytes += data.len();
+        // Columns of source maps are counted in chars, not bytes.
+        self.line_pos += if data.is_ascii() {
+            data.len()
+        } else {
+            data.chars().count()
+        };
+        Ok(data.len())
     }
 
     fn write(&mut self, span: Option<Span>, data: &str) -> io::Result<usize> {
         let mut cnt = 0;
 
         if !data.is_empty() {
-            if let Some(span) = span {
-                if !span.is_dummy() {
-                    self.srcmap(span.lo())
-                }
-            }
-
             if self.line_start {
                 cnt += self.write_indent_string()?;
                 self.line_start = false;
             }
-            cnt += self.raw_write(data.as_bytes())?;
+
+            // Spans are only used for source maps, so they are not even looked
+            // at if source maps are disabled.
+            let span = match self.srcmap {
+                Some(..) => span.filter(|span| !span.is_dummy()),
+                None => None,
+            };
 
             if let Some(span) = span {
-                if !span.is_dummy() {
-                    self.srcmap(span.hi())
-                }
+                self.srcmap(span.lo())
+            }
+
+            cnt += self.raw_write(data)?;
+
+            if let Some(span) = span {
+                self.srcmap(span.hi())
             }
         }
 
@@ -142,7 +151,7 @@ impl<'a, W: Write> WriteJs for JsWriter<'a, W> {
 
     fn write_line(&mut self) -> Result {
         if !self.line_start {
-            self.raw_write(self.new_line.as_bytes())?;
+            self.raw_write(self.new_line)?;
             self.line_count += 1;
             self.line_pos = 0;
             self.line_start = true;
@@ -190,6 +199,10 @@ impl<'a, W: Write> WriteJs for JsWriter<'a, W> {
         self.write(None, s)?;
         Ok(())
     }
+
+    fn current_col(&self) -> usize {
+        self.line_pos
+    }
 }
 
 fn compute_line_starts(s: &str) -> Vec<usize> {
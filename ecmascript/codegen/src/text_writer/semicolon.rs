@@ -1,10 +1,15 @@
 use super::{Result, WriteJs};
 use swc_common::Span;
 
+/// Omits the semicolon at the end of the output.
+///
+/// A semicolon is written only when something other than a line break follows
+/// it, so line breaks after the last semicolon are omitted too.
 pub fn omit_trailing_semi<W: WriteJs>(w: W) -> impl WriteJs {
     OmitTrailingSemi {
         inner: w,
         pending_semi: false,
+        pending_lines: 0,
     }
 }
 
@@ -12,6 +17,8 @@ pub fn omit_trailing_semi<W: WriteJs>(w: W) -> impl WriteJs {
 struct OmitTrailingSemi<W: WriteJs> {
     inner: W,
     pending_semi: bool,
+    /// Line breaks written after the pending semicolon.
+    pending_lines: usize,
 }
 
 macro_rules! with_semi {
@@ -38,6 +45,7 @@ impl<W: WriteJs> WriteJs for OmitTrailingSemi<W> {
     with_semi!(decrease_indent());
 
     fn write_semi(&mut self) -> Result {
+        self.commit_pending_semi()?;
         self.pending_semi = true;
         Ok(())
     }
@@ -48,12 +56,31 @@ impl<W: WriteJs> WriteJs for OmitTrailingSemi<W> {
     with_semi!(write_operator(s: &str));
     with_semi!(write_param(s: &str));
     with_semi!(write_property(s: &str));
-    with_semi!(write_line());
+
+    fn write_line(&mut self) -> Result {
+        if self.pending_semi {
+            self.pending_lines += 1;
+            return Ok(());
+        }
+
+        self.inner.write_line()
+    }
+
     with_semi!(write_lit(span: Span, s: &str));
     with_semi!(write_str_lit(span: Span, s: &str));
     with_semi!(write_str(s: &str));
     with_semi!(write_symbol(span: Span, s: &str));
     with_semi!(write_punct(s: &'static str));
+
+    fn current_col(&self) -> usize {
+        if self.pending_lines != 0 {
+            0
+        } else if self.pending_semi {
+            self.inner.current_col() + 1
+        } else {
+            self.inner.current_col()
+        }
+    }
 }
 
 impl<W: WriteJs> OmitTrailingSemi<W> {
@@ -61,6 +88,11 @@ impl<W: WriteJs> OmitTrailingSemi<W> {
         if self.pending_semi {
             self.inner.write_punct(";")?;
             self.pending_semi = false;
+
+            for _ in 0..self.pending_lines {
+                self.inner.write_line()?;
+            }
+            self.pending_lines = 0;
         }
         Ok(())
     }
@@ -163,6 +163,7 @@ pub enum SyntaxError {
 
     ExportDefaultWithOutFrom,
     ExportNamespaceFrom,
+    InvalidImportAssertion,
 
     DotsWithoutIdentifier,
 
@@ -362,6 +363,9 @@ impl<'a> From<ErrorToDiag<'a>> for DiagnosticBuilder<'a> {
             ExportNamespaceFrom => "export * as Foo from 'foo'; requires \
                                     `jsc.parser.exportNamespaceFrom` to be true"
                 .into(),
+            InvalidImportAssertion => "An import assertion should be a key-value pair with a \
+                                       string literal as the value"
+                .into(),
 
             DotsWithoutIdentifier => {
                 "`...` must be followed by an identifier in declaration contexts".into()
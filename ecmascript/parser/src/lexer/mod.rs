@@ -33,6 +33,49 @@ pub mod util;
 
 pub(crate) type LexResult<T> = Result<T, Error>;
 
+/// Comments collected by the lexer, if [Comments] is given.
+///
+/// Comments at the end of a file are trailing comments of the last token.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentCollection {
+    None,
+    Leading,
+    Trailing,
+    All,
+}
+
+impl CommentCollection {
+    pub fn leading(self) -> bool {
+        match self {
+            CommentCollection::Leading | CommentCollection::All => true,
+            _ => false,
+        }
+    }
+
+    pub fn trailing(self) -> bool {
+        match self {
+            CommentCollection::Trailing | CommentCollection::All => true,
+            _ => false,
+        }
+    }
+}
+
+impl Default for CommentCollection {
+    fn default() -> Self {
+        CommentCollection::All
+    }
+}
+
+impl From<bool> for CommentCollection {
+    fn from(collect: bool) -> Self {
+        if collect {
+            CommentCollection::All
+        } else {
+            CommentCollection::None
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub(crate) struct Char(u32);
 
@@ -97,6 +140,7 @@ impl FusedIterator for CharIter {}
 pub struct Lexer<'a, I: Input> {
     session: Session<'a>,
     comments: Option<&'a Comments>,
+    comment_collection: CommentCollection,
     leading_comments_buffer: Option<Vec<Comment>>,
     pub(crate) ctx: Context,
     input: I,
@@ -125,6 +169,7 @@ impl<'a, I: Input> Lexer<'a, I> {
                 None
             },
             comments,
+            comment_collection: Default::default(),
             input,
             state: State::new(syntax),
             ctx: Default::default(),
@@ -134,6 +179,13 @@ impl<'a, I: Input> Lexer<'a, I> {
         }
     }
 
+    /// Collects only comments selected by `collection`. All comments are
+    /// collected by default.
+    pub fn comment_collection(mut self, collection: CommentCollection) -> Self {
+        self.comment_collection = collection;
+        self
+    }
+
     /// Utility method to reuse buffer.
     fn with_buf<F, Ret>(&mut self, op: F) -> LexResult<Ret>
     where
@@ -156,11 +156,12 @@ impl<'a, I: Input> Iterator for Lexer<'a, I> {
             let c = match self.input.cur() {
                 Some(c) => c,
                 None => {
-                    if self
-                        .leading_comments_buffer
-                        .as_ref()
-                        .map(|v| !v.is_empty())
-                        .unwrap_or(false)
+                    if self.comment_collection.trailing()
+                        && self
+                            .leading_comments_buffer
+                            .as_ref()
+                            .map(|v| !v.is_empty())
+                            .unwrap_or(false)
                     {
                         let last = self.state.prev_hi;
                         for c in self.leading_comments_buffer.as_mut().unwrap().drain(..) {
@@ -244,10 +245,14 @@ impl<'a, I: Input> Iterator for Lexer<'a, I> {
             if self.leading_comments_buffer.is_some()
                 && !self.leading_comments_buffer.as_ref().unwrap().is_empty()
             {
-                self.comments.as_ref().unwrap().add_leading(
-                    start,
-                    mem::replace(&mut self.leading_comments_buffer.as_mut().unwrap(), vec![]),
-                );
+                if self.comment_collection.leading() {
+                    self.comments.as_ref().unwrap().add_leading(
+                        start,
+                        mem::replace(&mut self.leading_comments_buffer.as_mut().unwrap(), vec![]),
+                    );
+                } else {
+                    self.leading_comments_buffer.as_mut().unwrap().clear();
+                }
             }
             self.state.update(start, &token);
             self.state.prev_hi = self.last_pos();
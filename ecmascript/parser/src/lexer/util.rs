@@ -191,6 +191,10 @@ impl<'a, I: Input> Lexer<'a, I> {
         }
 
         if let Some(ref comments) = self.comments {
+            if !is_for_next && !self.comment_collection.trailing() {
+                return;
+            }
+
             let s = self.input.slice(slice_start, end);
             let cmt = Comment {
                 kind: CommentKind::Line,
@@ -233,6 +237,10 @@ impl<'a, I: Input> Lexer<'a, I> {
 
                 let pos = self.cur_pos();
                 if let Some(ref comments) = self.comments {
+                    if !is_for_next && !self.comment_collection.trailing() {
+                        return Ok(());
+                    }
+
                     let src = self.input.slice(slice_start, pos);
                     let s = &src[..src.len() - 2];
                     let cmt = Comment {
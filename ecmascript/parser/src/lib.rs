@@ -284,6 +284,18 @@ impl Syntax {
         }
     }
 
+    pub fn import_assertions(self) -> bool {
+        match self {
+            Syntax::Es(EsConfig {
+                import_assertions: true,
+                ..
+            })
+            | Syntax::Typescript(..) => true,
+
+            _ => false,
+        }
+    }
+
     pub fn top_level_await(self) -> bool {
         match self {
             Syntax::Es(EsConfig {
@@ -337,6 +349,10 @@ pub enum JscTarget {
     Es2018,
     #[serde(rename = "es2019")]
     Es2019,
+    /// The latest version of ECMAScript, including proposals enabled by
+    /// [Syntax]. Nothing is downleveled.
+    #[serde(rename = "esnext")]
+    EsNext,
 }
 
 impl Default for JscTarget {
@@ -407,6 +423,10 @@ pub struct EsConfig {
     /// Stage 3.
     #[serde(default)]
     pub top_level_await: bool,
+
+    /// Stage 3. `import json from './foo.json' assert { type: 'json' }`
+    #[serde(default)]
+    pub import_assertions: bool,
 }
 
 /// Syntactic context.
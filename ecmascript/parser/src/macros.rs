@@ -122,6 +122,9 @@ macro_rules! tok {
     ("as") => {
         crate::token::Token::Word(crate::token::Word::Ident(swc_atoms::js_word!("as")))
     };
+    ("assert") => {
+        crate::token::Token::Word(crate::token::Word::Ident(swc_atoms::js_word!("assert")))
+    };
     ("await") => {
         crate::token::Token::Word(crate::token::Word::Keyword(crate::token::Keyword::Await))
     };
@@ -50,12 +50,14 @@ impl<'a, I: Tokens> Parser<'a, I> {
                 },
                 _ => unreachable!(),
             };
+            let asserts = self.parse_import_asserts()?;
             expect!(';');
             return Ok(ModuleDecl::Import(ImportDecl {
                 span: span!(start),
                 src,
                 specifiers: vec![],
                 type_only: false,
+                asserts,
             }))
             .map(ModuleItem::from);
         }
@@ -100,13 +102,16 @@ impl<'a, I: Tokens> Parser<'a, I> {
             }
         }
 
-        let src = self.parse_from_clause_and_semi()?;
+        let src = self.parse_from_clause()?;
+        let asserts = self.parse_import_asserts()?;
+        expect!(';');
 
         Ok(ModuleDecl::Import(ImportDecl {
             span: span!(start),
             specifiers,
             src,
             type_only,
+            asserts,
         }))
         .map(ModuleItem::from)
     }
@@ -488,6 +493,12 @@ impl<'a, I: Tokens> Parser<'a, I> {
     }
 
     fn parse_from_clause_and_semi(&mut self) -> PResult<'a, Str> {
+        let src = self.parse_from_clause()?;
+        expect!(';');
+        Ok(src)
+    }
+
+    fn parse_from_clause(&mut self) -> PResult<'a, Str> {
         expect!("from");
 
         let str_start = cur_pos!();
@@ -502,9 +513,57 @@ impl<'a, I: Tokens> Parser<'a, I> {
             },
             _ => unexpected!(),
         };
-        expect!(';');
         Ok(src)
     }
+
+    /// Parses `assert { type: 'json' }` after the module specifier of an
+    /// import declaration.
+    ///
+    /// Keys should be identifier names or string literals, and values should
+    /// be string literals.
+    fn parse_import_asserts(&mut self) -> PResult<'a, Option<ObjectLit>> {
+        if !self.input.syntax().import_assertions()
+            || !is!("assert")
+            || self.input.had_line_break_before_cur()
+        {
+            return Ok(None);
+        }
+        bump!();
+
+        if !is!('{') {
+            unexpected!()
+        }
+        let obj: Box<Expr> = self.parse_object()?;
+        let obj = match *obj {
+            Expr::Object(obj) => obj,
+            _ => unreachable!("parse_object should return an object literal"),
+        };
+
+        for prop in &obj.props {
+            let valid = match *prop {
+                PropOrSpread::Prop(ref prop) => match **prop {
+                    Prop::KeyValue(KeyValueProp {
+                        key: PropName::Ident(..),
+                        value: ref v,
+                    })
+                    | Prop::KeyValue(KeyValueProp {
+                        key: PropName::Str(..),
+                        value: ref v,
+                    }) => match **v {
+                        Expr::Lit(Lit::Str(..)) => true,
+                        _ => false,
+                    },
+                    _ => false,
+                },
+                _ => false,
+            };
+            if !valid {
+                syntax_error!(prop.span(), SyntaxError::InvalidImportAssertion)
+            }
+        }
+
+        Ok(Some(obj))
+    }
 }
 
 impl IsDirective for ModuleItem {
@@ -564,4 +623,38 @@ export default class Foo {
             |p| p.parse_module().map_err(|mut e| e.emit()),
         );
     }
+
+    fn import_assertions(src: &'static str) {
+        crate::test_parser(
+            src,
+            Syntax::Es(EsConfig {
+                import_assertions: true,
+                ..Default::default()
+            }),
+            |p| p.parse_module().map_err(|mut e| e.emit()),
+        );
+    }
+
+    #[test]
+    fn test_import_assertions() {
+        import_assertions("import a from 'a.json' assert { type: 'json', 'other': 'b' };");
+    }
+
+    #[test]
+    #[should_panic(expected = "An import assertion should be a key-value pair")]
+    fn test_import_assertions_non_string_value() {
+        import_assertions("import a from 'a.json' assert { type: json };");
+    }
+
+    #[test]
+    #[should_panic(expected = "An import assertion should be a key-value pair")]
+    fn test_import_assertions_computed_key() {
+        import_assertions("import a from 'a.json' assert { ['type']: 'json' };");
+    }
+
+    #[test]
+    #[should_panic(expected = "An import assertion should be a key-value pair")]
+    fn test_import_assertions_shorthand() {
+        import_assertions("import a from 'a.json' assert { type };");
+    }
 }
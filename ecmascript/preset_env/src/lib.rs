@@ -290,6 +290,7 @@ impl Fold<Module> for Polyfills {
                             has_escape: false,
                         },
                         type_only: false,
+                        asserts: None,
                     }))
                 }),
             );
@@ -306,6 +307,7 @@ impl Fold<Module> for Polyfills {
                             has_escape: false,
                         },
                         type_only: false,
+                        asserts: None,
                     }))
                 }),
             );
@@ -205,7 +205,10 @@ fn exec(c: PresetConfig, dir: PathBuf) -> Result<(), Error> {
                 {
                     let handlers = box MyHandlers;
                     let mut emitter = Emitter {
-                        cfg: swc_ecma_codegen::Config { minify: false },
+                        cfg: swc_ecma_codegen::Config {
+                            minify: false,
+                            ..Default::default()
+                        },
                         comments: None,
                         cm: cm.clone(),
                         wr: box swc_ecma_codegen::text_writer::JsWriter::new(
@@ -10,15 +10,27 @@ use swc_common::{
 use swc_ecma_ast::*;
 
 pub fn fixer() -> impl Pass {
+    fixer_with_config(Default::default())
+}
+
+pub fn fixer_with_config(config: FixerConfig) -> impl Pass {
     Fixer {
         ctx: Default::default(),
         span_map: Default::default(),
+        preserve_parens: config.preserve_parens,
     }
 }
 
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FixerConfig {
+    /// Keep parens already in the ast, even if they are not required.
+    pub preserve_parens: bool,
+}
+
 #[derive(Debug)]
 struct Fixer {
     ctx: Context,
+    preserve_parens: bool,
     /// A hash map to preserve original span.
     ///
     /// Key is span of inner expression, and value is span of the paren
@@ -319,7 +331,18 @@ impl Fixer {
 
 impl Fold<Expr> for Fixer {
     fn fold(&mut self, expr: Expr) -> Expr {
-        let expr = validate!(expr);
+        let expr = match expr {
+            Expr::Paren(paren) if self.preserve_parens => {
+                // Parens make the inner expression independent of the context.
+                let old = self.ctx;
+                self.ctx = Context::Default;
+                let paren = paren.fold_children(self);
+                self.ctx = old;
+
+                return Expr::Paren(paren);
+            }
+            _ => validate!(expr),
+        };
         let expr = expr.fold_children(self);
         let expr = validate!(expr);
         let expr = self.unwrap_expr(expr);
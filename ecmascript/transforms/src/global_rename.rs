@@ -0,0 +1,281 @@
+use crate::pass::Pass;
+use std::collections::{HashMap, HashSet};
+use swc_atoms::JsWord;
+use swc_common::{Fold, FoldWith, Visit, VisitWith};
+use swc_ecma_ast::*;
+use swc_ecma_utils::{find_ids, ident::IdentLike, Id};
+
+/// Renames references to global variables.
+///
+/// `renames` maps the name of a global to its new name, e.g. `Promise` to
+/// `SomePolyfill`. Only references which are not bound anywhere in the file
+/// are renamed, so local bindings with the same name and references to them
+/// are left untouched.
+///
+/// This pass depends on [resolver](crate::resolver), as references to
+/// globals share the syntax context of top-level bindings.
+pub fn global_rename(renames: HashMap<JsWord, JsWord>) -> impl Pass {
+    GlobalRename {
+        renames,
+        bindings: Default::default(),
+    }
+}
+
+struct GlobalRename {
+    renames: HashMap<JsWord, JsWord>,
+    /// Bindings declared in the file.
+    bindings: HashSet<Id>,
+}
+
+noop_fold_type!(GlobalRename);
+
+impl GlobalRename {
+    fn fold_root<T>(&mut self, node: T) -> T
+    where
+        T: for<'a> FoldWith<IdentRenamer<'a>> + VisitWith<BindingCollector>,
+    {
+        if self.renames.is_empty() {
+            return node;
+        }
+
+        let mut v = BindingCollector::default();
+        node.visit_with(&mut v);
+        self.bindings = v.bindings;
+
+        let (renames, bindings) = (&self.renames, &self.bindings);
+        node.fold_children(&mut IdentRenamer {
+            lookup: &|i| {
+                if bindings.contains(&i.to_id()) {
+                    return None;
+                }
+                renames.get(&i.sym).cloned()
+            },
+        })
+    }
+}
+
+impl Fold<Module> for GlobalRename {
+    fn fold(&mut self, node: Module) -> Module {
+        self.fold_root(node)
+    }
+}
+
+impl Fold<Script> for GlobalRename {
+    fn fold(&mut self, node: Script) -> Script {
+        self.fold_root(node)
+    }
+}
+
+/// Renames identifiers to the name returned by `lookup`.
+///
+/// Property names are not renamed, and shorthand properties and patterns are
+/// expanded if renamed, e.g. `{ foo }` becomes `{ foo: bar }`.
+pub(crate) struct IdentRenamer<'a> {
+    pub lookup: &'a dyn Fn(&Ident) -> Option<JsWord>,
+}
+
+noop_fold_type!(IdentRenamer<'_>);
+
+impl IdentRenamer<'_> {
+    fn rename(&self, i: Ident) -> Ident {
+        match (self.lookup)(&i) {
+            Some(sym) => Ident { sym, ..i },
+            None => i,
+        }
+    }
+}
+
+impl Fold<Ident> for IdentRenamer<'_> {
+    fn fold(&mut self, i: Ident) -> Ident {
+        self.rename(i)
+    }
+}
+
+impl Fold<Expr> for IdentRenamer<'_> {
+    fn fold(&mut self, e: Expr) -> Expr {
+        match e {
+            Expr::Member(e) if !e.computed => Expr::Member(MemberExpr {
+                obj: e.obj.fold_with(self),
+                ..e
+            }),
+            _ => e.fold_children(self),
+        }
+    }
+}
+
+impl Fold<PropName> for IdentRenamer<'_> {
+    fn fold(&mut self, n: PropName) -> PropName {
+        match n {
+            PropName::Computed(e) => PropName::Computed(e.fold_with(self)),
+            _ => n,
+        }
+    }
+}
+
+/// Imports only declare bindings, and renaming them would change the imported
+/// names.
+impl Fold<ImportDecl> for IdentRenamer<'_> {
+    fn fold(&mut self, node: ImportDecl) -> ImportDecl {
+        node
+    }
+}
+
+/// Exported names are not references.
+impl Fold<NamedExport> for IdentRenamer<'_> {
+    fn fold(&mut self, node: NamedExport) -> NamedExport {
+        node
+    }
+}
+
+impl Fold<Prop> for IdentRenamer<'_> {
+    fn fold(&mut self, p: Prop) -> Prop {
+        match p {
+            Prop::Shorthand(i) => {
+                let value = self.rename(i.clone());
+                if value.sym == i.sym {
+                    return Prop::Shorthand(i);
+                }
+
+                Prop::KeyValue(KeyValueProp {
+                    key: PropName::Ident(i),
+                    value: box Expr::Ident(value),
+                })
+            }
+            _ => p.fold_children(self),
+        }
+    }
+}
+
+impl Fold<ObjectPatProp> for IdentRenamer<'_> {
+    fn fold(&mut self, p: ObjectPatProp) -> ObjectPatProp {
+        match p {
+            ObjectPatProp::Assign(AssignPatProp { span, key, value }) => {
+                let value = value.fold_with(self);
+                let left = self.rename(key.clone());
+                if left.sym == key.sym {
+                    return ObjectPatProp::Assign(AssignPatProp { span, key, value });
+                }
+
+                ObjectPatProp::KeyValue(KeyValuePatProp {
+                    key: PropName::Ident(key),
+                    value: match value {
+                        Some(right) => box Pat::Assign(AssignPat {
+                            span,
+                            left: box Pat::Ident(left),
+                            right,
+                            type_ann: None,
+                        }),
+                        None => box Pat::Ident(left),
+                    },
+                })
+            }
+            _ => p.fold_children(self),
+        }
+    }
+}
+
+/// Collects bindings declared anywhere in a file.
+#[derive(Default)]
+pub(crate) struct BindingCollector {
+    pub bindings: HashSet<Id>,
+}
+
+noop_visit_type!(BindingCollector);
+
+impl BindingCollector {
+    fn add_pat(&mut self, pat: &Pat) {
+        let ids: Vec<Id> = find_ids(pat);
+        self.bindings.extend(ids);
+    }
+}
+
+impl Visit<VarDeclarator> for BindingCollector {
+    fn visit(&mut self, node: &VarDeclarator) {
+        self.add_pat(&node.name);
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<Param> for BindingCollector {
+    fn visit(&mut self, node: &Param) {
+        self.add_pat(&node.pat);
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<SetterProp> for BindingCollector {
+    fn visit(&mut self, node: &SetterProp) {
+        self.add_pat(&node.param);
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<ArrowExpr> for BindingCollector {
+    fn visit(&mut self, node: &ArrowExpr) {
+        for p in &node.params {
+            self.add_pat(p);
+        }
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<CatchClause> for BindingCollector {
+    fn visit(&mut self, node: &CatchClause) {
+        if let Some(ref p) = node.param {
+            self.add_pat(p);
+        }
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<FnDecl> for BindingCollector {
+    fn visit(&mut self, node: &FnDecl) {
+        self.bindings.insert(node.ident.to_id());
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<FnExpr> for BindingCollector {
+    fn visit(&mut self, node: &FnExpr) {
+        if let Some(ref i) = node.ident {
+            self.bindings.insert(i.to_id());
+        }
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<ClassDecl> for BindingCollector {
+    fn visit(&mut self, node: &ClassDecl) {
+        self.bindings.insert(node.ident.to_id());
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<ClassExpr> for BindingCollector {
+    fn visit(&mut self, node: &ClassExpr) {
+        if let Some(ref i) = node.ident {
+            self.bindings.insert(i.to_id());
+        }
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<ImportSpecifier> for BindingCollector {
+    fn visit(&mut self, node: &ImportSpecifier) {
+        let local = match *node {
+            ImportSpecifier::Named(ref s) => &s.local,
+            ImportSpecifier::Default(ref s) => &s.local,
+            ImportSpecifier::Namespace(ref s) => &s.local,
+        };
+        self.bindings.insert(local.to_id());
+    }
+}
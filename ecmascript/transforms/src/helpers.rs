@@ -1,3 +1,4 @@
+use crate::pass::Pass;
 use once_cell::sync::Lazy;
 use scoped_tls::scoped_thread_local;
 use std::sync::atomic::{AtomicBool, Ordering};
@@ -6,7 +7,7 @@ use swc_ecma_ast::*;
 use swc_ecma_parser::{lexer::Lexer, Parser, SourceFileInput};
 use swc_ecma_utils::{
     options::{CM, SESSION},
-    prepend_stmts, quote_ident, quote_str, DropSpan,
+    prepend_stmts, quote_ident, quote_str, DropSpan, ExprFactory,
 };
 
 #[macro_export]
@@ -61,6 +62,7 @@ scoped_thread_local!(pub static HELPERS: Helpers);
 #[derive(Default)]
 pub struct Helpers {
     external: bool,
+    skip_injection: bool,
     mark: HelperMark,
     inner: Inner,
 }
@@ -69,6 +71,20 @@ impl Helpers {
     pub fn new(external: bool) -> Self {
         Helpers {
             external,
+            skip_injection: false,
+            mark: Default::default(),
+            inner: Default::default(),
+        }
+    }
+
+    /// Creates helpers which are referenced as globals like `_classCallCheck`
+    /// and never injected, for bundlers which provide helpers themselves.
+    ///
+    /// [Helpers::used] still returns used helpers.
+    pub fn global() -> Self {
+        Helpers {
+            external: false,
+            skip_injection: true,
             mark: Default::default(),
             inner: Default::default(),
         }
@@ -101,6 +117,24 @@ macro_rules! define_helpers {
         }
 
         impl Helpers {
+            /// Returns names of helpers used so far, including helpers
+            /// required by other helpers.
+            ///
+            /// Names are snake-cased and without a leading underscore (e.g.
+            /// `class_call_check` or `type_of`). The helper named `foo` is
+            /// defined in `helpers/_foo.js`.
+            pub fn used(&self) -> Vec<&'static str> {
+                let mut buf = vec![];
+
+                $(
+                    if self.inner.$name.load(Ordering::Relaxed) {
+                        buf.push(stringify!($name));
+                    }
+                )*
+
+                buf
+            }
+
             $(
                 pub fn $name(&self) {
                     self.inner.$name.store(true, Ordering::Relaxed);
@@ -228,38 +262,93 @@ pub struct InjectHelpers;
 noop_fold_type!(InjectHelpers);
 
 impl InjectHelpers {
-    fn mk_helpers(&self) -> Vec<ModuleItem> {
-        let (mark, external) = HELPERS.with(|helper| (helper.mark(), helper.external()));
+    fn mk_helpers(&self, c: InjectConfig) -> Vec<ModuleItem> {
+        let (mark, external, skip_injection) = HELPERS.with(|helper| {
+            (helper.mark(), helper.external(), helper.skip_injection)
+        });
+        if skip_injection {
+            return vec![];
+        }
         if external {
-            if self.is_helper_used() {
+            if !self.is_helper_used() {
+                return vec![];
+            }
+
+            let local = quote_ident!(DUMMY_SP.apply_mark(mark), "swcHelpers");
+            if c.require {
+                vec![ModuleItem::Stmt(Stmt::Decl(Decl::Var(VarDecl {
+                    span: DUMMY_SP,
+                    kind: VarDeclKind::Var,
+                    declare: false,
+                    decls: vec![VarDeclarator {
+                        span: DUMMY_SP,
+                        name: Pat::Ident(local),
+                        init: Some(box Expr::Call(CallExpr {
+                            span: DUMMY_SP,
+                            callee: quote_ident!("require").as_callee(),
+                            args: vec![Lit::Str(quote_str!("@swc/helpers")).as_arg()],
+                            type_args: None,
+                        })),
+                        definite: false,
+                    }],
+                })))]
+            } else {
                 vec![ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
                     span: DUMMY_SP,
                     specifiers: vec![ImportSpecifier::Namespace(ImportStarAsSpecifier {
                         span: DUMMY_SP,
-                        local: quote_ident!(DUMMY_SP.apply_mark(mark), "swcHelpers"),
+                        local,
                     })],
                     src: quote_str!("@swc/helpers"),
                     type_only: false,
+                    asserts: None,
                 }))]
-            } else {
-                vec![]
             }
         } else {
             self.build_helpers()
         }
     }
-}
 
-impl Fold<Module> for InjectHelpers {
-    fn fold(&mut self, module: Module) -> Module {
+    fn inject(&self, module: Module, c: InjectConfig) -> Module {
         let mut module = validate!(module);
-        let helpers = self.mk_helpers();
+        let helpers = self.mk_helpers(c);
 
         prepend_stmts(&mut module.body, helpers.into_iter());
         module
     }
 }
 
+impl Fold<Module> for InjectHelpers {
+    fn fold(&mut self, module: Module) -> Module {
+        self.inject(module, Default::default())
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct InjectConfig {
+    /// Imports external helpers using `require('@swc/helpers')` instead of
+    /// an import declaration. This should be set if the module is transformed
+    /// into a CommonJS module.
+    pub require: bool,
+}
+
+/// [InjectHelpers] with a config.
+pub fn inject_helpers_with_config(c: InjectConfig) -> impl Pass {
+    InjectHelpersWithConfig { c }
+}
+
+struct InjectHelpersWithConfig {
+    c: InjectConfig,
+}
+
+noop_fold_type!(InjectHelpersWithConfig);
+
+impl Fold<Module> for InjectHelpersWithConfig {
+    fn fold(&mut self, module: Module) -> Module {
+        InjectHelpers.inject(module, self.c)
+    }
+}
+
 struct Marker(Mark);
 
 noop_fold_type!(Marker);
@@ -13,7 +13,8 @@ extern crate swc_ecma_utils;
 
 pub use self::{
     const_modules::const_modules,
-    fixer::fixer,
+    fixer::{fixer, fixer_with_config, FixerConfig},
+    global_rename::global_rename,
     hygiene::hygiene,
     resolver::{resolver, resolver_with_mark},
 };
@@ -33,6 +34,7 @@ pub mod compat;
 mod const_modules;
 pub mod debug;
 mod fixer;
+mod global_rename;
 pub mod modules;
 pub mod optimization;
 pub mod pass;
@@ -1,6 +1,9 @@
 #[macro_use]
 pub mod util;
 pub mod amd;
+pub mod cjs_to_esm;
 pub mod common_js;
 pub mod import_analysis;
+pub mod import_meta;
+pub mod resolve_import;
 pub mod umd;
@@ -0,0 +1,515 @@
+use crate::pass::Pass;
+use fxhash::FxHashSet;
+use swc_atoms::JsWord;
+use swc_common::{Fold, Span, Spanned, Visit, VisitWith, DUMMY_SP};
+use swc_ecma_ast::*;
+use swc_ecma_utils::find_ids;
+
+/// Converts a CommonJS module to an es module.
+///
+/// Only top-level statements of these forms are recognized.
+///
+///  - `require('foo');` becomes `import 'foo';`
+///  - `const foo = require('foo');` becomes `import foo from 'foo';`
+///  - `const { a, b: c } = require('foo');` becomes `import { a, b as c } from
+///    'foo';`
+///  - `module.exports = expr;` and `exports.default = expr;` become `export
+///    default expr;`
+///  - `exports.foo = expr;` and `module.exports.foo = expr;` become `export {
+///    expr as foo };` if `expr` is a top-level binding which is never
+///    reassigned, and `export const foo = expr;` otherwise.
+///
+/// The argument of `require` should be a string literal, and destructuring
+/// patterns may not have default values or rest elements.
+///
+/// The module is left untouched if anything else refers to `require`,
+/// `module` or `exports` (e.g. a dynamic `require(name)`, a `require` in a
+/// function or a read of `module.exports`), if one of them is declared as a
+/// binding, if an export is assigned twice, or if `exports.foo = expr;` would
+/// introduce a binding named `foo` which is already declared or referenced.
+///
+/// Note that imports are hoisted, so side effects of statements before a
+/// `require` now run after the required module is evaluated.
+pub fn cjs_to_esm() -> impl Pass {
+    CjsToEsm
+}
+
+struct CjsToEsm;
+
+noop_fold_type!(CjsToEsm);
+
+impl Fold<Module> for CjsToEsm {
+    fn fold(&mut self, module: Module) -> Module {
+        match convert(&module) {
+            Some(body) => Module { body, ..module },
+            None => module,
+        }
+    }
+}
+
+fn is_cjs_name(sym: &JsWord) -> bool {
+    match &**sym {
+        "require" | "module" | "exports" => true,
+        _ => false,
+    }
+}
+
+/// Returns `None` if the module should be left untouched.
+fn convert(module: &Module) -> Option<Vec<ModuleItem>> {
+    let mut bindings = BindingFinder::default();
+    module.visit_with(&mut bindings);
+    if bindings.found_cjs_name {
+        return None;
+    }
+    let top_level = bindings.top_level;
+
+    let mut usage = UsageFinder::default();
+    module.visit_with(&mut usage);
+
+    let mut changed = false;
+    let mut has_default = false;
+    let mut exported = FxHashSet::default();
+    let mut body = Vec::with_capacity(module.body.len());
+
+    for item in &module.body {
+        let stmt = match *item {
+            ModuleItem::Stmt(ref stmt) => stmt,
+            _ => {
+                body.push(item.clone());
+                continue;
+            }
+        };
+
+        let converted = match *stmt {
+            Stmt::Expr(ExprStmt { span, ref expr }) => match **expr {
+                Expr::Call(ref call) => require_src(call).map(|src| {
+                    ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+                        span,
+                        specifiers: vec![],
+                        src,
+                        type_only: false,
+                        asserts: None,
+                    }))
+                }),
+                Expr::Assign(ref assign) => match export_target(assign) {
+                    Some(ExportTarget::Default) => {
+                        if has_default {
+                            return None;
+                        }
+                        has_default = true;
+
+                        Some(ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(
+                            ExportDefaultExpr {
+                                span,
+                                expr: assign.right.clone(),
+                            },
+                        )))
+                    }
+                    Some(ExportTarget::Named(name)) => {
+                        if !exported.insert(name.sym.clone()) {
+                            return None;
+                        }
+
+                        Some(export_named(span, name, &assign.right, &top_level, &usage)?)
+                    }
+                    None => None,
+                },
+                _ => None,
+            },
+
+            Stmt::Decl(Decl::Var(VarDecl {
+                span,
+                kind: VarDeclKind::Const,
+                ref decls,
+                ..
+            })) if decls.len() == 1 => import_decl(span, &decls[0]),
+
+            _ => None,
+        };
+
+        match converted {
+            Some(converted) => {
+                changed = true;
+                body.push(converted);
+            }
+            None => body.push(item.clone()),
+        }
+    }
+
+    if !changed || (has_default && !exported.is_empty()) {
+        return None;
+    }
+
+    let mut usage = UsageFinder::default();
+    body.visit_with(&mut usage);
+    if usage.referenced.iter().any(is_cjs_name) {
+        return None;
+    }
+
+    Some(body)
+}
+
+/// Returns `'foo'` for `require('foo')`.
+fn require_src(call: &CallExpr) -> Option<Str> {
+    match call.callee {
+        ExprOrSuper::Expr(box Expr::Ident(ref callee)) if &*callee.sym == "require" => {}
+        _ => return None,
+    }
+
+    match call.args[..] {
+        [ExprOrSpread {
+            spread: None,
+            expr: box Expr::Lit(Lit::Str(ref src)),
+        }] => Some(src.clone()),
+        _ => None,
+    }
+}
+
+fn import_decl(span: Span, declarator: &VarDeclarator) -> Option<ModuleItem> {
+    let src = match declarator.init {
+        Some(box Expr::Call(ref call)) => require_src(call)?,
+        _ => return None,
+    };
+
+    let specifiers = match declarator.name {
+        Pat::Ident(ref local) => vec![ImportSpecifier::Default(ImportDefaultSpecifier {
+            span: local.span,
+            local: local.clone(),
+        })],
+        Pat::Object(ref obj) => obj
+            .props
+            .iter()
+            .map(|prop| match *prop {
+                ObjectPatProp::KeyValue(KeyValuePatProp {
+                    key: PropName::Ident(ref imported),
+                    value: box Pat::Ident(ref local),
+                }) => Some(ImportSpecifier::Named(ImportNamedSpecifier {
+                    span: prop.span(),
+                    local: local.clone(),
+                    imported: if imported.sym == local.sym {
+                        None
+                    } else {
+                        Some(imported.clone())
+                    },
+                })),
+                ObjectPatProp::Assign(AssignPatProp {
+                    span,
+                    ref key,
+                    value: None,
+                }) => Some(ImportSpecifier::Named(ImportNamedSpecifier {
+                    span,
+                    local: key.clone(),
+                    imported: None,
+                })),
+                _ => None,
+            })
+            .collect::<Option<_>>()?,
+        _ => return None,
+    };
+
+    Some(ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+        span,
+        specifiers,
+        src,
+        type_only: false,
+        asserts: None,
+    })))
+}
+
+enum ExportTarget {
+    Default,
+    Named(Ident),
+}
+
+fn member_of<'a>(e: &'a Expr, obj_name: &str) -> Option<&'a Ident> {
+    match *e {
+        Expr::Member(MemberExpr {
+            obj: ExprOrSuper::Expr(box Expr::Ident(ref obj)),
+            prop: box Expr::Ident(ref prop),
+            computed: false,
+            ..
+        }) if &*obj.sym == obj_name => Some(prop),
+        _ => None,
+    }
+}
+
+/// Recognizes `module.exports = `, `exports.foo = ` and `module.exports.foo =
+/// `.
+fn export_target(assign: &AssignExpr) -> Option<ExportTarget> {
+    if assign.op != AssignOp::Assign {
+        return None;
+    }
+
+    let left = match assign.left {
+        PatOrExpr::Expr(ref e) => &**e,
+        PatOrExpr::Pat(box Pat::Expr(ref e)) => &**e,
+        _ => return None,
+    };
+
+    if let Some(prop) = member_of(left, "module") {
+        if &*prop.sym == "exports" {
+            return Some(ExportTarget::Default);
+        }
+        return None;
+    }
+
+    let name = match *left {
+        Expr::Member(MemberExpr {
+            obj: ExprOrSuper::Expr(ref obj),
+            prop: box Expr::Ident(ref prop),
+            computed: false,
+            ..
+        }) => match **obj {
+            Expr::Ident(ref obj) if &*obj.sym == "exports" => prop,
+            ref obj if member_of(obj, "module").map_or(false, |p| &*p.sym == "exports") => prop,
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    if &*name.sym == "default" {
+        Some(ExportTarget::Default)
+    } else {
+        Some(ExportTarget::Named(name.clone()))
+    }
+}
+
+fn export_named(
+    span: Span,
+    name: Ident,
+    value: &Expr,
+    top_level: &FxHashSet<JsWord>,
+    usage: &UsageFinder,
+) -> Option<ModuleItem> {
+    // An export specifier is a live binding, so it's used only if the value
+    // can't change.
+    if let Expr::Ident(ref orig) = *value {
+        if top_level.contains(&orig.sym) && !usage.reassigned.contains(&orig.sym) {
+            return Some(ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(
+                NamedExport {
+                    span,
+                    specifiers: vec![ExportSpecifier::Named(ExportNamedSpecifier {
+                        span,
+                        orig: orig.clone(),
+                        exported: if orig.sym == name.sym {
+                            None
+                        } else {
+                            Some(name)
+                        },
+                    })],
+                    src: None,
+                    type_only: false,
+                },
+            )));
+        }
+    }
+
+    // `foo` would refer to the new binding instead of a global.
+    if top_level.contains(&name.sym) || usage.referenced.contains(&name.sym) {
+        return None;
+    }
+
+    Some(ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+        span,
+        decl: Decl::Var(VarDecl {
+            span,
+            kind: VarDeclKind::Const,
+            declare: false,
+            decls: vec![VarDeclarator {
+                span: DUMMY_SP,
+                name: Pat::Ident(name),
+                init: Some(box value.clone()),
+                definite: false,
+            }],
+        }),
+    })))
+}
+
+/// Collects top-level bindings and checks if `require`, `module` or `exports`
+/// is declared anywhere.
+#[derive(Default)]
+struct BindingFinder {
+    top_level: FxHashSet<JsWord>,
+    found_cjs_name: bool,
+}
+
+noop_visit_type!(BindingFinder);
+
+impl BindingFinder {
+    fn check(&mut self, ids: Vec<Ident>) {
+        self.found_cjs_name |= ids.iter().any(|i| is_cjs_name(&i.sym));
+    }
+}
+
+impl Visit<Module> for BindingFinder {
+    fn visit(&mut self, node: &Module) {
+        for item in &node.body {
+            match *item {
+                ModuleItem::Stmt(Stmt::Decl(Decl::Var(ref decl)))
+                | ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                    decl: Decl::Var(ref decl),
+                    ..
+                })) => {
+                    let ids: Vec<Ident> = find_ids(&decl.decls);
+                    self.top_level.extend(ids.into_iter().map(|i| i.sym));
+                }
+                ModuleItem::Stmt(Stmt::Decl(Decl::Fn(FnDecl { ref ident, .. })))
+                | ModuleItem::Stmt(Stmt::Decl(Decl::Class(ClassDecl { ref ident, .. })))
+                | ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                    decl: Decl::Fn(FnDecl { ref ident, .. }),
+                    ..
+                }))
+                | ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                    decl: Decl::Class(ClassDecl { ref ident, .. }),
+                    ..
+                })) => {
+                    self.top_level.insert(ident.sym.clone());
+                }
+                ModuleItem::ModuleDecl(ModuleDecl::Import(ref import)) => {
+                    self.top_level
+                        .extend(import.specifiers.iter().map(|s| match *s {
+                            ImportSpecifier::Named(ref s) => s.local.sym.clone(),
+                            ImportSpecifier::Default(ref s) => s.local.sym.clone(),
+                            ImportSpecifier::Namespace(ref s) => s.local.sym.clone(),
+                        }));
+                }
+                _ => {}
+            }
+        }
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<Pat> for BindingFinder {
+    fn visit(&mut self, node: &Pat) {
+        let ids: Vec<Ident> = find_ids(node);
+        self.check(ids);
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<FnDecl> for BindingFinder {
+    fn visit(&mut self, node: &FnDecl) {
+        self.check(vec![node.ident.clone()]);
+
+        node.function.visit_with(self);
+    }
+}
+
+impl Visit<ClassDecl> for BindingFinder {
+    fn visit(&mut self, node: &ClassDecl) {
+        self.check(vec![node.ident.clone()]);
+
+        node.class.visit_with(self);
+    }
+}
+
+impl Visit<ImportDecl> for BindingFinder {
+    fn visit(&mut self, node: &ImportDecl) {
+        self.check(
+            node.specifiers
+                .iter()
+                .map(|s| match *s {
+                    ImportSpecifier::Named(ref s) => s.local.clone(),
+                    ImportSpecifier::Default(ref s) => s.local.clone(),
+                    ImportSpecifier::Namespace(ref s) => s.local.clone(),
+                })
+                .collect(),
+        );
+    }
+}
+
+/// Collects names of referenced and reassigned identifiers.
+#[derive(Default)]
+struct UsageFinder {
+    referenced: FxHashSet<JsWord>,
+    reassigned: FxHashSet<JsWord>,
+}
+
+noop_visit_type!(UsageFinder);
+
+impl UsageFinder {
+    fn add_reassigned(&mut self, pat: &Pat) {
+        let ids: Vec<Ident> = find_ids(pat);
+        for id in ids {
+            self.referenced.insert(id.sym.clone());
+            self.reassigned.insert(id.sym);
+        }
+    }
+}
+
+impl Visit<Expr> for UsageFinder {
+    fn visit(&mut self, node: &Expr) {
+        if let Expr::Ident(ref i) = *node {
+            self.referenced.insert(i.sym.clone());
+        }
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<MemberExpr> for UsageFinder {
+    fn visit(&mut self, node: &MemberExpr) {
+        node.obj.visit_with(self);
+
+        if node.computed {
+            node.prop.visit_with(self);
+        }
+    }
+}
+
+impl Visit<Prop> for UsageFinder {
+    fn visit(&mut self, node: &Prop) {
+        if let Prop::Shorthand(ref i) = *node {
+            self.referenced.insert(i.sym.clone());
+        }
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<AssignExpr> for UsageFinder {
+    fn visit(&mut self, node: &AssignExpr) {
+        match node.left {
+            PatOrExpr::Pat(ref pat) => self.add_reassigned(pat),
+            PatOrExpr::Expr(box Expr::Ident(ref i)) => {
+                self.reassigned.insert(i.sym.clone());
+            }
+            _ => {}
+        }
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<UpdateExpr> for UsageFinder {
+    fn visit(&mut self, node: &UpdateExpr) {
+        if let Expr::Ident(ref i) = *node.arg {
+            self.reassigned.insert(i.sym.clone());
+        }
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<ForInStmt> for UsageFinder {
+    fn visit(&mut self, node: &ForInStmt) {
+        if let VarDeclOrPat::Pat(ref pat) = node.left {
+            self.add_reassigned(pat);
+        }
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<ForOfStmt> for UsageFinder {
+    fn visit(&mut self, node: &ForOfStmt) {
+        if let VarDeclOrPat::Pat(ref pat) = node.left {
+            self.add_reassigned(pat);
+        }
+
+        node.visit_children(self);
+    }
+}
@@ -0,0 +1,60 @@
+use crate::pass::Pass;
+use swc_common::{Fold, FoldWith};
+use swc_ecma_ast::*;
+
+#[derive(Debug, Default, Clone)]
+pub struct Config {
+    /// Replaces `import.meta.url`.
+    pub url: Option<Box<Expr>>,
+    /// Replaces other usages of `import.meta`.
+    pub meta: Option<Box<Expr>>,
+}
+
+/// Replaces `import.meta`, which is available only in es modules.
+///
+/// `import.meta.url` is replaced with [Config::url], and other usages of
+/// `import.meta` are replaced with [Config::meta]. Usages without a
+/// replacement are left as is.
+pub fn import_meta(c: Config) -> impl Pass {
+    ImportMeta { c }
+}
+
+struct ImportMeta {
+    c: Config,
+}
+
+noop_fold_type!(ImportMeta);
+
+fn is_import_meta(e: &Expr) -> bool {
+    match *e {
+        Expr::MetaProp(MetaPropExpr { ref meta, ref prop }) => {
+            &*meta.sym == "import" && &*prop.sym == "meta"
+        }
+        _ => false,
+    }
+}
+
+impl Fold<Expr> for ImportMeta {
+    fn fold(&mut self, e: Expr) -> Expr {
+        match e {
+            Expr::Member(MemberExpr {
+                obj: ExprOrSuper::Expr(ref obj),
+                prop: box Expr::Ident(ref prop),
+                computed: false,
+                ..
+            }) if is_import_meta(obj) && &*prop.sym == "url" => {
+                if let Some(ref url) = self.c.url {
+                    return (**url).clone();
+                }
+            }
+            Expr::MetaProp(..) if is_import_meta(&e) => {
+                if let Some(ref meta) = self.c.meta {
+                    return (**meta).clone();
+                }
+            }
+            _ => {}
+        }
+
+        e.fold_children(self)
+    }
+}
@@ -0,0 +1,127 @@
+use crate::pass::Pass;
+use std::sync::Arc;
+use swc_common::{FileName, Fold, FoldWith, SourceMap, Span, DUMMY_SP};
+use swc_ecma_ast::*;
+
+pub type Resolve = Arc<dyn Fn(&str, &FileName) -> Option<String> + Send + Sync>;
+
+/// Rewrites module specifiers of imports, re-exports and dynamic imports with
+/// a literal specifier.
+///
+/// `resolve` receives the specifier and the name of the importing file, and
+/// the specifier is replaced if it returns `Some`. The importing file is
+/// looked up from the span of the module, so nothing is rewritten in a module
+/// with a dummy span.
+pub fn resolve_import(cm: Arc<SourceMap>, resolve: Resolve) -> impl Pass {
+    ResolveImport {
+        cm,
+        resolve,
+        file_name: None,
+    }
+}
+
+struct ResolveImport {
+    cm: Arc<SourceMap>,
+    resolve: Resolve,
+    /// Name of the importing file.
+    file_name: Option<FileName>,
+}
+
+noop_fold_type!(ResolveImport);
+
+impl ResolveImport {
+    fn fold_file<T>(&mut self, span: Span, node: T) -> T
+    where
+        T: FoldWith<Self>,
+    {
+        if span == DUMMY_SP {
+            return node;
+        }
+
+        self.file_name = Some(self.cm.span_to_filename(span));
+        let node = node.fold_children(self);
+        self.file_name = None;
+
+        node
+    }
+
+    fn resolve(&self, src: Str) -> Str {
+        let file_name = match self.file_name {
+            Some(ref file_name) => file_name,
+            None => return src,
+        };
+
+        match (self.resolve)(&src.value, file_name) {
+            Some(value) => Str {
+                value: value.into(),
+                has_escape: false,
+                ..src
+            },
+            None => src,
+        }
+    }
+}
+
+impl Fold<Module> for ResolveImport {
+    fn fold(&mut self, node: Module) -> Module {
+        self.fold_file(node.span, node)
+    }
+}
+
+impl Fold<Script> for ResolveImport {
+    fn fold(&mut self, node: Script) -> Script {
+        self.fold_file(node.span, node)
+    }
+}
+
+impl Fold<ImportDecl> for ResolveImport {
+    fn fold(&mut self, node: ImportDecl) -> ImportDecl {
+        ImportDecl {
+            src: self.resolve(node.src),
+            ..node
+        }
+    }
+}
+
+impl Fold<NamedExport> for ResolveImport {
+    fn fold(&mut self, node: NamedExport) -> NamedExport {
+        NamedExport {
+            src: node.src.map(|src| self.resolve(src)),
+            ..node
+        }
+    }
+}
+
+impl Fold<ExportAll> for ResolveImport {
+    fn fold(&mut self, node: ExportAll) -> ExportAll {
+        ExportAll {
+            src: self.resolve(node.src),
+            ..node
+        }
+    }
+}
+
+impl Fold<CallExpr> for ResolveImport {
+    fn fold(&mut self, node: CallExpr) -> CallExpr {
+        let mut node = node.fold_children(self);
+
+        match node.callee {
+            ExprOrSuper::Expr(box Expr::Ident(Ident {
+                sym: js_word!("import"),
+                ..
+            })) => {}
+            _ => return node,
+        }
+
+        if let Some(ExprOrSpread {
+            spread: None,
+            expr: box Expr::Lit(Lit::Str(ref mut src)),
+        }) = node.args.first_mut()
+        {
+            let s = src.clone();
+            *src = self.resolve(s);
+        }
+
+        node
+    }
+}
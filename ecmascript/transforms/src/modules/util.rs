@@ -709,17 +709,23 @@ pub(super) fn define_es_module(exports: Ident) -> Stmt {
     .into_stmt()
 }
 
+/// Returns true if the directive prologue of `stmts` contains `"use strict"`.
 pub(super) fn has_use_strict(stmts: &[ModuleItem]) -> bool {
-    if stmts.is_empty() {
-        return false;
-    }
-    match *stmts.first().unwrap() {
-        ModuleItem::Stmt(Stmt::Expr(ExprStmt {
-            expr: box Expr::Lit(Lit::Str(Str { ref value, .. })),
-            ..
-        })) => &*value == "use strict",
-        _ => false,
+    for stmt in stmts {
+        match *stmt {
+            ModuleItem::Stmt(Stmt::Expr(ExprStmt {
+                expr: box Expr::Lit(Lit::Str(Str { ref value, .. })),
+                ..
+            })) => {
+                if &**value == "use strict" {
+                    return true;
+                }
+            }
+            _ => return false,
+        }
     }
+
+    false
 }
 
 pub(super) fn use_strict() -> Stmt {
@@ -1,5 +1,13 @@
-pub use self::{inline_globals::InlineGlobals, json_parse::JsonParse, simplify::simplifier};
+pub use self::{
+    dedup_temp_vars::dedup_temp_vars, drop_console::drop_console, drop_debugger::drop_debugger,
+    inline_globals::InlineGlobals, json_parse::JsonParse, rename_top_level::rename_top_level,
+    simplify::simplifier,
+};
 
+mod dedup_temp_vars;
+mod drop_console;
+mod drop_debugger;
 mod inline_globals;
 mod json_parse;
+pub mod rename_top_level;
 pub mod simplify;
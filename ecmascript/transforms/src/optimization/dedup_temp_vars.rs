@@ -0,0 +1,391 @@
+use crate::{pass::Pass, util::StmtLike};
+use std::collections::{HashMap, HashSet};
+use swc_common::{Fold, FoldWith, SyntaxContext, Visit, VisitWith};
+use swc_ecma_ast::*;
+use swc_ecma_utils::{find_ids, ident::IdentLike, Id};
+
+/// Merges identical `var` declarations of temporary variables created by
+/// transforms, like `var _this = this` injected by two passes.
+///
+/// In a run of consecutive `var` statements, a declarator is removed if an
+/// earlier one declares a temporary variable of the same name with the same
+/// initializer, and references to it are updated to use the earlier one. The
+/// initializer should be `this`, a literal or an identifier, and neither
+/// variable may be assigned later, so both always hold the same value.
+///
+/// Only variables created by transforms, which have a mark but no position in
+/// the source, are merged. Variables of the user are never touched.
+///
+/// This pass should run before [hygiene](crate::hygiene), as variables are
+/// distinguished by their syntax context.
+pub fn dedup_temp_vars() -> impl Pass {
+    DedupTempVars::default()
+}
+
+#[derive(Debug, Default)]
+struct DedupTempVars {
+    /// Variables assigned after their declaration.
+    assigned: HashSet<Id>,
+    /// Syntax contexts of removed variables are replaced with the one of the
+    /// variable which is kept.
+    renamed: HashMap<Id, SyntaxContext>,
+}
+
+noop_fold_type!(DedupTempVars);
+
+impl DedupTempVars {
+    fn fold_root<T>(&mut self, node: T) -> T
+    where
+        T: FoldWith<Self> + for<'a> VisitWith<AssignFinder<'a>>,
+    {
+        node.visit_with(&mut AssignFinder {
+            assigned: &mut self.assigned,
+        });
+
+        let node = node.fold_children(self);
+        if self.renamed.is_empty() {
+            return node;
+        }
+
+        node.fold_with(&mut Renamer {
+            renamed: &self.renamed,
+        })
+    }
+
+    /// Returns true if `decl` is a duplicate of a declarator in `seen`.
+    fn is_duplicate(
+        &mut self,
+        decl: &VarDeclarator,
+        seen: &mut Vec<(Ident, Option<Box<Expr>>)>,
+    ) -> bool {
+        let stable = match decl.init {
+            None => true,
+            // A regex literal creates a new object.
+            Some(box Expr::Lit(Lit::Regex(..))) => false,
+            Some(box Expr::This(..)) | Some(box Expr::Lit(..)) | Some(box Expr::Ident(..)) => true,
+            _ => false,
+        };
+        // The initializer may have side effects, so previous variables may hold
+        // different values.
+        if !stable {
+            seen.clear();
+            return false;
+        }
+
+        let ident = match decl.name {
+            Pat::Ident(ref i) if is_temp(i) && !self.assigned.contains(&i.to_id()) => Some(i),
+            _ => None,
+        };
+
+        if let Some(ident) = ident {
+            let kept = seen
+                .iter()
+                .find(|(kept, init)| kept.sym == ident.sym && *init == decl.init)
+                .map(|(kept, _)| kept.span.ctxt());
+            if let Some(ctxt) = kept {
+                if ctxt != ident.span.ctxt() {
+                    self.renamed.insert(ident.to_id(), ctxt);
+                }
+                return true;
+            }
+        }
+
+        // A declarator which rebinds a previous variable or a variable used by
+        // its initializer may change the value it holds.
+        let ids: Vec<Id> = find_ids(&decl.name);
+        seen.retain(|(kept, init)| {
+            !ids.contains(&kept.to_id())
+                && match init {
+                    Some(box Expr::Ident(i)) => !ids.contains(&i.to_id()),
+                    _ => true,
+                }
+        });
+
+        if let Some(ident) = ident {
+            seen.push((ident.clone(), decl.init.clone()));
+        }
+        false
+    }
+}
+
+/// Returns true if `i` is created by a transform.
+fn is_temp(i: &Ident) -> bool {
+    i.span.is_dummy() && i.span.ctxt() != SyntaxContext::empty()
+}
+
+impl Fold<Module> for DedupTempVars {
+    fn fold(&mut self, node: Module) -> Module {
+        self.fold_root(node)
+    }
+}
+
+impl Fold<Script> for DedupTempVars {
+    fn fold(&mut self, node: Script) -> Script {
+        self.fold_root(node)
+    }
+}
+
+impl<T: StmtLike> Fold<Vec<T>> for DedupTempVars
+where
+    Self: Fold<T>,
+{
+    fn fold(&mut self, stmts: Vec<T>) -> Vec<T> {
+        let mut seen = vec![];
+        let mut buf = Vec::with_capacity(stmts.len());
+
+        for stmt in stmts {
+            let stmt = self.fold(stmt);
+            let stmt = match stmt.try_into_stmt() {
+                Ok(Stmt::Decl(Decl::Var(var))) if var.kind == VarDeclKind::Var => {
+                    let decls: Vec<_> = var
+                        .decls
+                        .into_iter()
+                        .filter(|decl| !self.is_duplicate(decl, &mut seen))
+                        .collect();
+                    if decls.is_empty() {
+                        continue;
+                    }
+
+                    Stmt::Decl(Decl::Var(VarDecl { decls, ..var }))
+                }
+                Ok(stmt) => {
+                    seen.clear();
+                    stmt
+                }
+                Err(item) => {
+                    seen.clear();
+                    buf.push(item);
+                    continue;
+                }
+            };
+
+            buf.push(T::from_stmt(stmt));
+        }
+
+        buf
+    }
+}
+
+struct AssignFinder<'a> {
+    assigned: &'a mut HashSet<Id>,
+}
+
+impl Visit<AssignExpr> for AssignFinder<'_> {
+    fn visit(&mut self, node: &AssignExpr) {
+        self.assigned.extend(find_ids::<_, Id>(&node.left));
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<UpdateExpr> for AssignFinder<'_> {
+    fn visit(&mut self, node: &UpdateExpr) {
+        self.assigned.extend(find_ids::<_, Id>(&node.arg));
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<ForInStmt> for AssignFinder<'_> {
+    fn visit(&mut self, node: &ForInStmt) {
+        self.assigned.extend(find_ids::<_, Id>(&node.left));
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<ForOfStmt> for AssignFinder<'_> {
+    fn visit(&mut self, node: &ForOfStmt) {
+        self.assigned.extend(find_ids::<_, Id>(&node.left));
+
+        node.visit_children(self);
+    }
+}
+
+struct Renamer<'a> {
+    renamed: &'a HashMap<Id, SyntaxContext>,
+}
+
+noop_fold_type!(Renamer<'_>);
+
+impl Fold<Ident> for Renamer<'_> {
+    fn fold(&mut self, i: Ident) -> Ident {
+        match self.renamed.get(&i.to_id()) {
+            Some(&ctxt) => Ident {
+                span: i.span.with_ctxt(ctxt),
+                ..i
+            },
+            None => i,
+        }
+    }
+}
+
+impl Fold<Expr> for Renamer<'_> {
+    fn fold(&mut self, e: Expr) -> Expr {
+        match e {
+            Expr::Member(e) if !e.computed => Expr::Member(MemberExpr {
+                obj: e.obj.fold_with(self),
+                ..e
+            }),
+            _ => e.fold_children(self),
+        }
+    }
+}
+
+impl Fold<PropName> for Renamer<'_> {
+    fn fold(&mut self, n: PropName) -> PropName {
+        match n {
+            PropName::Computed(e) => PropName::Computed(e.fold_with(self)),
+            _ => n,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::Tester;
+    use swc_common::{chain, DUMMY_SP};
+
+    /// Declares `var _tmp = init` at the top of the module and uses it at the
+    /// end, like a transform which stores a value in a temporary variable.
+    struct Temp(Expr);
+
+    impl Fold<Vec<ModuleItem>> for Temp {
+        fn fold(&mut self, mut items: Vec<ModuleItem>) -> Vec<ModuleItem> {
+            let tmp = private_ident!("_tmp");
+
+            items.insert(
+                0,
+                ModuleItem::Stmt(Stmt::Decl(Decl::Var(VarDecl {
+                    span: DUMMY_SP,
+                    kind: VarDeclKind::Var,
+                    declare: false,
+                    decls: vec![VarDeclarator {
+                        span: DUMMY_SP,
+                        name: Pat::Ident(tmp.clone()),
+                        init: Some(box self.0.clone()),
+                        definite: false,
+                    }],
+                }))),
+            );
+            items.push(ModuleItem::Stmt(Stmt::Expr(ExprStmt {
+                span: DUMMY_SP,
+                expr: box Expr::Ident(tmp),
+            })));
+
+            items
+        }
+    }
+
+    fn this() -> Temp {
+        Temp(Expr::This(ThisExpr { span: DUMMY_SP }))
+    }
+
+    test!(
+        ::swc_ecma_parser::Syntax::default(),
+        |_| chain!(this(), this(), dedup_temp_vars()),
+        identical,
+        "foo();",
+        "var _tmp = this;
+foo();
+_tmp;
+_tmp;"
+    );
+
+    /// Returns the number of variables declared after applying `tr`.
+    fn count_vars(tr: impl Fold<Module>) -> usize {
+        let mut count = 0;
+        Tester::run(|tester| {
+            let module = tester.apply_transform(tr, "input.js", Default::default(), "foo();")?;
+            count = module
+                .body
+                .iter()
+                .map(|item| match item {
+                    ModuleItem::Stmt(Stmt::Decl(Decl::Var(var))) => var.decls.len(),
+                    _ => 0,
+                })
+                .sum();
+            Ok(())
+        });
+
+        count
+    }
+
+    #[test]
+    fn different_init() {
+        let null = || Temp(Expr::Lit(Lit::Null(Null { span: DUMMY_SP })));
+
+        assert_eq!(count_vars(chain!(null(), null(), dedup_temp_vars())), 1);
+        assert_eq!(count_vars(chain!(this(), null(), dedup_temp_vars())), 2);
+    }
+
+    #[test]
+    fn side_effect_between() {
+        let call = Temp(Expr::Call(CallExpr {
+            span: DUMMY_SP,
+            callee: ExprOrSuper::Expr(box Expr::Ident(quote_ident!("bar"))),
+            args: vec![],
+            type_args: None,
+        }));
+
+        assert_eq!(count_vars(chain!(this(), call, this(), dedup_temp_vars())), 3);
+    }
+
+    /// Declares `var _tmp = a; var a = 2; var _tmp = a;`, where `a` is a
+    /// variable of the user.
+    struct RebindInit;
+
+    impl Fold<Vec<ModuleItem>> for RebindInit {
+        fn fold(&mut self, mut items: Vec<ModuleItem>) -> Vec<ModuleItem> {
+            let tmp = private_ident!("_tmp");
+            let var = |name: Ident, init: Expr| {
+                ModuleItem::Stmt(Stmt::Decl(Decl::Var(VarDecl {
+                    span: DUMMY_SP,
+                    kind: VarDeclKind::Var,
+                    declare: false,
+                    decls: vec![VarDeclarator {
+                        span: DUMMY_SP,
+                        name: Pat::Ident(name),
+                        init: Some(box init),
+                        definite: false,
+                    }],
+                })))
+            };
+
+            items.splice(
+                0..0,
+                vec![
+                    var(tmp.clone(), Expr::Ident(quote_ident!("a"))),
+                    var(
+                        quote_ident!("a"),
+                        Expr::Lit(Lit::Num(Number {
+                            span: DUMMY_SP,
+                            value: 2.0,
+                        })),
+                    ),
+                    var(tmp, Expr::Ident(quote_ident!("a"))),
+                ],
+            );
+
+            items
+        }
+    }
+
+    #[test]
+    fn rebound_init() {
+        assert_eq!(count_vars(chain!(RebindInit, dedup_temp_vars())), 3);
+    }
+
+    test!(
+        ::swc_ecma_parser::Syntax::default(),
+        |_| dedup_temp_vars(),
+        user_vars,
+        "var _tmp = this;
+var _tmp = this;
+foo(_tmp);",
+        "var _tmp = this;
+var _tmp = this;
+foo(_tmp);"
+    );
+}
@@ -0,0 +1,279 @@
+use crate::{pass::Pass, util::undefined};
+use fxhash::FxHashSet;
+use swc_atoms::JsWord;
+use swc_common::{Fold, FoldWith, Spanned, SyntaxContext, Visit, VisitWith};
+use swc_ecma_ast::*;
+
+/// Removes calls to methods of the global `console`.
+///
+/// If `methods` is `None`, calls to all methods are removed. A call used as
+/// an expression is replaced with `void 0`.
+///
+/// A call is preserved if its callee refers to a local binding named
+/// `console`.
+///
+/// This pass should be applied after `resolver`, as references to a local
+/// binding named `console` are distinguished by their syntax context.
+pub fn drop_console(methods: Option<Vec<JsWord>>) -> impl Pass + 'static {
+    DropConsole {
+        methods: methods.map(|methods| methods.into_iter().collect()),
+        bindings: Default::default(),
+    }
+}
+
+#[derive(Debug, Clone)]
+struct DropConsole {
+    methods: Option<FxHashSet<JsWord>>,
+    /// Syntax contexts of bindings named `console`.
+    bindings: FxHashSet<SyntaxContext>,
+}
+
+noop_fold_type!(DropConsole);
+
+impl Fold<Module> for DropConsole {
+    fn fold(&mut self, node: Module) -> Module {
+        self.bindings = find_console_bindings(&node);
+
+        node.fold_children(self)
+    }
+}
+
+impl Fold<Script> for DropConsole {
+    fn fold(&mut self, node: Script) -> Script {
+        self.bindings = find_console_bindings(&node);
+
+        node.fold_children(self)
+    }
+}
+
+impl DropConsole {
+    fn should_drop(&self, e: &Expr) -> bool {
+        let callee = match *e {
+            Expr::Call(CallExpr {
+                callee: ExprOrSuper::Expr(box Expr::Member(ref callee)),
+                ..
+            }) => callee,
+            _ => return false,
+        };
+
+        match callee.obj {
+            ExprOrSuper::Expr(box Expr::Ident(ref obj))
+                if &*obj.sym == "console" && !self.bindings.contains(&obj.span.ctxt()) => {}
+            _ => return false,
+        }
+
+        let methods = match self.methods {
+            Some(ref methods) => methods,
+            None => return true,
+        };
+
+        match *callee.prop {
+            Expr::Ident(ref prop) if !callee.computed => methods.contains(&prop.sym),
+            Expr::Lit(Lit::Str(ref prop)) if callee.computed => methods.contains(&prop.value),
+            _ => false,
+        }
+    }
+
+    fn should_drop_stmt(&self, s: &Stmt) -> bool {
+        match *s {
+            Stmt::Expr(ExprStmt { ref expr, .. }) => self.should_drop(expr),
+            _ => false,
+        }
+    }
+}
+
+impl Fold<Vec<ModuleItem>> for DropConsole {
+    fn fold(&mut self, mut items: Vec<ModuleItem>) -> Vec<ModuleItem> {
+        items.retain(|item| match item {
+            ModuleItem::Stmt(s) => !self.should_drop_stmt(s),
+            _ => true,
+        });
+
+        items.fold_children(self)
+    }
+}
+
+impl Fold<Vec<Stmt>> for DropConsole {
+    fn fold(&mut self, mut stmts: Vec<Stmt>) -> Vec<Stmt> {
+        stmts.retain(|s| !self.should_drop_stmt(s));
+
+        stmts.fold_children(self)
+    }
+}
+
+impl Fold<Stmt> for DropConsole {
+    fn fold(&mut self, s: Stmt) -> Stmt {
+        if self.should_drop_stmt(&s) {
+            return Stmt::Empty(EmptyStmt { span: s.span() });
+        }
+
+        s.fold_children(self)
+    }
+}
+
+impl Fold<Expr> for DropConsole {
+    fn fold(&mut self, e: Expr) -> Expr {
+        if self.should_drop(&e) {
+            return *undefined(e.span());
+        }
+
+        e.fold_children(self)
+    }
+}
+
+fn find_console_bindings<T>(node: &T) -> FxHashSet<SyntaxContext>
+where
+    T: VisitWith<BindingFinder>,
+{
+    let mut v = BindingFinder::default();
+    node.visit_with(&mut v);
+    v.bindings
+}
+
+#[derive(Default)]
+struct BindingFinder {
+    bindings: FxHashSet<SyntaxContext>,
+}
+
+noop_visit_type!(BindingFinder);
+
+impl BindingFinder {
+    fn add(&mut self, i: &Ident) {
+        if &*i.sym == "console" {
+            self.bindings.insert(i.span.ctxt());
+        }
+    }
+}
+
+impl Visit<Pat> for BindingFinder {
+    fn visit(&mut self, node: &Pat) {
+        if let Pat::Ident(ref i) = *node {
+            self.add(i);
+        }
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<FnDecl> for BindingFinder {
+    fn visit(&mut self, node: &FnDecl) {
+        self.add(&node.ident);
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<FnExpr> for BindingFinder {
+    fn visit(&mut self, node: &FnExpr) {
+        if let Some(ref i) = node.ident {
+            self.add(i);
+        }
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<ClassDecl> for BindingFinder {
+    fn visit(&mut self, node: &ClassDecl) {
+        self.add(&node.ident);
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<ClassExpr> for BindingFinder {
+    fn visit(&mut self, node: &ClassExpr) {
+        if let Some(ref i) = node.ident {
+            self.add(i);
+        }
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<ImportSpecifier> for BindingFinder {
+    fn visit(&mut self, node: &ImportSpecifier) {
+        match *node {
+            ImportSpecifier::Named(ImportNamedSpecifier { ref local, .. })
+            | ImportSpecifier::Default(ImportDefaultSpecifier { ref local, .. })
+            | ImportSpecifier::Namespace(ImportStarAsSpecifier { ref local, .. }) => {
+                self.add(local)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::resolver::resolver;
+    use swc_common::chain;
+
+    test!(
+        ::swc_ecma_parser::Syntax::default(),
+        |_| chain!(resolver(), drop_console(None)),
+        drop_all,
+        "console.log('a');
+foo();
+console.error('b');
+if (a) console.debug(a);",
+        "foo();
+if (a) ;"
+    );
+
+    test!(
+        ::swc_ecma_parser::Syntax::default(),
+        |_| chain!(
+            resolver(),
+            drop_console(Some(vec!["log".into(), "debug".into()]))
+        ),
+        drop_selected,
+        "console.log('a');
+console['debug']('b');
+console.error('c');
+function foo() {
+    console.log('d');
+    return 1;
+}",
+        "console.error('c');
+function foo() {
+    return 1;
+}"
+    );
+
+    test!(
+        ::swc_ecma_parser::Syntax::default(),
+        |_| chain!(resolver(), drop_console(None)),
+        expr_position,
+        "const a = [console.log('a'), b];
+foo(console.log, console.log());",
+        "const a = [void 0, b];
+foo(console.log, void 0);"
+    );
+
+    test!(
+        ::swc_ecma_parser::Syntax::default(),
+        |_| chain!(resolver(), drop_console(None)),
+        shadowed,
+        "function foo(console) {
+    console.log('a');
+}
+{
+    const console = bar();
+    console.log('b');
+}
+console.log('c');
+{
+    console.log('d');
+}",
+        "function foo(console) {
+    console.log('a');
+}
+{
+    const console = bar();
+    console.log('b');
+}
+{
+}"
+    );
+}
@@ -0,0 +1,111 @@
+use crate::pass::Pass;
+use swc_common::{Fold, FoldWith};
+use swc_ecma_ast::*;
+
+/// Removes `debugger` statements.
+///
+/// A `debugger` statement which is the body of another statement (e.g.
+/// `if (a) debugger;`) is replaced with an empty statement.
+pub fn drop_debugger() -> impl Pass + 'static {
+    DropDebugger
+}
+
+#[derive(Debug, Clone, Copy)]
+struct DropDebugger;
+
+noop_fold_type!(DropDebugger);
+
+impl Fold<Vec<ModuleItem>> for DropDebugger {
+    fn fold(&mut self, mut items: Vec<ModuleItem>) -> Vec<ModuleItem> {
+        items.retain(|item| match item {
+            ModuleItem::Stmt(Stmt::Debugger(..)) => false,
+            _ => true,
+        });
+
+        items.fold_children(self)
+    }
+}
+
+impl Fold<Vec<Stmt>> for DropDebugger {
+    fn fold(&mut self, mut stmts: Vec<Stmt>) -> Vec<Stmt> {
+        stmts.retain(|stmt| match stmt {
+            Stmt::Debugger(..) => false,
+            _ => true,
+        });
+
+        stmts.fold_children(self)
+    }
+}
+
+impl Fold<Stmt> for DropDebugger {
+    fn fold(&mut self, stmt: Stmt) -> Stmt {
+        match stmt {
+            Stmt::Debugger(DebuggerStmt { span }) => Stmt::Empty(EmptyStmt { span }),
+            _ => stmt.fold_children(self),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    test!(
+        ::swc_ecma_parser::Syntax::default(),
+        |_| drop_debugger(),
+        top_level,
+        "foo();
+debugger;
+bar();",
+        "foo();
+bar();"
+    );
+
+    test!(
+        ::swc_ecma_parser::Syntax::default(),
+        |_| drop_debugger(),
+        function_body,
+        "function foo() {
+    debugger;
+    return 1;
+}",
+        "function foo() {
+    return 1;
+}"
+    );
+
+    test!(
+        ::swc_ecma_parser::Syntax::default(),
+        |_| drop_debugger(),
+        only_stmt_in_block,
+        "if (a) {
+    debugger;
+}
+b();",
+        "if (a) {
+}
+b();"
+    );
+
+    test!(
+        ::swc_ecma_parser::Syntax::default(),
+        |_| drop_debugger(),
+        body_of_if,
+        "if (a) debugger;
+else b();",
+        "if (a) ;
+else b();"
+    );
+
+    test!(
+        ::swc_ecma_parser::Syntax::default(),
+        |_| drop_debugger(),
+        empty_stmt_untouched,
+        "a();
+;
+label: for(;;) debugger;",
+        "a();
+;
+label: for(;;);"
+    );
+}
@@ -1,18 +1,130 @@
-use std::collections::HashMap;
+use crate::{global_rename::BindingCollector, util::HANDLER};
+use std::collections::{HashMap, HashSet};
 use swc_atoms::{js_word, JsWord};
-use swc_common::{Fold, FoldWith};
+use swc_common::{Fold, FoldWith, Spanned, VisitWith};
 use swc_ecma_ast::*;
+use swc_ecma_utils::{ident::IdentLike, Id};
 
 #[derive(Clone)]
 pub struct InlineGlobals {
     pub envs: HashMap<JsWord, Expr>,
+    /// Keys are names of identifiers or dotted paths of member expressions
+    /// like `process.env.NODE_ENV`. Paths take precedence over `envs`.
     pub globals: HashMap<JsWord, Expr>,
+    /// Results of `typeof` for global variables, like `undefined` for
+    /// `process` while targeting browsers.
+    ///
+    /// Only references which are not bound anywhere in the file are folded,
+    /// so this depends on [resolver](crate::resolver) to tell a global from a
+    /// local binding with the same name.
+    pub typeofs: HashMap<JsWord, JsWord>,
 }
 
 noop_fold_type!(InlineGlobals);
 
+/// Returns a dotted path like `process.env.NODE_ENV` if `e` is a chain of
+/// property accesses on an identifier.
+fn member_path(e: &MemberExpr) -> Option<String> {
+    let mut path = match e.obj {
+        ExprOrSuper::Expr(box Expr::Ident(ref i)) => i.sym.to_string(),
+        ExprOrSuper::Expr(box Expr::Member(ref obj)) => member_path(obj)?,
+        _ => return None,
+    };
+
+    let prop = match *e.prop {
+        Expr::Ident(ref i) if !e.computed => &i.sym,
+        Expr::Lit(Lit::Str(Str { ref value, .. })) if e.computed => value,
+        _ => return None,
+    };
+    path.push('.');
+    path.push_str(prop);
+
+    Some(path)
+}
+
+impl InlineGlobals {
+    fn fold_root<T>(&mut self, node: T) -> T
+    where
+        T: FoldWith<Self> + VisitWith<BindingCollector> + for<'a> FoldWith<TypeOfFolder<'a>>,
+    {
+        let node = node.fold_children(self);
+        if self.typeofs.is_empty() {
+            return node;
+        }
+
+        let mut v = BindingCollector::default();
+        node.visit_with(&mut v);
+
+        node.fold_with(&mut TypeOfFolder {
+            typeofs: &self.typeofs,
+            bindings: v.bindings,
+        })
+    }
+
+    /// Returns true if a member expression with the dotted `path` is replaced.
+    fn is_replaced(&self, path: &str) -> bool {
+        const ENV: &str = "process.env.";
+
+        self.globals.contains_key(&JsWord::from(path))
+            || (path.starts_with(ENV) && self.envs.contains_key(&JsWord::from(&path[ENV.len()..])))
+    }
+}
+
+impl Fold<Module> for InlineGlobals {
+    fn fold(&mut self, node: Module) -> Module {
+        self.fold_root(node)
+    }
+}
+
+impl Fold<Script> for InlineGlobals {
+    fn fold(&mut self, node: Script) -> Script {
+        self.fold_root(node)
+    }
+}
+
+/// Assignments to replaced paths are kept with a warning, as replacing them
+/// results in invalid code.
+impl Fold<AssignExpr> for InlineGlobals {
+    fn fold(&mut self, e: AssignExpr) -> AssignExpr {
+        let path = match e.left {
+            PatOrExpr::Expr(box Expr::Member(ref m))
+            | PatOrExpr::Pat(box Pat::Expr(box Expr::Member(ref m))) => member_path(m),
+            _ => None,
+        };
+
+        match path {
+            Some(path) if self.is_replaced(&path) => {
+                HANDLER.with(|handler| {
+                    handler
+                        .struct_span_warn(
+                            e.left.span(),
+                            &format!("`{}` is not replaced as it's assigned", path),
+                        )
+                        .emit()
+                });
+
+                AssignExpr {
+                    right: e.right.fold_with(self),
+                    ..e
+                }
+            }
+            _ => e.fold_children(self),
+        }
+    }
+}
+
 impl Fold<Expr> for InlineGlobals {
     fn fold(&mut self, expr: Expr) -> Expr {
+        if let Expr::Member(ref e) = expr {
+            if !self.globals.is_empty() {
+                if let Some(value) =
+                    member_path(e).and_then(|path| self.globals.get(&JsWord::from(path)))
+                {
+                    return value.clone().fold_with(self);
+                }
+            }
+        }
+
         let expr = match expr {
             Expr::Member(expr) => {
                 if expr.computed {
@@ -91,9 +203,45 @@ impl Fold<Expr> for InlineGlobals {
     }
 }
 
+/// Folds `typeof` of globals in [InlineGlobals::typeofs].
+struct TypeOfFolder<'a> {
+    typeofs: &'a HashMap<JsWord, JsWord>,
+    /// Bindings declared in the file.
+    bindings: HashSet<Id>,
+}
+
+noop_fold_type!(TypeOfFolder<'_>);
+
+impl Fold<Expr> for TypeOfFolder<'_> {
+    fn fold(&mut self, expr: Expr) -> Expr {
+        let expr = expr.fold_children(self);
+
+        if let Expr::Unary(UnaryExpr {
+            span,
+            op: op!("typeof"),
+            arg: box Expr::Ident(ref i),
+        }) = expr
+        {
+            if !self.bindings.contains(&i.to_id()) {
+                if let Some(value) = self.typeofs.get(&i.sym) {
+                    return Expr::Lit(Lit::Str(Str {
+                        span,
+                        value: value.clone(),
+                        has_escape: false,
+                    }));
+                }
+            }
+        }
+
+        expr
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::resolver::resolver;
+    use swc_common::chain;
 
     fn mk_map(
         tester: &mut crate::tests::Tester<'_>,
@@ -148,6 +296,7 @@ mod tests {
         |tester| InlineGlobals {
             envs: envs(tester, &[]),
             globals: globals(tester, &[]),
+            typeofs: Default::default(),
         },
         issue_215,
         r#"if (process.env.x === 'development') {}"#,
@@ -159,6 +308,7 @@ mod tests {
         |tester| InlineGlobals {
             envs: envs(tester, &[("NODE_ENV", "development")]),
             globals: globals(tester, &[]),
+            typeofs: Default::default(),
         },
         node_env,
         r#"if (process.env.NODE_ENV === 'development') {}"#,
@@ -170,6 +320,7 @@ mod tests {
         |tester| InlineGlobals {
             envs: envs(tester, &[]),
             globals: globals(tester, &[("__DEBUG__", "true")]),
+            typeofs: Default::default(),
         },
         inline_globals,
         r#"if (__DEBUG__) {}"#,
@@ -181,17 +332,74 @@ mod tests {
         |tester| InlineGlobals {
             envs: envs(tester, &[]),
             globals: globals(tester, &[("debug", "true")]),
+            typeofs: Default::default(),
         },
         non_global,
         r#"if (foo.debug) {}"#,
         r#"if (foo.debug) {}"#
     );
 
+    test!(
+        ::swc_ecma_parser::Syntax::default(),
+        |tester| InlineGlobals {
+            envs: envs(tester, &[("NODE_ENV", "development")]),
+            globals: globals(tester, &[("process.env.NODE_ENV", "'production'")]),
+            typeofs: Default::default(),
+        },
+        member_path,
+        r#"if (process.env.NODE_ENV !== 'production' && process.env['NODE_ENV']) {}"#,
+        r#"if ('production' !== 'production' && 'production') {}"#
+    );
+
+    test!(
+        ::swc_ecma_parser::Syntax::default(),
+        |tester| InlineGlobals {
+            envs: envs(tester, &[]),
+            globals: globals(tester, &[("process.env.NODE_ENV", "'production'")]),
+            typeofs: Default::default(),
+        },
+        member_path_prefix,
+        r#"use(process.env, process.env.NODE_ENV.length, foo.process.env.NODE_ENV);"#,
+        r#"use(process.env, 'production'.length, foo.process.env.NODE_ENV);"#
+    );
+
+    test!(
+        ::swc_ecma_parser::Syntax::default(),
+        |tester| InlineGlobals {
+            envs: envs(tester, &[]),
+            globals: globals(tester, &[]),
+            typeofs: vec![("process".into(), "undefined".into())]
+                .into_iter()
+                .collect(),
+        },
+        typeofs,
+        r#"if (typeof process === 'undefined') {} use(typeof window, process);"#,
+        r#"if ('undefined' === 'undefined') {} use(typeof window, process);"#
+    );
+
+    test!(
+        ::swc_ecma_parser::Syntax::default(),
+        |tester| chain!(
+            resolver(),
+            InlineGlobals {
+                envs: envs(tester, &[]),
+                globals: globals(tester, &[]),
+                typeofs: vec![("process".into(), "undefined".into())]
+                    .into_iter()
+                    .collect(),
+            }
+        ),
+        typeofs_local_binding,
+        r#"function f(process) { return typeof process; } use(typeof process);"#,
+        r#"function f(process) { return typeof process; } use('undefined');"#
+    );
+
     test!(
         Default::default(),
         |tester| InlineGlobals {
             envs: envs(tester, &[]),
             globals: globals(tester, &[]),
+            typeofs: Default::default(),
         },
         issue_417_1,
         "const test = process.env['x']",
@@ -203,6 +411,7 @@ mod tests {
         |tester| InlineGlobals {
             envs: envs(tester, &[("x", "FOO")]),
             globals: globals(tester, &[]),
+            typeofs: Default::default(),
         },
         issue_417_2,
         "const test = process.env['x']",
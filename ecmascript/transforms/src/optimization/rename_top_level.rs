@@ -0,0 +1,170 @@
+use crate::{global_rename::IdentRenamer, pass::Pass};
+use std::collections::{HashMap, HashSet};
+use swc_atoms::JsWord;
+use swc_common::{Fold, FoldWith, Visit, VisitWith};
+use swc_ecma_ast::*;
+use swc_ecma_utils::{find_ids, ident::IdentLike, Id};
+
+#[derive(Debug, Default)]
+pub struct Config {
+    /// Names of globals declared elsewhere, e.g. by other scripts which are
+    /// concatenated with this one.
+    pub reserved: HashSet<JsWord>,
+
+    /// If set, all top-level bindings are renamed to `{prefix}{name}`.
+    pub prefix: Option<JsWord>,
+}
+
+/// Renames top-level bindings of a script which would collide with other
+/// globals, so scripts can be concatenated safely.
+///
+/// A binding is renamed if its name is in [Config::reserved], or to
+/// `{prefix}{name}` if [Config::prefix] is set. If the new name is used in the
+/// script or reserved, a number is appended to it.
+///
+/// Top-level bindings of a module are not globals, so modules are left
+/// untouched.
+///
+/// This pass depends on [resolver](crate::resolver), as references are
+/// updated by their syntax context.
+pub fn rename_top_level(c: Config) -> impl Pass {
+    RenameTopLevel {
+        c,
+        renamed: Default::default(),
+    }
+}
+
+struct RenameTopLevel {
+    c: Config,
+    renamed: HashMap<Id, JsWord>,
+}
+
+noop_fold_type!(RenameTopLevel);
+
+impl RenameTopLevel {
+    fn new_name(&self, sym: &JsWord, used: &HashSet<JsWord>) -> JsWord {
+        let base = match self.c.prefix {
+            Some(ref prefix) => format!("{}{}", prefix, sym),
+            None => sym.to_string(),
+        };
+        let is_free = |s: &JsWord| !used.contains(s) && !self.c.reserved.contains(s);
+
+        if self.c.prefix.is_some() {
+            let sym: JsWord = base.clone().into();
+            if is_free(&sym) {
+                return sym;
+            }
+        }
+
+        let mut i = 0;
+        loop {
+            i += 1;
+            let sym: JsWord = format!("{}{}", base, i).into();
+
+            if is_free(&sym) {
+                break sym;
+            }
+        }
+    }
+}
+
+impl Fold<Module> for RenameTopLevel {
+    fn fold(&mut self, node: Module) -> Module {
+        node
+    }
+}
+
+impl Fold<Script> for RenameTopLevel {
+    fn fold(&mut self, node: Script) -> Script {
+        if self.c.reserved.is_empty() && self.c.prefix.is_none() {
+            return node;
+        }
+
+        let mut bindings = vec![];
+        for stmt in &node.body {
+            match *stmt {
+                Stmt::Decl(Decl::Fn(ref f)) => bindings.push(f.ident.to_id()),
+                Stmt::Decl(Decl::Class(ref c)) => bindings.push(c.ident.to_id()),
+                Stmt::Decl(Decl::Var(ref v)) => {
+                    let ids: Vec<Id> = find_ids(&v.decls);
+                    bindings.extend(ids);
+                }
+                _ => {}
+            }
+        }
+        // `var`s in blocks are top-level bindings too.
+        let mut v = VarFinder::default();
+        node.visit_with(&mut v);
+        bindings.extend(v.vars);
+
+        let mut v = SymbolCollector::default();
+        node.visit_with(&mut v);
+        let mut used = v.symbols;
+
+        for id in bindings {
+            if self.renamed.contains_key(&id) {
+                continue;
+            }
+            if self.c.prefix.is_none() && !self.c.reserved.contains(&id.0) {
+                continue;
+            }
+
+            let sym = self.new_name(&id.0, &used);
+            used.insert(sym.clone());
+            self.renamed.insert(id, sym);
+        }
+
+        if self.renamed.is_empty() {
+            return node;
+        }
+
+        let renamed = &self.renamed;
+        node.fold_children(&mut IdentRenamer {
+            lookup: &|i| renamed.get(&i.to_id()).cloned(),
+        })
+    }
+}
+
+/// Finds `var`s which are not in a function.
+#[derive(Default)]
+struct VarFinder {
+    vars: Vec<Id>,
+}
+
+noop_visit_type!(VarFinder);
+
+impl Visit<VarDecl> for VarFinder {
+    fn visit(&mut self, node: &VarDecl) {
+        if node.kind == VarDeclKind::Var {
+            let ids: Vec<Id> = find_ids(&node.decls);
+            self.vars.extend(ids);
+        }
+    }
+}
+
+impl Visit<Function> for VarFinder {
+    fn visit(&mut self, _: &Function) {}
+}
+
+impl Visit<ArrowExpr> for VarFinder {
+    fn visit(&mut self, _: &ArrowExpr) {}
+}
+
+impl Visit<Class> for VarFinder {
+    fn visit(&mut self, _: &Class) {}
+}
+
+/// Collects all symbols used in a file, so a new name doesn't shadow or get
+/// shadowed by another binding.
+#[derive(Default)]
+struct SymbolCollector {
+    symbols: HashSet<JsWord>,
+}
+
+noop_visit_type!(SymbolCollector);
+
+impl Visit<Ident> for SymbolCollector {
+    fn visit(&mut self, i: &Ident) {
+        self.symbols.insert(i.sym.clone());
+    }
+}
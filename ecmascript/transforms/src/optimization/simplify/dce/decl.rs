@@ -11,13 +11,49 @@ impl Fold<FnDecl> for Dce<'_> {
 
         if self.marking_phase || self.included.contains(&f.ident.to_id()) {
             f.function.span = f.function.span.apply_mark(self.config.used_mark);
-            f.function.body = self.fold_in_marking_phase(f.function.body);
+            let old = self.marking_phase;
+            self.marking_phase = true;
+            f.function.body = self.fold_body(f.function.body);
+            self.marking_phase = old;
         }
 
         f.fold_children(self)
     }
 }
 
+/// Folds `body` with [Dce::fold_body], as a function body may start with
+/// directives.
+macro_rules! fn_body {
+    ($T:ty) => {
+        impl Fold<$T> for Dce<'_> {
+            fn fold(&mut self, mut node: $T) -> $T {
+                let body = node.body.take();
+                let mut node = node.fold_children(self);
+                node.body = self.fold_body(body);
+                node
+            }
+        }
+    };
+}
+
+fn_body!(Function);
+fn_body!(Constructor);
+fn_body!(GetterProp);
+fn_body!(SetterProp);
+
+impl Fold<ArrowExpr> for Dce<'_> {
+    fn fold(&mut self, node: ArrowExpr) -> ArrowExpr {
+        let params = node.params.fold_with(self);
+        let body = self.fold_body(node.body);
+
+        ArrowExpr {
+            params,
+            body,
+            ..node
+        }
+    }
+}
+
 impl Fold<ClassDecl> for Dce<'_> {
     fn fold(&mut self, mut node: ClassDecl) -> ClassDecl {
         if self.is_marked(node.span()) {
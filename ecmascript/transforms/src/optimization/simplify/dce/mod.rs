@@ -1,7 +1,7 @@
 use self::side_effect::{ImportDetector, SideEffectVisitor};
 use crate::pass::RepeatedJsPass;
 use fxhash::FxHashSet;
-use std::borrow::Cow;
+use std::{borrow::Cow, mem};
 use swc_atoms::JsWord;
 use swc_common::{
     chain,
@@ -65,6 +65,7 @@ pub fn dce<'a>(config: Config<'a>) -> impl RepeatedJsPass + 'a {
             changed: false,
             marking_phase: false,
             import_dropping_phase: false,
+            may_have_directives: false,
         },
         UsedMarkRemover { used_mark }
     )
@@ -117,6 +118,10 @@ struct Dce<'a> {
     /// It means, imports are not marked (as used) nor removed.
     import_dropping_phase: bool,
 
+    /// If true, the next statement list is a program or a function body, so
+    /// it may start with a directive prologue.
+    may_have_directives: bool,
+
     dropped: bool,
 }
 
@@ -144,10 +149,44 @@ where
 {
     fn fold(&mut self, mut items: Vec<T>) -> Vec<T> {
         let old = self.changed;
+        let may_have_directives = mem::replace(&mut self.may_have_directives, false);
 
         let mut preserved = FxHashSet::default();
         preserved.reserve(items.len());
 
+        // Directives like `'use strict'` don't have side effects, but they
+        // change the semantics of the code.
+        if may_have_directives {
+            let used_mark = self.config.used_mark;
+            let mut idx = 0u32;
+            let mut in_prologue = true;
+            items = items.move_map(|item| {
+                let item = match item.try_into_stmt() {
+                    Ok(Stmt::Expr(ExprStmt {
+                        span,
+                        expr: box Expr::Lit(Lit::Str(s)),
+                    })) if in_prologue => {
+                        preserved.insert(idx);
+                        T::from_stmt(Stmt::Expr(ExprStmt {
+                            span: span.apply_mark(used_mark),
+                            expr: box Expr::Lit(Lit::Str(s)),
+                        }))
+                    }
+                    Ok(stmt) => {
+                        in_prologue = false;
+                        T::from_stmt(stmt)
+                    }
+                    Err(item) => {
+                        in_prologue = false;
+                        item
+                    }
+                };
+
+                idx += 1;
+                item
+            });
+        }
+
         loop {
             self.changed = false;
             let mut idx = 0u32;
@@ -273,6 +312,19 @@ impl Dce<'_> {
         node
     }
 
+    /// Folds a program or function body, which may start with directives.
+    pub fn fold_body<T>(&mut self, node: T) -> T
+    where
+        T: FoldWith<Self>,
+    {
+        let old = self.may_have_directives;
+        self.may_have_directives = true;
+        let node = node.fold_with(self);
+        self.may_have_directives = old;
+
+        node
+    }
+
     pub fn drop_imports<T>(&mut self, node: T) -> T
     where
         T: FoldWith<Self>,
@@ -1,6 +1,69 @@
 use super::Dce;
-use swc_common::Fold;
+use swc_common::{Fold, FoldWith};
 use swc_ecma_ast::*;
+use swc_ecma_utils::{find_ids, ident::IdentLike, Id};
+
+impl Fold<Module> for Dce<'_> {
+    fn fold(&mut self, node: Module) -> Module {
+        // Exported bindings are used by other modules, even if they look unused
+        // in this module, so they are marked as used before anything is dropped.
+        for item in &node.body {
+            let ids: Vec<Id> = match *item {
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ref e)) => {
+                    let ids: Vec<Id> = match e.decl {
+                        Decl::Class(ClassDecl { ref ident, .. })
+                        | Decl::Fn(FnDecl { ref ident, .. }) => vec![ident.to_id()],
+                        Decl::Var(ref v) => find_ids(&v.decls),
+                        _ => continue,
+                    };
+
+                    ids.into_iter()
+                        .filter(|id| self.is_exported(&id.0))
+                        .collect()
+                }
+                ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(NamedExport {
+                    src: None,
+                    ref specifiers,
+                    ..
+                })) => specifiers
+                    .iter()
+                    .filter_map(|s| match *s {
+                        ExportSpecifier::Named(ref s) => {
+                            let exported = s.exported.as_ref().unwrap_or(&s.orig);
+                            if self.is_exported(&exported.sym) {
+                                Some(s.orig.to_id())
+                            } else {
+                                None
+                            }
+                        }
+                        _ => None,
+                    })
+                    .collect(),
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(ExportDefaultExpr {
+                    expr: box Expr::Ident(ref i),
+                    ..
+                })) if self.is_exported(&js_word!("default")) => vec![i.to_id()],
+                _ => continue,
+            };
+
+            self.included.extend(ids);
+        }
+
+        Module {
+            body: self.fold_body(node.body),
+            ..node
+        }
+    }
+}
+
+impl Fold<Script> for Dce<'_> {
+    fn fold(&mut self, node: Script) -> Script {
+        Script {
+            body: self.fold_body(node.body),
+            ..node
+        }
+    }
+}
 
 impl Fold<ImportDecl> for Dce<'_> {
     fn fold(&mut self, mut import: ImportDecl) -> ImportDecl {
@@ -1,17 +1,67 @@
-use self::scope::{Scope, ScopeKind, VarType};
+use self::{
+    never_reassigned::{find_exported, find_never_reassigned, find_written, WriteFinder},
+    scope::{Scope, ScopeKind, VarType},
+    trivial_fn::{find_trivial_fns, TrivialFnFinder},
+};
 use crate::{pass::RepeatedJsPass, scope::IdentType};
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+};
+use swc_atoms::JsWord;
 use swc_common::{
     pass::{CompilerPass, Repeated},
-    Fold, FoldWith, Visit, VisitWith,
+    Fold, FoldWith, Span, Spanned, Visit, VisitWith,
 };
 use swc_ecma_ast::*;
-use swc_ecma_utils::{contains_this_expr, find_ids, ident::IdentLike, undefined, Id};
+use swc_ecma_utils::{
+    contains_this_expr, find_ids, ident::IdentLike, undefined, ExprExt, Id, Known, Unknown,
+    COMMENTS, HANDLER,
+};
 
+mod never_reassigned;
 mod scope;
+mod trivial_fn;
+
+/// Default value of [Config::max_depth].
+pub const DEFAULT_MAX_DEPTH: usize = 1000;
 
 #[derive(Debug, Default)]
-pub struct Config {}
+pub struct Config {
+    /// Inline calls to functions which only return a parameter or a literal,
+    /// like `function id(x) { return x; }`.
+    pub inline_trivial_fns: bool,
+
+    /// Bindings with these names are never inlined.
+    pub deny: Vec<JsWord>,
+
+    /// Bindings with these names are inlined as an alias even if a call
+    /// happens between the declaration of the aliased variable and the alias,
+    /// as long as the aliased variable is initialized with a literal and never
+    /// reassigned.
+    pub allow: Vec<JsWord>,
+
+    /// Logs bindings, constants and inline barriers of the scope when
+    /// switching to the inlining phase of a module, at debug level.
+    pub dump_scope: bool,
+
+    /// Maximum nesting depth of expressions and scopes. Defaults to
+    /// [DEFAULT_MAX_DEPTH].
+    ///
+    /// Deeper nodes are left untouched and an error is reported to
+    /// [HANDLER], instead of overflowing the stack. The output should not be
+    /// used in that case.
+    ///
+    /// Only this pass is guarded. Other recursive passes, the parser and the
+    /// code generator can still overflow the stack on such code.
+    pub max_depth: Option<usize>,
+
+    /// Set to true if [Config::max_depth] is exceeded.
+    pub too_deep: Arc<AtomicBool>,
+}
 
 /// Note: this pass assumes that resolver is invoked before the pass.
 ///
@@ -26,15 +76,45 @@ pub struct Config {}
 ///
 /// Currently all functions are treated as a black box, and all the pass gives
 /// up inlining variables across a function call or a constructor call.
-pub fn inlining(_: Config) -> impl RepeatedJsPass + 'static {
+/// Calls to trivial functions can be inlined with
+/// [Config::inline_trivial_fns].
+///
+/// Top-level `let` and `var` bindings of a module which are initialized with
+/// a literal and never reassigned are inlined like `const`.
+///
+/// A variable annotated with `/* @__NOINLINE__ */` (or `#__NOINLINE__`), as a
+/// leading comment of the name or the initializer, is not inlined. Bindings
+/// can also be excluded by name with [Config::deny].
+///
+/// Bindings used in the body of a `with` statement are not inlined, as the
+/// object may have a property with the same name.
+///
+/// Reads in a branch of an `if` statement with a constant test are not
+/// counted, as the branch is removed by [dead_branch_remover] which runs right
+/// after this pass in [simplifier]. Such reads are still replaced if the
+/// variable is inlined.
+///
+/// [dead_branch_remover]: crate::optimization::simplify::dead_branch_remover
+/// [simplifier]: crate::optimization::simplifier
+pub fn inlining(config: Config) -> impl RepeatedJsPass + 'static {
+    let mut scope = Scope::default();
+    scope.deny = config.deny.into_iter().collect();
+    scope.allow = config.allow.into_iter().collect();
+
     Inlining {
         phase: Phase::Analysis,
         is_first_run: true,
         changed: false,
-        scope: Default::default(),
+        scope,
         var_decl_kind: VarDeclKind::Var,
         ident_type: IdentType::Ref,
         pat_mode: PatFoldingMode::VarDecl,
+        inline_trivial_fns: config.inline_trivial_fns,
+        in_dead_branch: false,
+        dump_scope: config.dump_scope,
+        depth: 0,
+        max_depth: config.max_depth.unwrap_or(DEFAULT_MAX_DEPTH),
+        too_deep: config.too_deep,
     }
 }
 
@@ -69,6 +149,16 @@ struct Inlining<'a> {
     var_decl_kind: VarDeclKind,
     ident_type: IdentType,
     pat_mode: PatFoldingMode,
+    inline_trivial_fns: bool,
+    /// True while folding a branch of an `if` statement whose test is a known
+    /// constant that selects the other branch.
+    in_dead_branch: bool,
+    dump_scope: bool,
+    /// Nesting depth of the current node.
+    depth: usize,
+    max_depth: usize,
+    /// Set if [Inlining::max_depth] is exceeded, which is reported only once.
+    too_deep: Arc<AtomicBool>,
 }
 
 noop_fold_type!(Inlining<'_>);
@@ -84,10 +174,101 @@ enum PatFoldingMode {
 impl Inlining<'_> {
     fn fold_with_child<T>(&mut self, kind: ScopeKind, node: T) -> T
     where
-        T: 'static + for<'any> FoldWith<Inlining<'any>>,
+        T: 'static + for<'any> FoldWith<Inlining<'any>> + Spanned,
     {
         self.with_child(kind, node, |child, node| node.fold_children(child))
     }
+
+    /// Returns true if a node at `span` is nested too deeply to be folded.
+    fn check_depth(&mut self, span: Span) -> bool {
+        if self.depth < self.max_depth {
+            return false;
+        }
+
+        if !self.too_deep.swap(true, Ordering::Relaxed) {
+            HANDLER.with(|handler| {
+                handler
+                    .struct_span_err(
+                        span,
+                        &format!(
+                            "the code is nested too deeply to be optimized (more than {} levels)",
+                            self.max_depth
+                        ),
+                    )
+                    .emit()
+            });
+        }
+
+        true
+    }
+
+    /// Folds a conditional whose test is a literal, e.g. `flag ? a : b` after
+    /// `flag` is inlined as `true`.
+    ///
+    /// The conditional is kept if the dropped branch has side effects.
+    fn fold_const_cond(&mut self, e: CondExpr) -> Expr {
+        let test = match e.test.as_pure_bool() {
+            Known(test) => test,
+            Unknown => return Expr::Cond(e),
+        };
+        let dropped = if test { &e.alt } else { &e.cons };
+        if dropped.may_have_side_effects() {
+            return Expr::Cond(e);
+        }
+
+        self.changed = true;
+        if test {
+            *e.cons
+        } else {
+            *e.alt
+        }
+    }
+}
+
+impl Inlining<'_> {
+    fn analyze_root<T>(&mut self, node: &T)
+    where
+        T: VisitWith<TrivialFnFinder> + VisitWith<WriteFinder>,
+    {
+        if self.inline_trivial_fns {
+            let deny = &self.scope.deny;
+            self.scope.trivial_fns = find_trivial_fns(node);
+            self.scope
+                .trivial_fns
+                .retain(|id, _| !deny.contains(&id.0));
+        }
+        if !self.scope.allow.is_empty() {
+            self.scope.written = find_written(node);
+        }
+    }
+}
+
+impl Fold<Module> for Inlining<'_> {
+    fn fold(&mut self, node: Module) -> Module {
+        self.analyze_root(&node);
+        if self.is_first_run {
+            self.scope.exported = find_exported(&node);
+
+            let deny = &self.scope.deny;
+            let never_reassigned = find_never_reassigned(&node)
+                .into_iter()
+                .filter(|(id, _)| !deny.contains(&id.0))
+                .collect::<Vec<_>>();
+            self.scope
+                .constants
+                .extend(never_reassigned.into_iter().map(|(id, e)| (id, Some(e))));
+        }
+
+        node.fold_children(self)
+    }
+}
+
+impl Fold<Script> for Inlining<'_> {
+    fn fold(&mut self, node: Script) -> Script {
+        self.analyze_root(&node);
+
+        node.fold_children(self)
+    }
 }
 
 impl Fold<Vec<ModuleItem>> for Inlining<'_> {
@@ -98,6 +279,9 @@ impl Fold<Vec<ModuleItem>> for Inlining<'_> {
         items = items.fold_children(self);
 
         log::debug!("Switching to Inlining phase");
+        if self.dump_scope {
+            log::debug!("{}", self.scope.dump());
+        }
 
         // Inline
         self.phase = Phase::Inlining;
@@ -141,14 +325,63 @@ impl Fold<VarDecl> for Inlining<'_> {
     }
 }
 
+/// Returns true if `node` is annotated with `@__NOINLINE__`.
+pub(super) fn is_noinline(node: &VarDeclarator) -> bool {
+    if !COMMENTS.is_set() {
+        return false;
+    }
+
+    let positions = node
+        .init
+        .as_ref()
+        .map(|init| init.span().lo())
+        .into_iter()
+        .chain(Some(node.name.span().lo()));
+
+    COMMENTS.with(|comments| {
+        positions.into_iter().any(|pos| {
+            comments.leading_comments(pos).map_or(false, |cmts| {
+                cmts.iter().any(|c| {
+                    let text = c.text.trim();
+                    text == "@__NOINLINE__" || text == "#__NOINLINE__"
+                })
+            })
+        })
+    })
+}
+
 impl Fold<VarDeclarator> for Inlining<'_> {
     fn fold(&mut self, mut node: VarDeclarator) -> VarDeclarator {
         let kind = VarType::Var(self.var_decl_kind);
+        // The initializer of an exported `let` or `var` is not moved, as other
+        // modules read the binding.
+        let no_inline = self.phase == Phase::Analysis
+            && (is_noinline(&node) || {
+                let ids: Vec<Id> = find_ids(&node.name);
+                ids.iter().any(|id| {
+                    self.scope.is_denied(id)
+                        || (self.var_decl_kind != VarDeclKind::Const
+                            && self.scope.is_exported(id))
+                })
+            });
         node.init = node.init.fold_with(self);
 
         self.pat_mode = PatFoldingMode::VarDecl;
 
         match self.phase {
+            Phase::Analysis if no_inline => {
+                let ids: Vec<Id> = find_ids(&node.name);
+                for id in ids {
+                    if self.var_decl_kind == VarDeclKind::Const {
+                        if self.is_first_run {
+                            self.scope.constants.insert(id, None);
+                        }
+                    } else {
+                        self.declare(id.clone(), None, true, kind);
+                        self.scope.prevent_inline(&id);
+                    }
+                }
+            }
             Phase::Analysis => match node.name {
                 Pat::Ident(ref name) => {
                     //
@@ -189,6 +422,18 @@ impl Fold<VarDeclarator> for Inlining<'_> {
                                     self.scope.prevent_inline(&name.to_id());
                                     return node;
                                 }
+
+                                // A getter may be invoked, so the read should not be
+                                // moved or duplicated.
+                                let mut v = UnknownMemberReadFinder {
+                                    scope: &self.scope,
+                                    found: false,
+                                };
+                                e.visit_with(&mut v);
+                                if v.found {
+                                    self.scope.prevent_inline(&name.to_id());
+                                    return node;
+                                }
                             }
                         }
                     }
@@ -366,8 +611,18 @@ impl Fold<IfStmt> for Inlining<'_> {
     fn fold(&mut self, mut node: IfStmt) -> IfStmt {
         node.test = node.test.fold_with(self);
 
+        let (cons_dead, alt_dead) = match node.test.as_pure_bool() {
+            Known(true) => (false, true),
+            Known(false) => (true, false),
+            Unknown => (false, false),
+        };
+        let in_dead_branch = self.in_dead_branch;
+
+        self.in_dead_branch = in_dead_branch || cons_dead;
         node.cons = self.fold_with_child(ScopeKind::Cond, node.cons);
+        self.in_dead_branch = in_dead_branch || alt_dead;
         node.alt = self.fold_with_child(ScopeKind::Cond, node.alt);
+        self.in_dead_branch = in_dead_branch;
 
         node
     }
@@ -449,12 +704,12 @@ impl Fold<AssignExpr> for Inlining<'_> {
                     match *left {
                         Expr::Member(ref left) => {
                             log::trace!("Assign to member expression!");
-                            let mut v = IdentListVisitor {
+                            left.visit_with(&mut IdentListVisitor {
                                 scope: &mut self.scope,
-                            };
-
-                            left.visit_with(&mut v);
-                            e.right.visit_with(&mut v);
+                            });
+                            e.right.visit_with(&mut ReadIdentListVisitor {
+                                scope: &mut self.scope,
+                            });
                         }
 
                         _ => {}
@@ -471,12 +726,12 @@ impl Fold<AssignExpr> for Inlining<'_> {
         match e.op {
             op!("=") => {}
             _ => {
-                let mut v = IdentListVisitor {
+                e.left.visit_with(&mut IdentListVisitor {
                     scope: &mut self.scope,
-                };
-
-                e.left.visit_with(&mut v);
-                e.right.visit_with(&mut v)
+                });
+                e.right.visit_with(&mut ReadIdentListVisitor {
+                    scope: &mut self.scope,
+                })
             }
         }
 
@@ -526,9 +781,39 @@ impl Fold<MemberExpr> for Inlining<'_> {
     }
 }
 
+impl Inlining<'_> {
+    /// Returns true if `e` is `x = x` where `x` is a declared binding which can
+    /// be reassigned, so `e` is the same as reading `x`.
+    fn is_self_assign(&self, e: &AssignExpr) -> bool {
+        let left = match e.left {
+            PatOrExpr::Pat(box Pat::Ident(ref i)) | PatOrExpr::Expr(box Expr::Ident(ref i)) => i,
+            _ => return false,
+        };
+        let right = match *e.right {
+            Expr::Ident(ref i) => i,
+            _ => return false,
+        };
+        if left.to_id() != right.to_id() {
+            return false;
+        }
+
+        // Assignments to a constant throw, and a global may have a setter.
+        match self.scope.find_binding(&left.to_id()) {
+            Some(var) => var.kind != VarType::Var(VarDeclKind::Const),
+            None => false,
+        }
+    }
+}
+
 impl Fold<Expr> for Inlining<'_> {
     fn fold(&mut self, node: Expr) -> Expr {
+        if self.check_depth(node.span()) {
+            return node;
+        }
+
+        self.depth += 1;
         let node: Expr = node.fold_children(self);
+        self.depth -= 1;
 
         // Codes like
         //
@@ -546,6 +831,11 @@ impl Fold<Expr> for Inlining<'_> {
         if self.phase == Phase::Inlining {
             match node {
                 Expr::Assign(e @ AssignExpr { op: op!("="), .. }) => {
+                    if self.is_self_assign(&e) {
+                        self.changed = true;
+                        return *e.right;
+                    }
+
                     match e.left {
                         PatOrExpr::Pat(box Pat::Ident(ref i))
                         | PatOrExpr::Expr(box Expr::Ident(ref i)) => {
@@ -565,11 +855,39 @@ impl Fold<Expr> for Inlining<'_> {
                     return Expr::Assign(e);
                 }
 
+                Expr::Call(call) => return self.inline_trivial_fn_call(call),
+
                 _ => {}
             }
         }
 
         match node {
+            // `typeof` of an inlined primitive constant.
+            Expr::Unary(UnaryExpr {
+                span,
+                op: op!("typeof"),
+                arg: box Expr::Lit(ref lit),
+            }) => {
+                let ty = match lit {
+                    Lit::Str(..) => js_word!("string"),
+                    Lit::Num(..) => js_word!("number"),
+                    Lit::Bool(..) => js_word!("boolean"),
+                    _ => return node,
+                };
+                self.changed = true;
+
+                return Expr::Lit(Lit::Str(Str {
+                    span,
+                    value: ty,
+                    has_escape: false,
+                }));
+            }
+
+            Expr::Cond(e @ CondExpr {
+                test: box Expr::Lit(..),
+                ..
+            }) if self.phase == Phase::Inlining => return self.fold_const_cond(e),
+
             Expr::Ident(ref i) => {
                 let id = i.to_id();
                 if self.is_first_run {
@@ -581,7 +899,10 @@ impl Fold<Expr> for Inlining<'_> {
 
                 match self.phase {
                     Phase::Analysis => {
-                        self.scope.add_read(&id);
+                        // Reads in a dead branch are not counted, so they don't
+                        // prevent inlining of a binding which is read only once
+                        // in live code.
+                        self.scope.add_read(&id, self.in_dead_branch);
                     }
                     Phase::Inlining => {
                         log::trace!("Trying to inline: {:?}", id);
@@ -765,8 +1086,20 @@ impl Fold<ForStmt> for Inlining<'_> {
         node.update = node.update.fold_with(self);
         node.body = self.fold_with_child(ScopeKind::Loop, node.body);
 
-        if node.init.is_none() && node.test.is_none() && node.update.is_none() {
-            self.scope.store_inline_barrier(self.phase);
+        // `for(;;)` is not a barrier by itself. Only bindings written in the
+        // body can differ across iterations, and calls in the body store their
+        // own barriers.
+        if self.phase == Phase::Analysis
+            && node.init.is_none()
+            && node.test.is_none()
+            && node.update.is_none()
+        {
+            let mut v = WrittenIdVisitor::default();
+            node.body.visit_with(&mut v);
+
+            for id in v.ids {
+                self.scope.prevent_inline(&id);
+            }
         }
 
         node
@@ -815,6 +1148,20 @@ impl Fold<BinExpr> for Inlining<'_> {
     }
 }
 
+impl Fold<WithStmt> for Inlining<'_> {
+    fn fold(&mut self, node: WithStmt) -> WithStmt {
+        let obj = node.obj.fold_with(self);
+
+        // A read in the body may be a property of `obj`, so the body is left as
+        // is and bindings used in it are not inlined anywhere.
+        node.body.visit_with(&mut IdentListVisitor {
+            scope: &mut self.scope,
+        });
+
+        WithStmt { obj, ..node }
+    }
+}
+
 impl Fold<TryStmt> for Inlining<'_> {
     fn fold(&mut self, node: TryStmt) -> TryStmt {
         node.block.visit_with(&mut IdentListVisitor {
@@ -850,3 +1197,92 @@ impl Visit<Ident> for IdentListVisitor<'_, '_> {
         self.scope.add_write(&node.to_id(), true);
     }
 }
+
+/// Finds member expressions which may invoke a getter.
+///
+/// Only non-computed reads of properties of objects known to be plain object
+/// literals are considered safe. Functions are not visited, as their bodies are
+/// not evaluated where they are defined.
+struct UnknownMemberReadFinder<'a, 'b> {
+    scope: &'a Scope<'b>,
+    found: bool,
+}
+
+impl Visit<MemberExpr> for UnknownMemberReadFinder<'_, '_> {
+    fn visit(&mut self, node: &MemberExpr) {
+        match node.obj {
+            ExprOrSuper::Expr(box Expr::Ident(ref obj))
+                if !node.computed && self.scope.is_plain_object(&obj.to_id()) => {}
+            _ => self.found = true,
+        }
+    }
+}
+
+impl Visit<Function> for UnknownMemberReadFinder<'_, '_> {
+    fn visit(&mut self, _: &Function) {}
+}
+
+impl Visit<ArrowExpr> for UnknownMemberReadFinder<'_, '_> {
+    fn visit(&mut self, _: &ArrowExpr) {}
+}
+
+/// Collects identifiers which are assigned or updated.
+#[derive(Debug, Default)]
+struct WrittenIdVisitor {
+    ids: Vec<Id>,
+}
+
+impl Visit<AssignExpr> for WrittenIdVisitor {
+    fn visit(&mut self, node: &AssignExpr) {
+        match node.left {
+            PatOrExpr::Expr(box Expr::Ident(ref i))
+            | PatOrExpr::Pat(box Pat::Expr(box Expr::Ident(ref i))) => self.ids.push(i.to_id()),
+            PatOrExpr::Pat(ref p) => self.ids.extend(find_ids::<_, Id>(&**p)),
+            _ => {}
+        }
+
+        node.left.visit_with(self);
+        node.right.visit_with(self);
+    }
+}
+
+impl Visit<UpdateExpr> for WrittenIdVisitor {
+    fn visit(&mut self, node: &UpdateExpr) {
+        match node.arg {
+            box Expr::Ident(ref i) => self.ids.push(i.to_id()),
+            _ => node.arg.visit_with(self),
+        }
+    }
+}
+
+/// Same as [IdentListVisitor], but for expressions in a read position.
+///
+/// Reading a property does not modify the object, so the object of a
+/// non-computed member expression is not marked as written.
+#[derive(Debug)]
+struct ReadIdentListVisitor<'a, 'b> {
+    scope: &'a mut Scope<'b>,
+}
+
+impl Visit<MemberExpr> for ReadIdentListVisitor<'_, '_> {
+    fn visit(&mut self, node: &MemberExpr) {
+        if node.computed {
+            node.obj.visit_with(&mut IdentListVisitor {
+                scope: &mut self.scope,
+            });
+            node.prop.visit_with(self);
+            return;
+        }
+
+        match node.obj {
+            ExprOrSuper::Expr(box Expr::Ident(..)) => {}
+            _ => node.obj.visit_with(self),
+        }
+    }
+}
+
+impl Visit<Ident> for ReadIdentListVisitor<'_, '_> {
+    fn visit(&mut self, node: &Ident) {
+        self.scope.add_write(&node.to_id(), true);
+    }
+}
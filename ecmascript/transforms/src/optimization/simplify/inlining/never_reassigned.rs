@@ -0,0 +1,262 @@
+use super::is_noinline;
+use fxhash::{FxHashMap, FxHashSet};
+use swc_common::{BytePos, Visit, VisitWith};
+use swc_ecma_ast::*;
+use swc_ecma_utils::{find_ids, ident::IdentLike, Id};
+
+/// Finds top-level `let` and `var` bindings of a module which are initialized
+/// with a literal and never reassigned, so they can be inlined like `const`.
+///
+/// Bindings of a script are not included, as other scripts can reassign them.
+///
+/// A binding is not included if
+///
+///  - it's read before the declaration, which is a TDZ error for `let` and
+///    reads `undefined` for `var`. A read in a function declaration counts as
+///    a read at the start of the enclosing scope, as the function is hoisted
+///    and may be called before the declaration.
+///  - it's exported, as a live binding is visible to other modules.
+pub(super) fn find_never_reassigned(module: &Module) -> FxHashMap<Id, Expr> {
+    let mut candidates = FxHashMap::default();
+
+    for item in &module.body {
+        let decl = match *item {
+            ModuleItem::Stmt(Stmt::Decl(Decl::Var(ref decl))) => decl,
+            _ => continue,
+        };
+        if decl.kind == VarDeclKind::Const {
+            continue;
+        }
+
+        for declarator in &decl.decls {
+            if is_noinline(declarator) {
+                continue;
+            }
+
+            let i = match declarator.name {
+                Pat::Ident(ref i) => i,
+                _ => continue,
+            };
+
+            match declarator.init {
+                Some(box Expr::Lit(Lit::Regex(..))) | Some(box Expr::Lit(Lit::JSXText(..))) => {}
+                Some(box Expr::Lit(ref lit)) => {
+                    candidates.insert(i.to_id(), (declarator.span.lo(), lit.clone()));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if candidates.is_empty() {
+        return Default::default();
+    }
+
+    let mut v = WriteFinder::default();
+    module.visit_with(&mut v);
+
+    let WriteFinder {
+        written, reads, ..
+    } = v;
+
+    candidates
+        .into_iter()
+        .filter(|(id, (decl_pos, _))| {
+            !written.contains(id)
+                && reads
+                    .get(id)
+                    .map_or(true, |first_read| *first_read > *decl_pos)
+        })
+        .map(|(id, (_, lit))| (id, Expr::Lit(lit)))
+        .collect()
+}
+
+/// Finds bindings which are reassigned, declared more than once or exported.
+pub(super) fn find_written<T>(node: &T) -> FxHashSet<Id>
+where
+    T: VisitWith<WriteFinder>,
+{
+    let mut v = WriteFinder::default();
+    node.visit_with(&mut v);
+
+    v.written
+}
+
+/// Finds bindings exported by `module`, which are read by other modules.
+pub(super) fn find_exported(module: &Module) -> FxHashSet<Id> {
+    let mut v = ExportFinder::default();
+    module.visit_with(&mut v);
+
+    v.exported
+}
+
+#[derive(Debug, Default)]
+struct ExportFinder {
+    exported: FxHashSet<Id>,
+}
+
+noop_visit_type!(ExportFinder);
+
+impl Visit<ExportNamedSpecifier> for ExportFinder {
+    fn visit(&mut self, node: &ExportNamedSpecifier) {
+        self.exported.insert(node.orig.to_id());
+    }
+}
+
+impl Visit<ExportDecl> for ExportFinder {
+    fn visit(&mut self, node: &ExportDecl) {
+        if let Decl::Var(ref decl) = node.decl {
+            let ids: Vec<Id> = find_ids(&decl.decls);
+            self.exported.extend(ids);
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(super) struct WriteFinder {
+    declared: FxHashSet<Id>,
+    written: FxHashSet<Id>,
+    /// Position of the first read.
+    reads: FxHashMap<Id, BytePos>,
+    /// Start of the innermost block, where function declarations in it are
+    /// hoisted to.
+    scope_start: BytePos,
+    /// Set while visiting a function declaration to the position it's hoisted
+    /// to.
+    hoisted_to: Option<BytePos>,
+}
+
+noop_visit_type!(WriteFinder);
+
+impl WriteFinder {
+    fn declare(&mut self, id: Id) {
+        if !self.declared.insert(id.clone()) {
+            self.written.insert(id);
+        }
+    }
+}
+
+impl Visit<Expr> for WriteFinder {
+    fn visit(&mut self, node: &Expr) {
+        if let Expr::Ident(ref i) = *node {
+            let pos = match self.hoisted_to {
+                Some(pos) => pos,
+                None => i.span.lo(),
+            };
+            let first_read = self.reads.entry(i.to_id()).or_insert(pos);
+            if pos < *first_read {
+                *first_read = pos;
+            }
+        }
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<BlockStmt> for WriteFinder {
+    fn visit(&mut self, node: &BlockStmt) {
+        let old = self.scope_start;
+        self.scope_start = node.span.lo();
+        node.visit_children(self);
+        self.scope_start = old;
+    }
+}
+
+impl Visit<FnDecl> for WriteFinder {
+    fn visit(&mut self, node: &FnDecl) {
+        self.declare(node.ident.to_id());
+
+        // A function nested in a hoisted function may run as early as the
+        // outer one.
+        let old = self.hoisted_to;
+        self.hoisted_to = Some(old.unwrap_or(self.scope_start));
+        node.function.visit_with(self);
+        self.hoisted_to = old;
+    }
+}
+
+impl Visit<ClassDecl> for WriteFinder {
+    fn visit(&mut self, node: &ClassDecl) {
+        self.declare(node.ident.to_id());
+
+        node.class.visit_with(self);
+    }
+}
+
+impl Visit<VarDeclarator> for WriteFinder {
+    fn visit(&mut self, node: &VarDeclarator) {
+        let ids: Vec<Id> = find_ids(&node.name);
+        for id in ids {
+            self.declare(id);
+        }
+
+        node.init.visit_with(self);
+    }
+}
+
+impl Visit<ExportNamedSpecifier> for WriteFinder {
+    fn visit(&mut self, node: &ExportNamedSpecifier) {
+        self.written.insert(node.orig.to_id());
+    }
+}
+
+impl Visit<ExportDecl> for WriteFinder {
+    fn visit(&mut self, node: &ExportDecl) {
+        if let Decl::Var(ref decl) = node.decl {
+            let ids: Vec<Id> = find_ids(&decl.decls);
+            self.written.extend(ids);
+        }
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<AssignExpr> for WriteFinder {
+    fn visit(&mut self, node: &AssignExpr) {
+        match node.left {
+            PatOrExpr::Expr(box Expr::Ident(ref i))
+            | PatOrExpr::Pat(box Pat::Expr(box Expr::Ident(ref i))) => {
+                self.written.insert(i.to_id());
+            }
+            PatOrExpr::Pat(ref p) => {
+                let ids: Vec<Id> = find_ids(p);
+                self.written.extend(ids);
+            }
+            _ => {}
+        }
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<UpdateExpr> for WriteFinder {
+    fn visit(&mut self, node: &UpdateExpr) {
+        if let Expr::Ident(ref i) = *node.arg {
+            self.written.insert(i.to_id());
+        }
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<ForInStmt> for WriteFinder {
+    fn visit(&mut self, node: &ForInStmt) {
+        if let VarDeclOrPat::Pat(ref p) = node.left {
+            let ids: Vec<Id> = find_ids(p);
+            self.written.extend(ids);
+        }
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<ForOfStmt> for WriteFinder {
+    fn visit(&mut self, node: &ForOfStmt) {
+        if let VarDeclOrPat::Pat(ref p) = node.left {
+            let ids: Vec<Id> = find_ids(p);
+            self.written.extend(ids);
+        }
+
+        node.visit_children(self);
+    }
+}
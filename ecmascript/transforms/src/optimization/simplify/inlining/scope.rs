@@ -1,12 +1,14 @@
-use super::{Inlining, Phase};
+use super::{trivial_fn::TrivialFn, Inlining, Phase};
 use fxhash::{FxBuildHasher, FxHashMap, FxHashSet};
 use indexmap::map::{Entry, IndexMap};
 use std::{
     borrow::Cow,
     cell::{Cell, RefCell},
     collections::VecDeque,
+    fmt::Write,
 };
-use swc_atoms::js_word;
+use swc_atoms::{js_word, JsWord};
+use swc_common::Spanned;
 use swc_ecma_ast::*;
 use swc_ecma_utils::{ident::IdentLike, Id};
 
@@ -37,7 +39,12 @@ impl Inlining<'_> {
     pub(super) fn with_child<F, T>(&mut self, kind: ScopeKind, node: T, op: F) -> T
     where
         F: for<'any> FnOnce(&mut Inlining<'any>, T) -> T,
+        T: Spanned,
     {
+        if self.check_depth(node.span()) {
+            return node;
+        }
+
         let (node, unresolved_usages, bindings) = {
             let mut child = Inlining {
                 phase: self.phase,
@@ -47,6 +54,12 @@ impl Inlining<'_> {
                 var_decl_kind: VarDeclKind::Var,
                 ident_type: self.ident_type,
                 pat_mode: self.pat_mode,
+                inline_trivial_fns: self.inline_trivial_fns,
+                in_dead_branch: self.in_dead_branch,
+                dump_scope: self.dump_scope,
+                depth: self.depth + 1,
+                max_depth: self.max_depth,
+                too_deep: self.too_deep.clone(),
             };
 
             let node = op(&mut child, node);
@@ -135,7 +148,8 @@ impl Inlining<'_> {
             _ => None,
         };
 
-        let is_inline_prevented = self.scope.should_prevent_inline_because_of_scope(&id)
+        let is_inline_prevented = self.scope.is_denied(&id)
+            || self.scope.should_prevent_inline_because_of_scope(&id)
             || match init {
                 Some(ref e) => self.scope.is_inline_prevented(&e),
                 _ => false,
@@ -211,11 +225,16 @@ impl Inlining<'_> {
             if let Some((value_idx, vi)) = value_idx {
                 log::trace!("\tdeclare: {} -> {}", idx, value_idx);
 
+                // A barrier stored at `blocker` means that the call happened after
+                // `bindings[..blocker]` were declared. So a barrier recorded
+                // before the declaration of the value (e.g. a call in a
+                // preceding `if` branch) does not separate the value and the
+                // alias.
                 let barrier_exists = (|| {
                     for &blocker in self.scope.inline_barriers.borrow().iter() {
-                        if value_idx <= blocker && blocker <= idx {
+                        if value_idx < blocker && blocker <= idx {
                             return true;
-                        } else if idx <= blocker && blocker <= value_idx {
+                        } else if idx < blocker && blocker <= value_idx {
                             return true;
                         }
                     }
@@ -223,6 +242,18 @@ impl Inlining<'_> {
                     false
                 })();
 
+                // A call can't change the value of a variable which is
+                // initialized with a literal and never reassigned.
+                let barrier_exists = barrier_exists
+                    && !(self.scope.is_allowed(&id)
+                        && !self.scope.is_written(&vi)
+                        && self.scope.find_binding(&vi).map_or(false, |v| {
+                            match *v.value.borrow() {
+                                Some(Expr::Lit(..)) => true,
+                                _ => false,
+                            }
+                        }));
+
                 if value_idx > idx || barrier_exists {
                     log::trace!("Variable use before declaration: {:?}", id);
                     self.scope.prevent_inline(&id);
@@ -246,6 +277,26 @@ pub(super) struct Scope<'a> {
 
     /// Simple optimization. We don't need complex scope analysis.
     pub constants: FxHashMap<Id, Option<Expr>>,
+
+    /// Only the root scope has entries.
+    pub trivial_fns: FxHashMap<Id, TrivialFn>,
+
+    /// Only the root scope has entries.
+    pub deny: FxHashSet<JsWord>,
+
+    /// Only the root scope has entries.
+    pub allow: FxHashSet<JsWord>,
+
+    /// Bindings which are reassigned anywhere. Computed only if `allow` is not
+    /// empty.
+    ///
+    /// Only the root scope has entries.
+    pub written: FxHashSet<Id>,
+
+    /// Bindings exported by the module.
+    ///
+    /// Only the root scope has entries.
+    pub exported: FxHashSet<Id>,
 }
 
 impl<'a> Scope<'a> {
@@ -313,7 +364,7 @@ impl<'a> Scope<'a> {
         None
     }
 
-    fn read_prevents_inlining(&self, id: &Id) -> bool {
+    fn read_prevents_inlining(&self, id: &Id, in_dead_branch: bool) -> bool {
         log::trace!("read_prevents_inlining({:?})", id);
 
         if let Some(v) = self.find_binding(id) {
@@ -348,7 +399,7 @@ impl<'a> Scope<'a> {
                         log::debug!("{}: variable access from a nested function detected", id.0);
                         return true;
                     }
-                    ScopeKind::Loop | ScopeKind::Cond => {
+                    ScopeKind::Loop | ScopeKind::Cond if !in_dead_branch => {
                         return true;
                     }
                     _ => {}
@@ -360,8 +411,10 @@ impl<'a> Scope<'a> {
         false
     }
 
-    pub fn add_read(&mut self, id: &Id) {
-        if self.read_prevents_inlining(id) {
+    /// A read in a dead branch is not reflected in [VarInfo::read_cnt] and it
+    /// does not prevent inlining just because it's conditional.
+    pub fn add_read(&mut self, id: &Id, in_dead_branch: bool) {
+        if self.read_prevents_inlining(id, in_dead_branch) {
             log::debug!("prevent inlining because of read: {}", id.0);
 
             self.prevent_inline(id)
@@ -372,7 +425,9 @@ impl<'a> Scope<'a> {
         }
 
         if let Some(var_info) = self.find_binding(id) {
-            var_info.read_cnt.set(var_info.read_cnt.get() + 1);
+            if !in_dead_branch {
+                var_info.read_cnt.set(var_info.read_cnt.get() + 1);
+            }
             if var_info.hoisted.get() {
                 var_info.inline_prevented.set(true);
             }
@@ -510,6 +565,65 @@ impl<'a> Scope<'a> {
         self.parent.and_then(|parent| parent.find_constant(id))
     }
 
+    pub fn is_denied(&self, id: &Id) -> bool {
+        match self.parent {
+            None => self.deny.contains(&id.0),
+            Some(p) => p.is_denied(id),
+        }
+    }
+
+    pub fn is_exported(&self, id: &Id) -> bool {
+        match self.parent {
+            None => self.exported.contains(id),
+            Some(p) => p.is_exported(id),
+        }
+    }
+
+    pub fn is_allowed(&self, id: &Id) -> bool {
+        match self.parent {
+            None => self.allow.contains(&id.0),
+            Some(p) => p.is_allowed(id),
+        }
+    }
+
+    pub fn is_written(&self, id: &Id) -> bool {
+        match self.parent {
+            None => self.written.contains(id),
+            Some(p) => p.is_written(id),
+        }
+    }
+
+    /// Returns true if `id` is a binding initialized with an object literal
+    /// which can't have accessors, i.e. properties of it can be read without
+    /// side effects.
+    pub fn is_plain_object(&self, id: &Id) -> bool {
+        if self.is_written(id) {
+            return false;
+        }
+
+        let v = match self.find_binding(id) {
+            Some(v) => v,
+            None => return false,
+        };
+        if v.inline_prevented.get() {
+            return false;
+        }
+
+        let value = v.value.borrow();
+        match *value {
+            Some(Expr::Object(ObjectLit { ref props, .. })) => props.iter().all(is_plain_prop),
+            _ => false,
+        }
+    }
+
+    pub fn find_trivial_fn(&self, id: &Id) -> Option<&TrivialFn> {
+        if let Some(f) = self.trivial_fns.get(id) {
+            return Some(f);
+        }
+
+        self.parent.and_then(|parent| parent.find_trivial_fn(id))
+    }
+
     pub fn mark_this_sensitive(&self, callee: &Expr) {
         match callee {
             Expr::Ident(ref i) => {
@@ -621,6 +735,78 @@ impl<'a> Scope<'a> {
         }
     }
 
+    /// Returns a human-readable tree of bindings, constants and inline
+    /// barriers of this scope and its ancestors.
+    ///
+    /// Writes are not counted, so only reads are printed.
+    pub fn dump(&self) -> String {
+        let mut buf = String::new();
+        self.dump_to(&mut buf);
+        buf
+    }
+
+    /// Returns the depth of this scope.
+    fn dump_to(&self, buf: &mut String) -> usize {
+        let depth = match self.parent {
+            Some(p) => p.dump_to(buf) + 1,
+            None => 0,
+        };
+        let indent = "  ".repeat(depth);
+
+        let _ = writeln!(
+            buf,
+            "{}{:?} scope (barriers: {:?})",
+            indent,
+            self.kind,
+            self.inline_barriers.borrow()
+        );
+
+        for (idx, (id, v)) in self.bindings.iter().enumerate() {
+            let _ = write!(
+                buf,
+                "{}  [{}] {}{:?}: {:?}, reads: {}",
+                indent,
+                idx,
+                id.0,
+                id.1,
+                v.kind,
+                v.read_cnt.get()
+            );
+            if v.is_inline_prevented() {
+                buf.push_str(", inline prevented");
+            }
+            if v.read_from_nested_scope.get() {
+                buf.push_str(", read from nested scope");
+            }
+            if v.hoisted.get() {
+                buf.push_str(", hoisted");
+            }
+            if v.is_undefined.get() {
+                buf.push_str(", undefined");
+            }
+            buf.push('\n');
+        }
+
+        let mut constants = self.constants.iter().collect::<Vec<_>>();
+        constants.sort_by(|a, b| (&*(a.0).0).cmp(&*(b.0).0));
+        for (id, value) in constants {
+            let _ = writeln!(
+                buf,
+                "{}  const {}{:?}{}",
+                indent,
+                id.0,
+                id.1,
+                if value.is_some() {
+                    ""
+                } else {
+                    ", inline prevented"
+                }
+            );
+        }
+
+        depth
+    }
+
     pub fn has_same_this(&self, id: &Id, init: Option<&Expr>) -> bool {
         if let Some(v) = self.find_binding(id) {
             if v.this_sensitive.get() {
@@ -673,3 +859,18 @@ impl VarInfo {
         false
     }
 }
+
+/// Returns true if `p` is a data property which doesn't change the prototype.
+fn is_plain_prop(p: &PropOrSpread) -> bool {
+    let key = match *p {
+        PropOrSpread::Prop(box Prop::KeyValue(KeyValueProp { ref key, .. })) => key,
+        _ => return false,
+    };
+
+    match *key {
+        PropName::Ident(ref i) => &*i.sym != "__proto__",
+        PropName::Str(ref s) => &*s.value != "__proto__",
+        PropName::Num(..) => true,
+        PropName::Computed(..) => false,
+    }
+}
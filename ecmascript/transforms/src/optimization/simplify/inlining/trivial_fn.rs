@@ -0,0 +1,216 @@
+use super::Inlining;
+use fxhash::{FxHashMap, FxHashSet};
+use swc_common::{Visit, VisitWith};
+use swc_ecma_ast::*;
+use swc_ecma_utils::{find_ids, ident::IdentLike, undefined, ExprExt, Id};
+
+/// A function which returns a parameter or a literal without doing anything
+/// else.
+///
+/// As the body cannot reference `this`, `arguments` or a free variable, a
+/// call to such function can be replaced with the returned value.
+#[derive(Debug, Clone)]
+pub(super) enum TrivialFn {
+    /// `function (a, b) { return b; }`
+    Param(usize),
+    /// `function () { return 1; }`
+    Lit(Lit),
+}
+
+/// Finds trivial functions which are never reassigned.
+pub(super) fn find_trivial_fns<T>(node: &T) -> FxHashMap<Id, TrivialFn>
+where
+    T: VisitWith<TrivialFnFinder>,
+{
+    let mut v = TrivialFnFinder::default();
+    node.visit_with(&mut v);
+
+    let TrivialFnFinder {
+        mut fns, written, ..
+    } = v;
+    fns.retain(|id, _| !written.contains(id));
+
+    fns
+}
+
+impl Inlining<'_> {
+    /// Replaces a call to a trivial function with the returned value.
+    ///
+    /// Arguments other than the returned one are dropped, so this is done
+    /// only if they don't have side effects.
+    pub(super) fn inline_trivial_fn_call(&mut self, call: CallExpr) -> Expr {
+        let f = match call.callee {
+            ExprOrSuper::Expr(box Expr::Ident(ref callee)) => {
+                match self.scope.find_trivial_fn(&callee.to_id()) {
+                    Some(f) => f.clone(),
+                    None => return Expr::Call(call),
+                }
+            }
+            _ => return Expr::Call(call),
+        };
+
+        if call.args.iter().any(|arg| arg.spread.is_some()) {
+            return Expr::Call(call);
+        }
+
+        let returned = match f {
+            TrivialFn::Param(idx) => Some(idx),
+            TrivialFn::Lit(..) => None,
+        };
+
+        let args_have_side_effects = call
+            .args
+            .iter()
+            .enumerate()
+            .any(|(idx, arg)| Some(idx) != returned && arg.expr.may_have_side_effects());
+        if args_have_side_effects {
+            return Expr::Call(call);
+        }
+
+        log::trace!("Inlining a call to a trivial function");
+        self.changed = true;
+
+        match f {
+            TrivialFn::Param(idx) => match call.args.into_iter().nth(idx) {
+                Some(arg) => *arg.expr,
+                None => *undefined(call.span),
+            },
+            TrivialFn::Lit(lit) => Expr::Lit(lit),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub(super) struct TrivialFnFinder {
+    fns: FxHashMap<Id, TrivialFn>,
+    declared: FxHashSet<Id>,
+    written: FxHashSet<Id>,
+}
+
+noop_visit_type!(TrivialFnFinder);
+
+impl TrivialFnFinder {
+    fn declare(&mut self, id: Id) {
+        if !self.declared.insert(id.clone()) {
+            self.written.insert(id);
+        }
+    }
+}
+
+fn as_trivial_fn(f: &Function) -> Option<TrivialFn> {
+    if f.is_async || f.is_generator || !f.decorators.is_empty() {
+        return None;
+    }
+
+    let mut params = vec![];
+    for param in &f.params {
+        match param.pat {
+            Pat::Ident(ref i) if param.decorators.is_empty() => params.push(i.to_id()),
+            _ => return None,
+        }
+    }
+
+    let stmt = match &*f.body.as_ref()?.stmts {
+        [stmt] => stmt,
+        _ => return None,
+    };
+
+    match *stmt {
+        Stmt::Return(ReturnStmt {
+            arg: Some(box Expr::Ident(ref i)),
+            ..
+        }) => {
+            // The last parameter wins if there are duplicates.
+            let idx = params.iter().rposition(|p| *p == i.to_id())?;
+            Some(TrivialFn::Param(idx))
+        }
+        Stmt::Return(ReturnStmt {
+            arg: Some(box Expr::Lit(ref lit)),
+            ..
+        }) => match lit {
+            Lit::Regex(..) | Lit::JSXText(..) => None,
+            _ => Some(TrivialFn::Lit(lit.clone())),
+        },
+        _ => None,
+    }
+}
+
+impl Visit<FnDecl> for TrivialFnFinder {
+    fn visit(&mut self, node: &FnDecl) {
+        let id = node.ident.to_id();
+        self.declare(id.clone());
+
+        if let Some(f) = as_trivial_fn(&node.function) {
+            self.fns.insert(id, f);
+        }
+
+        node.function.visit_with(self);
+    }
+}
+
+impl Visit<ClassDecl> for TrivialFnFinder {
+    fn visit(&mut self, node: &ClassDecl) {
+        self.declare(node.ident.to_id());
+
+        node.class.visit_with(self);
+    }
+}
+
+impl Visit<VarDeclarator> for TrivialFnFinder {
+    fn visit(&mut self, node: &VarDeclarator) {
+        let ids: Vec<Id> = find_ids(&node.name);
+        self.written.extend(ids);
+
+        node.init.visit_with(self);
+    }
+}
+
+impl Visit<AssignExpr> for TrivialFnFinder {
+    fn visit(&mut self, node: &AssignExpr) {
+        match node.left {
+            PatOrExpr::Expr(box Expr::Ident(ref i))
+            | PatOrExpr::Pat(box Pat::Expr(box Expr::Ident(ref i))) => {
+                self.written.insert(i.to_id());
+            }
+            PatOrExpr::Pat(ref p) => {
+                let ids: Vec<Id> = find_ids(p);
+                self.written.extend(ids);
+            }
+            _ => {}
+        }
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<UpdateExpr> for TrivialFnFinder {
+    fn visit(&mut self, node: &UpdateExpr) {
+        if let Expr::Ident(ref i) = *node.arg {
+            self.written.insert(i.to_id());
+        }
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<ForInStmt> for TrivialFnFinder {
+    fn visit(&mut self, node: &ForInStmt) {
+        if let VarDeclOrPat::Pat(ref p) = node.left {
+            let ids: Vec<Id> = find_ids(p);
+            self.written.extend(ids);
+        }
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<ForOfStmt> for TrivialFnFinder {
+    fn visit(&mut self, node: &ForOfStmt) {
+        if let VarDeclOrPat::Pat(ref p) = node.left {
+            let ids: Vec<Id> = find_ids(p);
+            self.written.extend(ids);
+        }
+
+        node.visit_children(self);
+    }
+}
@@ -1,26 +1,54 @@
 //! Ported from closure compiler.
 pub use self::{branch::dead_branch_remover, expr::expr_simplifier};
-use crate::pass::RepeatedJsPass;
-use swc_common::{chain, pass::Repeat};
+use crate::pass::{Optional, RepeatedJsPass};
+use swc_common::{
+    chain,
+    pass::{Repeat, Timed},
+};
 
 mod branch;
 pub mod dce;
 mod expr;
 pub mod inlining;
 
-#[derive(Debug, Default)]
+#[derive(Debug)]
 pub struct Config<'a> {
     pub dce: dce::Config<'a>,
     pub inlining: inlining::Config,
+
+    /// Run simplify-expr. Defaults to `true`.
+    pub evaluate: bool,
+    /// Run inlining. Defaults to `true`.
+    pub inline: bool,
+    /// Run remove-dead-branch. Defaults to `true`.
+    pub dead_code: bool,
+    /// Run dce. Defaults to `true`.
+    pub unused: bool,
+}
+
+impl Default for Config<'_> {
+    fn default() -> Self {
+        Config {
+            dce: Default::default(),
+            inlining: Default::default(),
+            evaluate: true,
+            inline: true,
+            dead_code: true,
+            unused: true,
+        }
+    }
 }
 
 /// Performs simplify-expr, inlining, remove-dead-branch and dce until nothing
-/// changes.
+/// changes. Each pass can be disabled by [Config], e.g. to work around a
+/// miscompilation.
+///
+/// Time spent by each pass is recorded to [swc_common::pass::TIMINGS].
 pub fn simplifier<'a>(c: Config<'a>) -> impl RepeatedJsPass + 'a {
     Repeat::new(chain!(
-        expr_simplifier(),
-        inlining::inlining(c.inlining),
-        dead_branch_remover(),
-        dce::dce(c.dce)
+        Optional::new(Timed::new(expr_simplifier()), c.evaluate),
+        Optional::new(Timed::new(inlining::inlining(c.inlining)), c.inline),
+        Optional::new(Timed::new(dead_branch_remover()), c.dead_code),
+        Optional::new(Timed::new(dce::dce(c.dce)), c.unused)
     ))
 }
@@ -1,6 +1,6 @@
-use std::marker::PhantomData;
+use std::{borrow::Cow, marker::PhantomData};
 use swc_common::{
-    pass::{Repeated, RepeatedPass},
+    pass::{CompilerPass, Repeated, RepeatedPass},
     Fold, FoldWith,
 };
 use swc_ecma_ast::*;
@@ -45,6 +45,30 @@ impl<P: Pass> Optional<P> {
     }
 }
 
+impl<P> CompilerPass for Optional<P>
+where
+    P: Pass + CompilerPass,
+{
+    fn name() -> Cow<'static, str> {
+        P::name()
+    }
+}
+
+impl<P> Repeated for Optional<P>
+where
+    P: Pass + Repeated,
+{
+    fn changed(&self) -> bool {
+        self.enabled && self.pass.changed()
+    }
+
+    fn reset(&mut self) {
+        if self.enabled {
+            self.pass.reset()
+        }
+    }
+}
+
 macro_rules! mk_impl {
     ($T:ty) => {
         // impl<A: Pass, B: Pass> Fold<$T> for JoinedPass<A, B, $T> {
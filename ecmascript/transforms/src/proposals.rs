@@ -1,10 +1,15 @@
 pub use self::{
-    class_properties::class_properties, decorators::decorators, export::export,
-    nullish_coalescing::nullish_coalescing, opt_chaining::optional_chaining,
+    class_properties::{class_properties, class_properties_with_config},
+    decorators::decorators,
+    export::export,
+    import_assertions::import_assertions,
+    nullish_coalescing::nullish_coalescing,
+    opt_chaining::optional_chaining,
 };
 
-mod class_properties;
+pub mod class_properties;
 pub mod decorators;
 mod export;
+mod import_assertions;
 mod nullish_coalescing;
 mod opt_chaining;
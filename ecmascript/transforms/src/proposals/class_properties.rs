@@ -9,7 +9,7 @@ use crate::{
     pass::Pass,
     util::{
         alias_ident_for, alias_if_required, constructor::inject_after_super, default_constructor,
-        undefined, ExprFactory, ModuleItemLike, StmtLike,
+        is_valid_ident, undefined, ExprFactory, ModuleItemLike, StmtLike,
     },
 };
 use std::collections::HashSet;
@@ -30,11 +30,29 @@ mod used_name;
 ///
 /// We use custom helper to handle export defaul class
 pub fn class_properties() -> impl Pass {
-    ClassProperties { mark: Mark::root() }
+    class_properties_with_config(Default::default())
+}
+
+pub fn class_properties_with_config(config: Config) -> impl Pass {
+    ClassProperties {
+        config,
+        mark: Mark::root(),
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Config {
+    /// Initializes public fields with assignments instead of
+    /// `Object.defineProperty`, like `useDefineForClassFields: false` of tsc.
+    ///
+    /// Unlike `Object.defineProperty`, an assignment invokes a setter
+    /// inherited from the super class.
+    pub set_public_fields: bool,
 }
 
 #[derive(Clone)]
 struct ClassProperties {
+    config: Config,
     mark: Mark,
 }
 
@@ -350,42 +368,50 @@ impl ClassProperties {
 
                     let value = prop.value.unwrap_or_else(|| undefined(prop_span)).as_arg();
 
-                    let callee = helper!(define_property, "defineProperty");
+                    let (obj, value) = if prop.is_static {
+                        let value = value
+                            .fold_with(&mut SuperFieldAccessFolder {
+                                class_name: &ident,
+                                vars: &mut vars,
+                                constructor_this_mark: None,
+                                is_static: true,
+                                folding_constructor: false,
+                                in_injected_define_property_call: false,
+                                in_nested_scope: false,
+                                this_alias_mark: None,
+                            })
+                            .fold_with(&mut ThisInStaticFolder {
+                                ident: ident.clone(),
+                            });
 
-                    if prop.is_static {
-                        extra_stmts.push(
-                            CallExpr {
-                                span: DUMMY_SP,
-                                callee,
-                                args: vec![
-                                    ident.clone().as_arg(),
-                                    key,
-                                    value
-                                        .fold_with(&mut SuperFieldAccessFolder {
-                                            class_name: &ident,
-                                            vars: &mut vars,
-                                            constructor_this_mark: None,
-                                            is_static: true,
-                                            folding_constructor: false,
-                                            in_injected_define_property_call: false,
-                                            in_nested_scope: false,
-                                            this_alias_mark: None,
-                                        })
-                                        .fold_with(&mut ThisInStaticFolder {
-                                            ident: ident.clone(),
-                                        }),
-                                ],
-                                type_args: Default::default(),
-                            }
-                            .into_stmt(),
-                        )
+                        (Expr::Ident(ident.clone()), value)
                     } else {
-                        constructor_exprs.push(box Expr::Call(CallExpr {
+                        (Expr::This(ThisExpr { span: DUMMY_SP }), value)
+                    };
+
+                    let init = if self.config.set_public_fields {
+                        Expr::Assign(AssignExpr {
                             span: DUMMY_SP,
-                            callee,
-                            args: vec![ThisExpr { span: DUMMY_SP }.as_arg(), key, value],
+                            op: op!("="),
+                            left: PatOrExpr::Expr(box Expr::Member(field_member(obj, key.expr))),
+                            right: value.expr,
+                        })
+                    } else {
+                        Expr::Call(CallExpr {
+                            span: DUMMY_SP,
+                            callee: helper!(define_property, "defineProperty"),
+                            args: vec![obj.as_arg(), key, value],
                             type_args: Default::default(),
-                        }));
+                        })
+                    };
+
+                    if prop.is_static {
+                        extra_stmts.push(Stmt::Expr(ExprStmt {
+                            span: DUMMY_SP,
+                            expr: box init,
+                        }))
+                    } else {
+                        constructor_exprs.push(box init);
                     }
                 }
                 ClassMember::PrivateProp(prop) => {
@@ -538,3 +564,25 @@ impl ClassProperties {
         }
     }
 }
+
+/// Creates `obj.key` if `key` is a string which is a valid identifier, and
+/// `obj[key]` otherwise.
+fn field_member(obj: Expr, key: Box<Expr>) -> MemberExpr {
+    if let Expr::Lit(Lit::Str(ref s)) = *key {
+        if is_valid_ident(&s.value) {
+            return MemberExpr {
+                span: DUMMY_SP,
+                obj: obj.as_obj(),
+                prop: box Expr::Ident(Ident::new(s.value.clone(), s.span)),
+                computed: false,
+            };
+        }
+    }
+
+    MemberExpr {
+        span: DUMMY_SP,
+        obj: obj.as_obj(),
+        prop: key,
+        computed: true,
+    }
+}
@@ -1,4 +1,5 @@
 use self::legacy::Legacy;
+pub use self::metadata::metadata;
 use crate::{
     pass::Pass,
     util::{
@@ -13,6 +14,7 @@ use swc_common::{Fold, FoldWith, Spanned, Visit, VisitWith, DUMMY_SP};
 use swc_ecma_ast::*;
 
 mod legacy;
+mod metadata;
 mod usage;
 
 /// ## Simple class decorator
@@ -0,0 +1,216 @@
+use crate::{pass::Pass, util::ExprFactory};
+use swc_common::{Fold, FoldWith, DUMMY_SP};
+use swc_ecma_ast::*;
+
+/// Emits `Reflect.metadata` calls for decorated classes and class members,
+/// like `emitDecoratorMetadata` of tsc.
+///
+/// This pass reads type annotations, so it should be applied before
+/// `typescript::strip`. Metadata decorators are appended to the existing
+/// decorators, which means they are evaluated before user decorators.
+///
+/// ```ts
+/// class C {
+///   @dec
+///   method(a: string): number {}
+/// }
+/// ```
+///
+/// becomes
+///
+/// ```ts
+/// class C {
+///   @dec
+///   @Reflect.metadata("design:type", Function)
+///   @Reflect.metadata("design:paramtypes", [String])
+///   @Reflect.metadata("design:returntype", Number)
+///   method(a: string): number {}
+/// }
+/// ```
+pub fn metadata() -> impl Pass {
+    Metadata
+}
+
+struct Metadata;
+
+noop_fold_type!(Metadata);
+
+impl Fold<Class> for Metadata {
+    fn fold(&mut self, c: Class) -> Class {
+        let mut c = c.fold_children(self);
+
+        if !c.decorators.is_empty() {
+            let params = c.body.iter().find_map(|member| match member {
+                ClassMember::Constructor(c) => Some(&c.params),
+                _ => None,
+            });
+            let types = params
+                .map(|params| {
+                    params
+                        .iter()
+                        .map(|param| match param {
+                            ParamOrTsParamProp::Param(p) => serialize_pat(&p.pat).as_arg(),
+                            ParamOrTsParamProp::TsParamProp(p) => match &p.param {
+                                TsParamPropParam::Ident(i) => {
+                                    serialize_type(i.type_ann.as_ref()).as_arg()
+                                }
+                                TsParamPropParam::Assign(a) => serialize_pat(&a.left).as_arg(),
+                            },
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+            c.decorators
+                .push(reflect_metadata("design:paramtypes", array(types)));
+        }
+
+        c
+    }
+}
+
+impl Fold<ClassMethod> for Metadata {
+    fn fold(&mut self, m: ClassMethod) -> ClassMethod {
+        let mut m = m.fold_children(self);
+
+        if m.function.decorators.is_empty() {
+            return m;
+        }
+
+        let param_types = m
+            .function
+            .params
+            .iter()
+            .map(|p| serialize_pat(&p.pat).as_arg())
+            .collect();
+
+        m.function.decorators.push(reflect_metadata(
+            "design:type",
+            box Expr::Ident(quote_ident!("Function")),
+        ));
+        m.function
+            .decorators
+            .push(reflect_metadata("design:paramtypes", array(param_types)));
+        if m.function.return_type.is_some() {
+            let ret = serialize_type(m.function.return_type.as_ref());
+            m.function
+                .decorators
+                .push(reflect_metadata("design:returntype", ret));
+        }
+
+        m
+    }
+}
+
+impl Fold<ClassProp> for Metadata {
+    fn fold(&mut self, p: ClassProp) -> ClassProp {
+        let mut p = p.fold_children(self);
+
+        if p.decorators.is_empty() {
+            return p;
+        }
+
+        let ty = serialize_type(p.type_ann.as_ref());
+        p.decorators.push(reflect_metadata("design:type", ty));
+
+        p
+    }
+}
+
+fn reflect_metadata(key: &str, value: Box<Expr>) -> Decorator {
+    Decorator {
+        span: DUMMY_SP,
+        expr: box Expr::Call(CallExpr {
+            span: DUMMY_SP,
+            callee: member_expr!(DUMMY_SP, Reflect.metadata).as_callee(),
+            args: vec![Lit::Str(quote_str!(key)).as_arg(), value.as_arg()],
+            type_args: Default::default(),
+        }),
+    }
+}
+
+fn array(elems: Vec<ExprOrSpread>) -> Box<Expr> {
+    box Expr::Array(ArrayLit {
+        span: DUMMY_SP,
+        elems: elems.into_iter().map(Some).collect(),
+    })
+}
+
+fn serialize_pat(pat: &Pat) -> Box<Expr> {
+    let type_ann = match pat {
+        Pat::Ident(i) => i.type_ann.as_ref(),
+        Pat::Array(a) => a.type_ann.as_ref(),
+        Pat::Object(o) => o.type_ann.as_ref(),
+        Pat::Assign(a) => return serialize_pat(&a.left),
+        Pat::Rest(r) => r.type_ann.as_ref(),
+        _ => None,
+    };
+
+    serialize_type(type_ann)
+}
+
+/// Converts a type annotation to the runtime value used by metadata.
+fn serialize_type(type_ann: Option<&TsTypeAnn>) -> Box<Expr> {
+    fn ident(s: &str) -> Box<Expr> {
+        box Expr::Ident(quote_ident!(s))
+    }
+
+    fn serialize(ty: &TsType) -> Box<Expr> {
+        match ty {
+            TsType::TsKeywordType(k) => match k.kind {
+                TsKeywordTypeKind::TsStringKeyword => ident("String"),
+                TsKeywordTypeKind::TsNumberKeyword => ident("Number"),
+                TsKeywordTypeKind::TsBooleanKeyword => ident("Boolean"),
+                TsKeywordTypeKind::TsSymbolKeyword => ident("Symbol"),
+                TsKeywordTypeKind::TsVoidKeyword
+                | TsKeywordTypeKind::TsUndefinedKeyword
+                | TsKeywordTypeKind::TsNullKeyword
+                | TsKeywordTypeKind::TsNeverKeyword => box Expr::Unary(UnaryExpr {
+                    span: DUMMY_SP,
+                    op: op!("void"),
+                    arg: box Expr::Lit(Lit::Num(Number {
+                        span: DUMMY_SP,
+                        value: 0.0,
+                    })),
+                }),
+                _ => ident("Object"),
+            },
+            TsType::TsParenthesizedType(p) => serialize(&p.type_ann),
+            TsType::TsArrayType(..) | TsType::TsTupleType(..) => ident("Array"),
+            TsType::TsFnOrConstructorType(..) => ident("Function"),
+            TsType::TsLitType(l) => match l.lit {
+                TsLit::Number(..) => ident("Number"),
+                TsLit::Str(..) | TsLit::Tpl(..) => ident("String"),
+                TsLit::Bool(..) => ident("Boolean"),
+            },
+            TsType::TsTypeRef(TsTypeRef {
+                type_name: TsEntityName::Ident(i),
+                ..
+            }) => {
+                // typeof X === "undefined" ? Object : X
+                let name = Ident::new(i.sym.clone(), i.span);
+                box Expr::Cond(CondExpr {
+                    span: DUMMY_SP,
+                    test: box Expr::Bin(BinExpr {
+                        span: DUMMY_SP,
+                        op: op!("==="),
+                        left: box Expr::Unary(UnaryExpr {
+                            span: DUMMY_SP,
+                            op: op!("typeof"),
+                            arg: box Expr::Ident(name.clone()),
+                        }),
+                        right: box Expr::Lit(Lit::Str(quote_str!("undefined"))),
+                    }),
+                    cons: ident("Object"),
+                    alt: box Expr::Ident(name),
+                })
+            }
+            _ => ident("Object"),
+        }
+    }
+
+    match type_ann {
+        Some(ann) => serialize(&ann.type_ann),
+        None => ident("Object"),
+    }
+}
@@ -49,6 +49,7 @@ impl Fold<Vec<ModuleItem>> for ExportDefaultFrom {
                                     .clone()
                                     .expect("`export default from` requires source"),
                                 type_only: false,
+                                asserts: None,
                             })));
                             extra_stmts.push(ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(
                                 NamedExport {
@@ -81,6 +82,7 @@ impl Fold<Vec<ModuleItem>> for ExportDefaultFrom {
                                     .clone()
                                     .expect("`export default from` requires source"),
                                 type_only: false,
+                                asserts: None,
                             })));
                             extra_stmts.push(ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(
                                 NamedExport {
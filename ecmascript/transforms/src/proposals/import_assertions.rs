@@ -0,0 +1,23 @@
+use crate::pass::Pass;
+use swc_common::Fold;
+use swc_ecma_ast::*;
+
+/// Removes `assert { ... }` clauses from import declarations, for runtimes
+/// which don't understand import assertions.
+pub fn import_assertions() -> impl Pass {
+    ImportAssertions
+}
+
+#[derive(Clone, Copy)]
+struct ImportAssertions;
+
+noop_fold_type!(ImportAssertions);
+
+impl Fold<ImportDecl> for ImportAssertions {
+    fn fold(&mut self, import: ImportDecl) -> ImportDecl {
+        ImportDecl {
+            asserts: None,
+            ..import
+        }
+    }
+}
@@ -1,14 +1,21 @@
 use crate::{
     pass::Pass,
-    util::{prepend_stmts, var::VarCollector, ExprFactory},
+    util::{prepend_stmts, var::VarCollector, ExprFactory, COMMENTS, HANDLER},
+};
+use fxhash::{FxHashMap, FxHashSet};
+use swc_atoms::{js_word, JsWord};
+use swc_common::{
+    comments::{Comment, CommentKind},
+    util::move_map::MoveMap,
+    Fold, FoldWith, Spanned, Visit, VisitWith, DUMMY_SP,
 };
-use fxhash::FxHashMap;
-use swc_atoms::js_word;
-use swc_common::{util::move_map::MoveMap, Fold, FoldWith, Spanned, Visit, VisitWith, DUMMY_SP};
 use swc_ecma_ast::*;
-use swc_ecma_utils::{ident::IdentLike, Id};
+use swc_ecma_utils::{ident::IdentLike, to_int32, Id};
 
 /// Strips type annotations out.
+///
+/// Members of a `const enum` are inlined as literals and the declaration is
+/// removed.
 pub fn strip() -> impl Pass {
     Strip::default()
 }
@@ -42,6 +49,8 @@ impl Default for Phase {
 struct Scope {
     decls: FxHashMap<Id, DeclInfo>,
     imported_idents: FxHashMap<Id, DeclInfo>,
+    /// Values of members of `const enum`s.
+    const_enums: FxHashMap<Id, FxHashMap<JsWord, Lit>>,
 }
 
 #[derive(Debug, Default)]
@@ -84,9 +93,13 @@ impl Strip {
                 }
             }
 
-            Decl::TsEnum(TsEnumDecl { ref id, .. }) => {
-                // Currently swc cannot remove constant enums
-                store!(id.sym, id.span.ctxt(), true);
+            Decl::TsEnum(TsEnumDecl {
+                ref id, is_const, ..
+            }) => {
+                // Constant enums are inlined, so only normal enums exist at runtime.
+                if !is_const {
+                    store!(id.sym, id.span.ctxt(), true);
+                }
                 store!(id.sym, id.span.ctxt(), false);
             }
 
@@ -223,6 +236,8 @@ impl Fold<Vec<ModuleItem>> for Strip {
     fn fold(&mut self, items: Vec<ModuleItem>) -> Vec<ModuleItem> {
         let old = self.phase;
 
+        ConstEnumCollector::collect(&mut self.scope.const_enums, &items);
+
         // First pass
         self.phase = Phase::Analysis;
         let items = items.fold_children(self);
@@ -235,6 +250,10 @@ impl Fold<Vec<ModuleItem>> for Strip {
             self.was_side_effect_import = false;
             match item {
                 ModuleItem::Stmt(Stmt::Empty(..))
+                | ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                    decl: Decl::TsEnum(TsEnumDecl { is_const: true, .. }),
+                    ..
+                }))
                 | ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
                     type_only: true, ..
                 }))
@@ -535,6 +554,274 @@ impl Strip {
     }
 }
 
+impl Strip {
+    /// Replaces `E.A` or `E['A']` with the value of `A` if `E` is a
+    /// `const enum`.
+    fn inline_const_enum(&self, e: &MemberExpr) -> Option<Expr> {
+        let obj = match e.obj {
+            ExprOrSuper::Expr(box Expr::Ident(ref obj)) => obj,
+            _ => return None,
+        };
+        let values = self.scope.const_enums.get(&obj.to_id())?;
+
+        let name = match *e.prop {
+            Expr::Ident(ref prop) if !e.computed => prop.sym.clone(),
+            Expr::Lit(Lit::Str(ref prop)) if e.computed => prop.value.clone(),
+            _ => {
+                HANDLER.with(|handler| {
+                    handler
+                        .struct_span_err(
+                            e.span,
+                            "A const enum member can only be accessed using a string literal",
+                        )
+                        .emit()
+                });
+                return None;
+            }
+        };
+        let value = match values.get(&name) {
+            Some(value) => value.clone(),
+            None => {
+                HANDLER.with(|handler| {
+                    handler
+                        .struct_span_err(
+                            e.span,
+                            &format!("`{}` is not a member of const enum `{}`", name, obj.sym),
+                        )
+                        .emit()
+                });
+                return None;
+            }
+        };
+
+        if COMMENTS.is_set() {
+            COMMENTS.with(|comments| {
+                comments.add_trailing(
+                    e.span.hi(),
+                    Comment {
+                        kind: CommentKind::Block,
+                        span: DUMMY_SP,
+                        text: format!(" {}.{} ", obj.sym, name),
+                    },
+                )
+            });
+        }
+
+        Some(match value {
+            Lit::Num(Number { value, .. }) if value.is_sign_negative() => Expr::Unary(UnaryExpr {
+                span: e.span,
+                op: op!(unary, "-"),
+                arg: box Expr::Lit(Lit::Num(Number {
+                    span: e.span,
+                    value: -value,
+                })),
+            }),
+            Lit::Num(n) => Expr::Lit(Lit::Num(Number { span: e.span, ..n })),
+            Lit::Str(s) => Expr::Lit(Lit::Str(Str { span: e.span, ..s })),
+            lit => Expr::Lit(lit),
+        })
+    }
+}
+
+/// Computes values of members of `const enum`s.
+///
+/// A `const enum` is not inlined, but emitted like a normal enum, if the value
+/// of a member can't be computed or if its name is bound elsewhere, as `strip`
+/// runs before the resolver and can't tell shadowed names apart.
+struct ConstEnumCollector<'a> {
+    const_enums: &'a mut FxHashMap<Id, FxHashMap<JsWord, Lit>>,
+    /// Names bound by anything other than a `const enum`.
+    bound: FxHashSet<JsWord>,
+    /// Names of `const enum`s which can't be inlined.
+    skipped: FxHashSet<JsWord>,
+}
+
+impl ConstEnumCollector<'_> {
+    fn collect(const_enums: &mut FxHashMap<Id, FxHashMap<JsWord, Lit>>, items: &[ModuleItem]) {
+        let mut v = ConstEnumCollector {
+            const_enums,
+            bound: Default::default(),
+            skipped: Default::default(),
+        };
+        items.visit_with(&mut v);
+
+        let ConstEnumCollector {
+            const_enums,
+            bound,
+            skipped,
+        } = v;
+        const_enums.retain(|id, _| !bound.contains(&id.0) && !skipped.contains(&id.0));
+    }
+}
+
+impl Visit<TsEnumDecl> for ConstEnumCollector<'_> {
+    fn visit(&mut self, e: &TsEnumDecl) {
+        if !e.is_const {
+            self.bound.insert(e.id.sym.clone());
+            return;
+        }
+
+        let mut values = FxHashMap::default();
+        // Value of a member without an initializer
+        let mut next = Some(0.0);
+
+        for m in &e.members {
+            let name = match m.id {
+                TsEnumMemberId::Str(ref s) => s.value.clone(),
+                TsEnumMemberId::Ident(ref i) => i.sym.clone(),
+            };
+
+            let value = match m.init {
+                Some(ref init) => const_enum_value(init, &e.id, &values),
+                None => next.map(|value| {
+                    Lit::Num(Number {
+                        span: m.span,
+                        value,
+                    })
+                }),
+            };
+            let value = match value {
+                Some(value) => value,
+                None => {
+                    self.skipped.insert(e.id.sym.clone());
+                    return;
+                }
+            };
+
+            next = match value {
+                Lit::Num(Number { value, .. }) => Some(value + 1.0),
+                _ => None,
+            };
+            values.insert(name, value);
+        }
+
+        // Declarations with the same name are merged or in different scopes.
+        if self.const_enums.insert(e.id.to_id(), values).is_some() {
+            self.skipped.insert(e.id.sym.clone());
+        }
+    }
+}
+
+impl Visit<Pat> for ConstEnumCollector<'_> {
+    fn visit(&mut self, p: &Pat) {
+        if let Pat::Ident(ref i) = *p {
+            self.bound.insert(i.sym.clone());
+        }
+
+        p.visit_children(self);
+    }
+}
+
+macro_rules! bound_ident {
+    ($T:ty, $field:ident) => {
+        impl Visit<$T> for ConstEnumCollector<'_> {
+            fn visit(&mut self, node: &$T) {
+                self.bound.insert(node.$field.sym.clone());
+
+                node.visit_children(self);
+            }
+        }
+    };
+    ($T:ty, Option $field:ident) => {
+        impl Visit<$T> for ConstEnumCollector<'_> {
+            fn visit(&mut self, node: &$T) {
+                if let Some(ref i) = node.$field {
+                    self.bound.insert(i.sym.clone());
+                }
+
+                node.visit_children(self);
+            }
+        }
+    };
+}
+
+bound_ident!(FnDecl, ident);
+bound_ident!(ClassDecl, ident);
+bound_ident!(FnExpr, Option ident);
+bound_ident!(ClassExpr, Option ident);
+bound_ident!(ImportDefaultSpecifier, local);
+bound_ident!(ImportNamedSpecifier, local);
+bound_ident!(ImportStarAsSpecifier, local);
+
+/// Evaluates an initializer of a member of the `const enum` named `enum_id`.
+fn const_enum_value(e: &Expr, enum_id: &Ident, values: &FxHashMap<JsWord, Lit>) -> Option<Lit> {
+    let value = match *e {
+        Expr::Lit(ref lit @ Lit::Num(..)) | Expr::Lit(ref lit @ Lit::Str(..)) => lit.clone(),
+        Expr::Paren(ParenExpr { ref expr, .. }) => const_enum_value(expr, enum_id, values)?,
+        Expr::Unary(UnaryExpr { op, ref arg, .. }) => {
+            let arg = match const_enum_value(arg, enum_id, values)? {
+                Lit::Num(n) => n,
+                _ => return None,
+            };
+            let value = match op {
+                op!(unary, "-") => -arg.value,
+                op!(unary, "+") => arg.value,
+                op!("~") => !to_int32(arg.value) as f64,
+                _ => return None,
+            };
+
+            Lit::Num(Number { value, ..arg })
+        }
+        Expr::Bin(BinExpr {
+            op,
+            ref left,
+            ref right,
+            ..
+        }) => match (
+            const_enum_value(left, enum_id, values)?,
+            const_enum_value(right, enum_id, values)?,
+        ) {
+            (Lit::Num(l), Lit::Num(r)) => {
+                let (lv, rv) = (l.value, r.value);
+                // Only the lower 5 bits are used when shifting.
+                let shift = to_int32(rv) as u32 & 31;
+                let value = match op {
+                    op!(bin, "+") => lv + rv,
+                    op!(bin, "-") => lv - rv,
+                    op!("*") => lv * rv,
+                    op!("/") => lv / rv,
+                    op!("%") => lv % rv,
+                    op!("**") => lv.powf(rv),
+                    op!("|") => (to_int32(lv) | to_int32(rv)) as f64,
+                    op!("&") => (to_int32(lv) & to_int32(rv)) as f64,
+                    op!("^") => (to_int32(lv) ^ to_int32(rv)) as f64,
+                    op!("<<") => to_int32(lv).wrapping_shl(shift) as f64,
+                    op!(">>") => to_int32(lv).wrapping_shr(shift) as f64,
+                    op!(">>>") => (to_int32(lv) as u32).wrapping_shr(shift) as f64,
+                    _ => return None,
+                };
+
+                Lit::Num(Number { value, ..l })
+            }
+            (Lit::Str(l), Lit::Str(r)) if op == op!(bin, "+") => Lit::Str(Str {
+                value: format!("{}{}", l.value, r.value).into(),
+                has_escape: l.has_escape || r.has_escape,
+                ..l
+            }),
+            _ => return None,
+        },
+        // Other members of the same enum
+        Expr::Ident(ref i) => values.get(&i.sym).cloned()?,
+        Expr::Member(MemberExpr {
+            obj: ExprOrSuper::Expr(box Expr::Ident(ref obj)),
+            ref prop,
+            computed,
+            ..
+        }) if obj.sym == enum_id.sym => match **prop {
+            Expr::Ident(ref prop) if !computed => values.get(&prop.sym).cloned()?,
+            Expr::Lit(Lit::Str(ref prop)) if computed => values.get(&prop.value).cloned()?,
+            _ => return None,
+        },
+        _ => return None,
+    };
+
+    match value {
+        // tsc reports an error for `NaN` and `Infinity`.
+        Lit::Num(Number { value, .. }) if !value.is_finite() => None,
+        _ => Some(value),
+    }
+}
+
 impl Fold<ImportDecl> for Strip {
     fn fold(&mut self, mut import: ImportDecl) -> ImportDecl {
         match self.phase {
@@ -648,6 +935,18 @@ impl Fold<Class> for Strip {
 impl Fold<Decl> for Strip {
     fn fold(&mut self, decl: Decl) -> Decl {
         let decl = validate!(decl);
+        // A `const enum` which can't be inlined is emitted like a normal enum.
+        let decl = match decl {
+            Decl::TsEnum(e)
+                if e.is_const && !self.scope.const_enums.contains_key(&e.id.to_id()) =>
+            {
+                Decl::TsEnum(TsEnumDecl {
+                    is_const: false,
+                    ..e
+                })
+            }
+            _ => decl,
+        };
         self.handle_decl(&decl);
 
         let old = self.non_top_level;
@@ -667,6 +966,7 @@ impl Fold<Stmt> for Strip {
                 Decl::TsInterface(..)
                 | Decl::TsModule(..)
                 | Decl::TsTypeAlias(..)
+                | Decl::TsEnum(TsEnumDecl { is_const: true, .. })
                 | Decl::Var(VarDecl { declare: true, .. })
                 | Decl::Class(ClassDecl { declare: true, .. })
                 | Decl::Fn(FnDecl { declare: true, .. }) => {
@@ -728,6 +1028,14 @@ impl Fold<Expr> for Strip {
             _ => validate!(expr),
         };
 
+        let expr = match expr {
+            Expr::Member(e) => match self.inline_const_enum(&e) {
+                Some(value) => return value,
+                None => Expr::Member(e),
+            },
+            _ => expr,
+        };
+
         let expr = match expr {
             Expr::Member(MemberExpr {
                 span,
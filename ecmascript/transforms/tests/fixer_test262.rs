@@ -179,7 +179,10 @@ fn identity_tests(tests: &mut Vec<TestDescAndFn>) -> Result<(), io::Error> {
                         );
 
                         let mut emitter = Emitter {
-                            cfg: swc_ecma_codegen::Config { minify: false },
+                            cfg: swc_ecma_codegen::Config {
+                                minify: false,
+                                ..Default::default()
+                            },
                             cm: cm.clone(),
                             wr: box swc_ecma_codegen::text_writer::JsWriter::new(
                                 cm.clone(),
@@ -191,7 +194,10 @@ fn identity_tests(tests: &mut Vec<TestDescAndFn>) -> Result<(), io::Error> {
                             handlers,
                         };
                         let mut expected_emitter = Emitter {
-                            cfg: swc_ecma_codegen::Config { minify: false },
+                            cfg: swc_ecma_codegen::Config {
+                                minify: false,
+                                ..Default::default()
+                            },
                             cm: cm.clone(),
                             wr: box swc_ecma_codegen::text_writer::JsWriter::new(
                                 cm, "\n", &mut wr2, None,
@@ -0,0 +1,96 @@
+#![feature(box_syntax)]
+#![feature(test)]
+#![feature(box_patterns)]
+#![feature(specialization)]
+
+use swc_common::chain;
+use swc_ecma_transforms::{global_rename, resolver};
+
+#[macro_use]
+mod common;
+
+macro_rules! to {
+    ($name:ident, $src:expr, $expected:expr) => {
+        test!(
+            Default::default(),
+            |_| chain!(
+                resolver(),
+                global_rename(
+                    vec![("Promise".into(), "SomePolyfill".into())]
+                        .into_iter()
+                        .collect()
+                )
+            ),
+            $name,
+            $src,
+            $expected
+        );
+    };
+}
+
+macro_rules! identical {
+    ($name:ident, $src:expr) => {
+        to!($name, $src, $src);
+    };
+}
+
+to!(
+    global_ref,
+    "Promise.resolve(1);
+new Promise(function() {});",
+    "SomePolyfill.resolve(1);
+new SomePolyfill(function() {});"
+);
+
+to!(
+    shorthand_prop,
+    "const a = { Promise };",
+    "const a = { Promise: SomePolyfill };"
+);
+
+identical!(member_prop, "foo.Promise;");
+
+to!(
+    shadowed,
+    "function foo(Promise) {
+    return Promise;
+}
+Promise;",
+    "function foo(Promise) {
+    return Promise;
+}
+SomePolyfill;"
+);
+
+identical!(
+    top_level_binding,
+    "const Promise = 1;
+Promise;"
+);
+
+to!(assign, "Promise = foo;", "SomePolyfill = foo;");
+
+to!(
+    object_pat_assign,
+    "({ Promise } = foo);
+({ Promise = bar } = foo);",
+    "({ Promise: SomePolyfill } = foo);
+({ Promise: SomePolyfill = bar } = foo);"
+);
+
+identical!(
+    setter_param,
+    "const a = {
+    set foo (Promise) {
+        Promise = 1;
+    }
+};"
+);
+
+identical!(prop_key, "const a = { Promise: 1 };");
+
+identical!(
+    import_name,
+    "import { Promise as P } from 'foo';
+P;"
+);
@@ -0,0 +1,138 @@
+#![feature(box_syntax)]
+#![feature(test)]
+#![feature(box_patterns)]
+#![feature(specialization)]
+
+use swc_ecma_transforms::modules::cjs_to_esm::cjs_to_esm;
+
+#[macro_use]
+mod common;
+
+macro_rules! to {
+    ($name:ident, $src:expr, $expected:expr) => {
+        test!(Default::default(), |_| cjs_to_esm(), $name, $src, $expected);
+    };
+}
+
+macro_rules! identical {
+    ($name:ident, $src:expr) => {
+        to!($name, $src, $src);
+    };
+}
+
+to!(
+    require_side_effect,
+    "require('foo');",
+    "import 'foo';"
+);
+
+to!(
+    require_default,
+    "const foo = require('foo');
+foo();",
+    "import foo from 'foo';
+foo();"
+);
+
+to!(
+    require_destructuring,
+    "const { a, b: c } = require('foo');
+a(c);",
+    "import { a, b as c } from 'foo';
+a(c);"
+);
+
+to!(
+    module_exports,
+    "module.exports = { foo: 1 };",
+    "export default { foo: 1 };"
+);
+
+to!(
+    exports_named,
+    "exports.foo = 1;
+module.exports.bar = 2;",
+    "export const foo = 1;
+export const bar = 2;"
+);
+
+to!(
+    exports_binding,
+    "function foo() {}
+exports.foo = foo;
+exports.bar = foo;",
+    "function foo() {}
+export { foo };
+export { foo as bar };"
+);
+
+identical!(
+    dynamic_require,
+    "const foo = require('foo');
+const bar = require(name);"
+);
+
+identical!(
+    nested_require,
+    "const foo = require('foo');
+function bar() {
+    return require('bar');
+}"
+);
+
+identical!(
+    require_let,
+    "let foo = require('foo');
+foo = 1;"
+);
+
+identical!(
+    require_default_value,
+    "const { a = 1 } = require('foo');"
+);
+
+identical!(
+    read_exports,
+    "exports.foo = 1;
+exports.bar = exports.foo;"
+);
+
+identical!(
+    exports_twice,
+    "exports.foo = 1;
+exports.foo = 2;"
+);
+
+identical!(
+    default_and_named,
+    "module.exports = 1;
+exports.foo = 2;"
+);
+
+identical!(
+    name_conflict,
+    "const foo = 1;
+exports.foo = 2;"
+);
+
+identical!(
+    shadowed_require,
+    "function require(name) {}
+const foo = require('foo');"
+);
+
+identical!(
+    name_referenced,
+    "exports.foo = 1;
+use(foo);"
+);
+
+to!(
+    exports_reassigned_binding,
+    "let x = 1;
+exports.foo = x;
+x = 2;",
+    "let x = 1;
+export const foo = x;
+x = 2;"
+);
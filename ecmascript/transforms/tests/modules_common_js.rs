@@ -4155,3 +4155,44 @@ const resources = [
       }
   ];"
 );
+
+test!(
+    syntax(),
+    |_| tr(Default::default()),
+    use_strict_present,
+    "'use strict';
+import foo from 'foo';
+foo.bar = true;",
+    "'use strict';
+var _foo = _interopRequireDefault(require('foo'));
+_foo.default.bar = true;"
+);
+
+test!(
+    syntax(),
+    |_| tr(Default::default()),
+    use_strict_after_directive,
+    "'use client';
+'use strict';
+import foo from 'foo';
+foo.bar = true;",
+    "'use client';
+'use strict';
+var _foo = _interopRequireDefault(require('foo'));
+_foo.default.bar = true;"
+);
+
+test!(
+    syntax(),
+    |_| tr(Config {
+        strict_mode: false,
+        ..Default::default()
+    }),
+    use_strict_present_strict_mode_false,
+    "'use strict';
+import foo from 'foo';
+foo.bar = true;",
+    "'use strict';
+var _foo = _interopRequireDefault(require('foo'));
+_foo.default.bar = true;"
+);
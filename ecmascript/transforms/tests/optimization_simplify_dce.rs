@@ -360,3 +360,20 @@ noop!(
     resources.map(v => v)
 "
 );
+
+noop!(
+    directives,
+    "'use client'; 'use strict'; export function f() { 'use strict'; return 1; }"
+);
+
+to!(
+    not_directive,
+    "'use strict'; foo(); 'bar';",
+    "'use strict'; foo();"
+);
+
+to!(
+    block_not_directive,
+    "'use strict'; { 'foo'; } foo();",
+    "'use strict'; foo();"
+);
@@ -6,7 +6,10 @@
 #![feature(specialization)]
 
 use swc_common::chain;
-use swc_ecma_transforms::{optimization::simplify::inlining::inlining, resolver};
+use swc_ecma_transforms::{
+    optimization::simplify::inlining::{inlining, Config},
+    resolver,
+};
 
 #[macro_use]
 mod common;
@@ -1081,7 +1084,7 @@ fn test_no_inline_getprop_into_call_1() {
 
 #[test]
 fn test_no_inline_getprop_into_call_2() {
-    test("var a = b.c; f(a);", "var a; f(b.c);");
+    test_same("var a = b.c; f(a);");
 }
 
 #[test]
@@ -2136,3 +2139,310 @@ fn test_tagged_template_literals() {
         ),
     );
 }
+
+to!(
+    inline_barrier_in_preceding_cond,
+    "function f(c) {
+        var a = 1;
+        if (c) {
+            a = 2;
+        } else {
+            bar();
+        }
+        var x = 1;
+        var y = x;
+        use(y);
+    }",
+    "function f(c) {
+        var a = 1;
+        if (c) {
+            a = 2;
+        } else {
+            bar();
+        }
+        var x;
+        var y;
+        use(1);
+    }"
+);
+
+identical!(
+    inline_barrier_in_cond_between,
+    "function f(c) {
+        var x = 1;
+        if (c) {
+            bar();
+        }
+        var y = x;
+        use(y);
+    }"
+);
+
+identical!(
+    inline_barrier_cond_write,
+    "function f(c) {
+        var x = 1;
+        if (c) {
+            x = 2;
+        }
+        var y = x;
+        use(y);
+    }"
+);
+
+identical!(
+    inline_barrier_cond_write_after_alias,
+    "function f(c) {
+        var x = 1;
+        var y = x;
+        if (c) {
+            x = 2;
+        }
+        use(y);
+    }"
+);
+
+to!(
+    member_read_in_member_assign,
+    "function f() {
+        var o = obj;
+        a.b = o.x;
+        use(o);
+    }",
+    "function f() {
+        var o;
+        a.b = obj.x;
+        use(obj);
+    }"
+);
+
+to!(
+    member_read_in_compound_assign,
+    "function f() {
+        var o = obj;
+        x += o.y.z;
+        use(o);
+    }",
+    "function f() {
+        var o;
+        x += obj.y.z;
+        use(obj);
+    }"
+);
+
+identical!(
+    computed_member_read_in_member_assign,
+    "function f() {
+        var o = obj;
+        a.b = o[k];
+        use(o);
+    }"
+);
+
+identical!(
+    ident_in_member_assign,
+    "function f() {
+        var o = obj;
+        a.b = o;
+        use(o);
+    }"
+);
+
+identical!(
+    member_write,
+    "function f() {
+        var o = obj;
+        o.x = 1;
+        use(o);
+    }"
+);
+
+macro_rules! trivial_fn {
+    ($name:ident, $src:expr, $expected:expr) => {
+        test!(
+            Default::default(),
+            |_| chain!(
+                resolver(),
+                inlining(Config {
+                    inline_trivial_fns: true,
+                    ..Default::default()
+                })
+            ),
+            $name,
+            $src,
+            $expected
+        );
+    };
+}
+
+trivial_fn!(
+    trivial_fn_identity,
+    "function id(x) { return x; } use(id(foo()));",
+    "function id(x) { return x; } use(foo());"
+);
+
+trivial_fn!(
+    trivial_fn_param,
+    "function second(a, b) { return b; } use(second(1, bar()), second(foo(), 2));",
+    "function second(a, b) { return b; } use(bar(), second(foo(), 2));"
+);
+
+trivial_fn!(
+    trivial_fn_missing_arg,
+    "function id(x) { return x; } use(id());",
+    "function id(x) { return x; } use(void 0);"
+);
+
+trivial_fn!(
+    trivial_fn_lit,
+    "function one() { return 1; } use(one(), one);",
+    "function one() { return 1; } use(1, one);"
+);
+
+trivial_fn!(
+    trivial_fn_this,
+    "function f(x) { return this.x; } use(f(1));",
+    "function f(x) { return this.x; } use(f(1));"
+);
+
+trivial_fn!(
+    trivial_fn_arguments,
+    "function f(x) { return arguments; } use(f(1));",
+    "function f(x) { return arguments; } use(f(1));"
+);
+
+trivial_fn!(
+    trivial_fn_reassigned,
+    "function id(x) { return x; } function f() { id = g; } use(id(1));",
+    "function id(x) { return x; } function f() { id = g; } use(id(1));"
+);
+
+to!(
+    trivial_fn_disabled,
+    "function id(x) { return x; } use(id(1));",
+    "function id(x) { return x; } use(id(1));"
+);
+
+to!(
+    read_in_dead_branch,
+    "var a = x + y; if (false) use(a); use(a);",
+    "var a; if (false) use(x + y); use(x + y);"
+);
+
+to!(
+    read_in_dead_alt,
+    "var a = x + y; if (true) {} else { use(a); } use(a);",
+    "var a; if (true) {} else { use(x + y); } use(x + y);"
+);
+
+identical!(
+    read_in_unknown_branch,
+    "var a = x + y; if (b) use(a); use(a);"
+);
+
+to!(
+    typeof_number,
+    "const n = 1; use(typeof n);",
+    "const n = 1; use('number');"
+);
+
+to!(
+    typeof_string,
+    "var s = 'a'; use(typeof s);",
+    "var s; use('string');"
+);
+
+identical!(
+    typeof_reassigned,
+    "var s = 'a'; if (c) s = 2; use(typeof s);"
+);
+
+identical!(typeof_global, "use(typeof foo);");
+
+to!(
+    typeof_object,
+    "var o = {}; use(typeof o);",
+    "var o; use(typeof {});"
+);
+
+to!(
+    cond_const_true,
+    "const flag = true; use(flag ? a : b);",
+    "const flag = true; use(a);"
+);
+
+to!(
+    cond_const_false,
+    "const flag = false; use(flag ? a : b);",
+    "const flag = false; use(b);"
+);
+
+to!(
+    cond_const_impure_dropped_branch,
+    "const flag = true; use(flag ? a : b());",
+    "const flag = true; use(true ? a : b());"
+);
+
+to!(
+    infinite_loop_alias,
+    "var a = x; for (;;) { if (c) break; } var b = a; use(b);",
+    "var a; for (;;) { if (c) break; } var b; use(x);"
+);
+
+identical!(
+    infinite_loop_mutated,
+    "var a = x; for (;;) { a = y; if (c) break; } var b = a; use(b);"
+);
+
+to!(
+    never_reassigned_let,
+    "let x = 5; if (a) use(x); use(x);",
+    "let x; if (a) use(5); use(5);"
+);
+
+identical!(
+    reassigned_let,
+    "let x = 5; function f() { x = 6; } f(); use(x);"
+);
+
+identical!(exported_let, "export let x = 5; function f() { return x; }");
+
+to!(
+    member_read_of_local_object,
+    "var o = { x: 1 }; var a = o.x; use(a, o);",
+    "var o = { x: 1 }; var a; use(o.x, o);"
+);
+
+identical!(member_read_of_external_object, "var a = obj.x; use(a);");
+
+identical!(
+    member_read_of_accessor,
+    "var o = { get x() { return 1; } }; var a = o.x; use(a, o);"
+);
+
+identical!(
+    computed_member_read_of_local_object,
+    "var o = { x: 1 }; var a = o[k]; use(a, o);"
+);
+
+to!(
+    self_assign,
+    "var x = f(); x = x; use(x);",
+    "var x = f(); x; use(x);"
+);
+
+identical!(self_assign_update, "var x = f(); x = x++; use(x);");
+
+identical!(self_assign_const, "const x = f(); x = x; use(x);");
+
+identical!(self_assign_global, "x = x; use(x);");
+
+identical!(
+    never_reassigned_var_read_by_hoisted_fn,
+    "f(); var x = 5; function f() { use(x); }"
+);
+
+identical!(
+    never_reassigned_let_read_by_hoisted_fn,
+    "f(); let x = 5; function f() { use(x); }"
+);
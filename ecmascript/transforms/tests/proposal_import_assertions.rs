@@ -0,0 +1,35 @@
+#![feature(box_syntax)]
+#![feature(test)]
+#![feature(box_patterns)]
+#![feature(specialization)]
+
+use swc_ecma_parser::{EsConfig, Syntax};
+use swc_ecma_transforms::proposals::import_assertions;
+
+#[macro_use]
+mod common;
+
+fn syntax() -> Syntax {
+    Syntax::Es(EsConfig {
+        import_assertions: true,
+        ..Default::default()
+    })
+}
+
+test!(
+    syntax(),
+    |_| import_assertions(),
+    remove_asserts,
+    "import data from './data.json' assert { type: 'json' };
+import './style.css' assert { type: 'css' };",
+    "import data from './data.json';
+import './style.css';"
+);
+
+test!(
+    syntax(),
+    |_| import_assertions(),
+    keep_imports_without_asserts,
+    "import foo from 'foo';",
+    "import foo from 'foo';"
+);
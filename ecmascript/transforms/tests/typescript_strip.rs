@@ -541,3 +541,77 @@ to!(
         const a = {};
       }"
 );
+
+to!(
+    const_enum_num,
+    "const enum E {
+        A = 1,
+        B,
+        C = -A,
+    }
+    console.log(E.A, E.B, E['C']);",
+    "console.log(1, 2, -1);"
+);
+
+to!(
+    const_enum_str,
+    "export const enum State {
+        Opened = 'opened',
+        Closed = 'closed',
+    }
+    console.log(State.Opened, State['Closed']);",
+    "console.log('opened', 'closed');"
+);
+
+#[test]
+#[should_panic(expected = "A const enum member can only be accessed using a string literal")]
+fn const_enum_computed() {
+    test_transform!(
+        ::swc_ecma_parser::Syntax::Typescript(Default::default()),
+        |_| strip(),
+        "const enum E {
+            A = 1,
+        }
+        const key = 'A';
+        console.log(E[key]);",
+        "const key = 'A';
+        console.log(E[key]);",
+        true
+    );
+}
+
+to!(
+    const_enum_bitwise,
+    "const enum Flags {
+        A = 1 << 0,
+        B = 1 << 1,
+        C = A | B,
+        D = ~A,
+        E = C & ~A,
+        F = 'a' + 'b',
+    }
+    console.log(Flags.A, Flags.B, Flags.C, Flags.D, Flags.E, Flags.F);",
+    "console.log(1, 2, 3, -2, 2, 'ab');"
+);
+
+test!(
+    ::swc_ecma_parser::Syntax::Typescript(Default::default()),
+    |_| strip(),
+    const_enum_shadowed,
+    "const enum E {
+        A = 1,
+    }
+    function f(E) {
+        return E.A;
+    }",
+    r#"
+var E;
+(function (E) {
+    E["A"] = 1;
+})(E || (E = {}));
+function f(E) {
+    return E.A;
+}
+"#,
+    ok_if_code_eq
+);
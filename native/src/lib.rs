@@ -440,8 +440,7 @@ impl Task for PrintTask {
                     .config
                     .clone()
                     .unwrap_or_default()
-                    .minify
-                    .unwrap_or(false),
+                    .codegen_config(),
             )
         })
     }
@@ -507,7 +506,7 @@ fn print_sync(mut cx: MethodContext<JsCompiler>) -> JsResult<JsValue> {
                     .clone()
                     .unwrap_or(SourceMapsConfig::Bool(false)),
                 None,
-                options.config.unwrap_or_default().minify.unwrap_or(false),
+                options.config.unwrap_or_default().codegen_config(),
             )
         };
         complete_output(cx, result)
@@ -186,9 +186,58 @@ export interface Options extends Config {
    */
   sourceRoot?: string;
 
+  /**
+   * Path of the output file. If specified and `sourceMaps` is `true`, a
+   * `sourceMappingURL` comment relative to the output file is appended.
+   */
+  outputPath?: string;
+
+  /**
+   * Path of the source map file. Defaults to `<outputPath>.map`.
+   */
+  sourceMapPath?: string;
+
+  /**
+   * Embeds the source of input files in `sourcesContent` of the source map.
+   * Defaults to `false`.
+   */
+  sourcesContent?: boolean;
+
+  /**
+   * Records sources in the source map by their paths relative to `root`.
+   * Sources outside of `root` are recorded by their absolute paths.
+   */
+  relativeSources?: boolean;
+
   plugin?: Plugin;
 
   isModule?: boolean;
+
+  /**
+   * Reports time spent by each pass in `passTimings` of the output.
+   */
+  profile?: boolean;
+
+  /**
+   * Returns the source map as an object instead of a string.
+   */
+  sourceMapObject?: boolean;
+
+  /**
+   * Also returns non-minified code in `pretty` of the output.
+   */
+  emitPrettyAlongside?: boolean;
+
+  /**
+   * Returns comments in the code with their positions in `comments` of the
+   * output.
+   */
+  outputComments?: boolean;
+
+  /**
+   * Fails if a pass reports a warning.
+   */
+  failOnWarnings?: boolean;
 }
 
 export interface CallerOptions {
@@ -245,8 +294,24 @@ export interface EnvConfig {
 }
 
 export interface JscConfig {
+  /**
+   * Use loose mode of es2015 spread, for-of and destructuring transforms,
+   * which assumes values are arrays.
+   *
+   * Ignored if `env` is specified. Use `env.loose` instead.
+   *
+   * Defaults to `false`.
+   */
   loose?: boolean;
 
+  /**
+   * Keep `assert { ... }` clauses of import declarations. They are removed
+   * unless `target` is `esnext`.
+   *
+   * Defaults to `false`.
+   */
+  keepImportAssertions?: boolean;
+
   /**
    * Defaults to EsParserConfig
    */
@@ -257,6 +322,24 @@ export interface JscConfig {
    */
   externalHelpers?: boolean;
 
+  /**
+   * Reference helpers like `_classCallCheck` as globals without injecting
+   * them, for bundlers which provide helpers themselves.
+   */
+  skipHelpersInjection?: boolean;
+
+  /**
+   * Keep all comments, even while minifying. Comments of removed code are
+   * moved to the nearest surviving statement.
+   */
+  preserveAllComments?: boolean;
+
+  /**
+   * Comments to keep in the output. Defaults to `license` (comments starting
+   * with `!`) while minifying and `all` otherwise.
+   */
+  comments?: "all" | "license" | "leading" | "none";
+
   /**
    * Defaults to `es3` (which enableds **all** pass).
    */
@@ -270,7 +353,8 @@ export type JscTarget =
   | "es2016"
   | "es2017"
   | "es2018"
-  | "es2019";
+  | "es2019"
+  | "esnext";
 
 export type ParserConfig = TsParserConfig | EsParserConfig;
 export interface TsParserConfig {
@@ -331,6 +415,10 @@ export interface EsParserConfig {
    * Defaults to `false`
    */
   nullishCoalescing?: boolean;
+  /**
+   * Defaults to `false`
+   */
+  importAssertions?: boolean;
 }
 
 /**
@@ -348,6 +436,68 @@ export interface TransformConfig {
    * Defaults to null, which skips optimizer pass.
    */
   optimizer?: OptimizerConfig;
+
+  /**
+   * Use the legacy (stage 1) decorators proposal.
+   * Always enabled for typescript.
+   */
+  legacyDecorator?: boolean;
+
+  /**
+   * Emit `Reflect.metadata` for decorated classes and members.
+   * Effective only for typescript.
+   */
+  decoratorMetadata?: boolean;
+
+  /**
+   * Convert top-level `require` calls and assignments to `module.exports`
+   * into imports and exports. Modules using other CommonJS patterns are left
+   * untouched.
+   */
+  cjsToEsm?: boolean;
+
+  /**
+   * Renames references to global variables, e.g. `{ "Promise": "SomePolyfill" }`.
+   * Local bindings with the same name are not renamed.
+   */
+  globalRename?: { [name: string]: string };
+
+  /**
+   * Like `useDefineForClassFields` of tsc. If `false`, public class fields are
+   * initialized with assignments, which invoke setters inherited from the
+   * super class. Fields are initialized with `Object.defineProperty` by
+   * default.
+   */
+  useDefineForClassFields?: boolean;
+
+  /**
+   * Replaces `import.meta`, which is available only in es modules.
+   */
+  importMeta?: ImportMetaConfig;
+
+  /**
+   * Merge identical temporary variables created by transforms, like
+   * `var _this = this` declared by two passes. Variables of the input are
+   * never touched.
+   */
+  dedupTempVars?: boolean;
+}
+
+export interface ImportMetaConfig {
+  /**
+   * An expression replacing `import.meta.url`.
+   *
+   * Defaults to `require('url').pathToFileURL(__filename).toString()` if
+   * `module.type` is `commonjs`.
+   */
+  url?: string;
+
+  /**
+   * An expression replacing other usages of `import.meta`.
+   *
+   * Defaults to an object with `url` if `module.type` is `commonjs`.
+   */
+  meta?: string;
 }
 
 export interface ReactConfig {
@@ -401,6 +551,60 @@ export interface ConstModulesConfig {
 
 export interface OptimizerConfig {
   globals?: GlobalPassOption;
+
+  inline?: InlineOption;
+
+  compress?: CompressOption;
+}
+
+/**
+ * Passes of the optimizer to run, named after the compress options of terser.
+ * All passes are enabled by default.
+ */
+export interface CompressOption {
+  /**
+   * Folds constant expressions.
+   */
+  evaluate?: boolean;
+
+  /**
+   * Inlines variables.
+   */
+  inline?: boolean;
+
+  /**
+   * Removes unreachable branches.
+   */
+  deadCode?: boolean;
+
+  /**
+   * Removes unused declarations.
+   */
+  unused?: boolean;
+}
+
+/**
+ * Options for inlining variables, like names of bindings to treat specially.
+ */
+export interface InlineOption {
+  /**
+   * Bindings which are never inlined.
+   */
+  deny?: string[];
+
+  /**
+   * Bindings which are inlined as an alias even if there's a call between
+   * the alias and the aliased variable, as long as the aliased variable is
+   * initialized with a literal and never reassigned.
+   */
+  allow?: string[];
+
+  /**
+   * Maximum nesting depth of expressions and scopes for the inlining pass.
+   * Deeper code fails to compile instead of overflowing the stack while
+   * inlining. Other passes are not guarded. Defaults to `1000`.
+   */
+  maxDepth?: number;
 }
 
 /**
@@ -408,9 +612,10 @@ export interface OptimizerConfig {
  */
 export interface GlobalPassOption {
   /**
-   * Global variables.
+   * Global variables, or dotted paths of properties. Values are parsed as
+   * expressions, and paths take precedence over `envs`.
    *
-   * e.g. `{ __DEBUG__: true }`
+   * e.g. `{ __DEBUG__: "true", "process.env.NODE_ENV": "'production'" }`
    */
   vars?: { [key: string]: string };
 
@@ -420,9 +625,18 @@ export interface GlobalPassOption {
    * Defaults to `["NODE_ENV", "SWC_ENV"]`
    */
   envs?: string[];
+
+  /**
+   * Results of `typeof` for global variables.
+   *
+   * e.g. `{ process: "undefined" }` to remove node-only branches.
+   *
+   * If `env` targets only browsers, `process` defaults to `"undefined"`.
+   */
+  typeofs?: { [key: string]: string };
 }
 
-export type ModuleConfig = CommonJsConfig | UmdConfig | AmdConfig;
+export type ModuleConfig = Es6Config | CommonJsConfig | UmdConfig | AmdConfig;
 
 export interface BaseModuleConfig {
   /**
@@ -442,7 +656,7 @@ export interface BaseModuleConfig {
    *
    * Defaults to `true`.
    */
-  strict_mode?: boolean;
+  strictMode?: boolean;
 
   /**
    * Changes Babel's compiled import statements to be lazily evaluated when their imported bindings are used for the first time.
@@ -492,6 +706,10 @@ export interface BaseModuleConfig {
   noInterop?: boolean;
 }
 
+export interface Es6Config {
+  type: "es6";
+}
+
 export interface CommonJsConfig extends BaseModuleConfig {
   type: "commonjs";
 }
@@ -512,9 +730,24 @@ export interface Output {
    */
   code: string;
   /**
-   * Sourcemap (**not** base64 encoded)
+   * Sourcemap (**not** base64 encoded).
+   *
+   * An object if `sourceMapObject` is enabled.
+   */
+  map?: string | object;
+  /**
+   * Non-minified code, if `emitPrettyAlongside` is enabled.
+   */
+  pretty?: string;
+  /**
+   * Time spent by each pass, if `profile` is enabled.
+   */
+  passTimings?: { name: string; duration: { secs: number; nanos: number } }[];
+  /**
+   * Comments in the code, if `outputComments` is enabled. `line` and `col`
+   * are zero-based positions in the code.
    */
-  map?: string;
+  comments?: { kind: "line" | "block"; text: string; line: number; col: number }[];
 }
 
 export interface MatchPattern { }
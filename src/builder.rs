@@ -1,18 +1,56 @@
 use crate::config::{GlobalPassOption, JscTarget, ModuleConfig};
+use anyhow::{bail, Error};
 use either::Either;
-use std::{collections::HashMap, sync::Arc};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    rc::Rc,
+    sync::Arc,
+};
 use swc_atoms::JsWord;
-use swc_common::{chain, errors::Handler, fold::and_then::AndThen, Mark, SourceMap};
+use swc_common::{
+    chain, errors::Handler, fold::and_then::AndThen, pass::Timed, Fold, Mark, SourceMap,
+};
 use swc_ecmascript::{
+    ast::{Module, Program, Script},
     parser::Syntax,
     preset_env,
     transforms::{
-        compat, const_modules, fixer, helpers, hygiene, modules,
+        compat, const_modules, fixer_with_config, helpers, hygiene, modules, optimization,
         pass::{Optional, Pass},
-        typescript,
+        typescript, FixerConfig,
     },
 };
 
+/// Names of built-in passes, in the order they run.
+///
+/// `typescript`, `resolver` and `optimizer` are added by
+/// [PassBuilder::then_phase] (like [Options::build] does), and the others by
+/// [PassBuilder::finalize]. `optimizer` is the simplifier, which includes
+/// inlining and dce. There's no separate phase for inlining, so a pass which
+/// should run after resolution but before inlining is registered after
+/// `resolver`.
+///
+/// Custom passes can be inserted around them using [PassBuilder::before_pass]
+/// and [PassBuilder::after_pass], or [Options::custom_passes]. Time spent by
+/// them is recorded by name if [TIMINGS](swc_common::pass::TIMINGS) is set.
+///
+/// [Options::build]: crate::config::Options::build
+/// [Options::custom_passes]: crate::config::Options::custom_passes
+pub const PHASES: &[&str] = &[
+    "typescript",
+    "resolver",
+    "optimizer",
+    "compat",
+    "helpers",
+    "module",
+    "hygiene",
+    "fixer",
+];
+
+/// Phases added by [PassBuilder::finalize].
+const FINAL_PHASES: &[&str] = &["compat", "helpers", "module", "hygiene", "fixer"];
+
 /// Builder is used to create a high performance `Compiler`.
 pub struct PassBuilder<'a, 'b, P: Pass> {
     cm: &'a Arc<SourceMap>,
@@ -22,9 +60,109 @@ pub struct PassBuilder<'a, 'b, P: Pass> {
     global_mark: Mark,
     target: JscTarget,
     loose: bool,
+    preserve_parens: bool,
+    dedup_temp_vars: bool,
+    /// Shared with phases added by [PassBuilder::then_phase], so passes can
+    /// be registered for them later.
+    hooks: HashMap<&'static str, Rc<RefCell<Hooks>>>,
+    /// Phases added by [PassBuilder::then_phase].
+    added: HashSet<&'static str>,
+}
+
+/// A custom pass to run right before or after a built-in pass.
+pub struct CustomPass {
+    phase: &'static str,
+    after: bool,
+    pass: Box<dyn Pass>,
+}
+
+impl CustomPass {
+    /// Runs `pass` right before the pass named `name`, which should be one of
+    /// [PHASES].
+    ///
+    /// Returns an error if `name` is not a known phase.
+    pub fn before(name: &str, pass: impl Pass + 'static) -> Result<Self, Error> {
+        Ok(CustomPass {
+            phase: phase_name(name)?,
+            after: false,
+            pass: box pass,
+        })
+    }
+
+    /// Runs `pass` right after the pass named `name`, which should be one of
+    /// [PHASES].
+    ///
+    /// Returns an error if `name` is not a known phase.
+    pub fn after(name: &str, pass: impl Pass + 'static) -> Result<Self, Error> {
+        Ok(CustomPass {
+            phase: phase_name(name)?,
+            after: true,
+            pass: box pass,
+        })
+    }
+}
+
+/// Custom passes registered for a phase.
+#[derive(Default)]
+struct Hooks {
+    before: Passes,
+    after: Passes,
+}
+
+/// A built-in pass surrounded by custom passes registered for it.
+struct Phase<P> {
+    pass: Timed<P>,
+    hooks: Rc<RefCell<Hooks>>,
+}
+
+/// Runs passes in order.
+#[derive(Default)]
+struct Passes(Vec<Box<dyn Pass>>);
+
+macro_rules! impl_passes {
+    ($T:ty) => {
+        impl Fold<$T> for Passes {
+            fn fold(&mut self, mut node: $T) -> $T {
+                for pass in &mut self.0 {
+                    node = pass.fold(node);
+                }
+
+                node
+            }
+        }
+    };
+}
+
+impl_passes!(Program);
+impl_passes!(Module);
+impl_passes!(Script);
+
+macro_rules! impl_phase {
+    ($T:ty) => {
+        impl<P> Fold<$T> for Phase<P>
+        where
+            Timed<P>: Fold<$T>,
+        {
+            fn fold(&mut self, node: $T) -> $T {
+                let mut hooks = self.hooks.borrow_mut();
+
+                let node = hooks.before.fold(node);
+                let node = self.pass.fold(node);
+                hooks.after.fold(node)
+            }
+        }
+    };
 }
 
+impl_phase!(Program);
+impl_phase!(Module);
+impl_phase!(Script);
+
 impl<'a, 'b, P: Pass> PassBuilder<'a, 'b, P> {
+    /// `loose` enables loose mode of compat passes added by
+    /// [PassBuilder::finalize]. See [JscConfig::loose] for details.
+    ///
+    /// [JscConfig::loose]: crate::config::JscConfig::loose
     pub fn new(
         cm: &'a Arc<SourceMap>,
         handler: &'b Handler,
@@ -40,6 +178,13 @@ impl<'a, 'b, P: Pass> PassBuilder<'a, 'b, P> {
             global_mark,
             loose,
             env: None,
+            preserve_parens: false,
+            dedup_temp_vars: false,
+            hooks: PHASES
+                .iter()
+                .map(|&name| (name, Default::default()))
+                .collect(),
+            added: Default::default(),
         }
     }
 
@@ -53,9 +198,79 @@ impl<'a, 'b, P: Pass> PassBuilder<'a, 'b, P> {
             loose: self.loose,
             env: self.env,
             global_mark: self.global_mark,
+            preserve_parens: self.preserve_parens,
+            dedup_temp_vars: self.dedup_temp_vars,
+            hooks: self.hooks,
+            added: self.added,
         }
     }
 
+    /// Runs `pass` as the built-in pass named `name`, which should be one of
+    /// [PHASES], after passes added so far.
+    ///
+    /// Custom passes registered for `name` run around `pass`, even if they are
+    /// registered later.
+    ///
+    /// Returns an error if `name` is not a known phase.
+    pub fn then_phase(
+        self,
+        name: &str,
+        pass: impl Pass,
+    ) -> Result<PassBuilder<'a, 'b, impl Pass>, Error> {
+        let name = phase_name(name)?;
+
+        Ok(self.then_known_phase(name, pass))
+    }
+
+    /// [PassBuilder::then_phase] for a name from [PHASES].
+    pub(crate) fn then_known_phase(
+        mut self,
+        name: &'static str,
+        pass: impl Pass,
+    ) -> PassBuilder<'a, 'b, impl Pass> {
+        self.added.insert(name);
+        let hooks = self.hooks[name].clone();
+
+        self.then(Phase {
+            pass: Timed::with_name(name, pass),
+            hooks,
+        })
+    }
+
+    /// Runs `pass` right before the pass named `name`, which should be one of
+    /// [PHASES]. Passes added to the same phase run in the order they are
+    /// added.
+    ///
+    /// Returns an error if `name` is not a known phase.
+    pub fn before_pass(self, name: &str, pass: impl Pass + 'static) -> Result<Self, Error> {
+        Ok(self.custom_pass(CustomPass::before(name, pass)?))
+    }
+
+    /// Runs `pass` right after the pass named `name`, which should be one of
+    /// [PHASES]. Passes added to the same phase run in the order they are
+    /// added.
+    ///
+    /// Returns an error if `name` is not a known phase.
+    pub fn after_pass(self, name: &str, pass: impl Pass + 'static) -> Result<Self, Error> {
+        Ok(self.custom_pass(CustomPass::after(name, pass)?))
+    }
+
+    /// Registers `pass` for its phase, like [PassBuilder::before_pass] and
+    /// [PassBuilder::after_pass].
+    pub fn custom_pass(self, pass: CustomPass) -> Self {
+        {
+            let mut hooks = self.hooks[pass.phase].borrow_mut();
+            let passes = if pass.after {
+                &mut hooks.after
+            } else {
+                &mut hooks.before
+            };
+            passes.0.push(pass.pass);
+        }
+
+        self
+    }
+
     pub fn const_modules(
         self,
         globals: HashMap<JsWord, HashMap<JsWord, String>>,
@@ -82,6 +297,19 @@ impl<'a, 'b, P: Pass> PassBuilder<'a, 'b, P> {
         self
     }
 
+    /// Keep parens of the input even if they are not required.
+    pub fn preserve_parens(mut self, preserve_parens: bool) -> Self {
+        self.preserve_parens = preserve_parens;
+        self
+    }
+
+    /// Merge identical temporary variables created by transforms, like two
+    /// `var _this = this`, right before the hygiene pass.
+    pub fn dedup_temp_vars(mut self, enabled: bool) -> Self {
+        self.dedup_temp_vars = enabled;
+        self
+    }
+
     /// # Arguments
     /// ## module
     ///  - Use `None` if you want swc to emit import statements.
@@ -94,17 +322,38 @@ impl<'a, 'b, P: Pass> PassBuilder<'a, 'b, P> {
     ///  - helper injector
     ///  - identifier hygiene handler
     ///  - fixer
+    ///
+    /// # Panics
+    ///
+    /// Panics if custom passes are registered for a phase which is not added
+    /// by [PassBuilder::then_phase], as they would never run.
     pub fn finalize(
         self,
         root_mark: Mark,
         syntax: Syntax,
         module: Option<ModuleConfig>,
     ) -> impl Pass {
+        for (&name, hooks) in &self.hooks {
+            let hooks = hooks.borrow();
+            let is_empty = hooks.before.0.is_empty() && hooks.after.0.is_empty();
+            if !is_empty && !self.added.contains(name) && !FINAL_PHASES.contains(&name) {
+                panic!(
+                    "PassBuilder: custom passes are registered for `{}`, but it's not added by \
+                     `then_phase`",
+                    name
+                )
+            }
+        }
+
         let need_interop_analysis = match module {
             Some(ModuleConfig::CommonJs(ref c)) => !c.no_interop,
             Some(ModuleConfig::Amd(ref c)) => !c.config.no_interop,
             Some(ModuleConfig::Umd(ref c)) => !c.config.no_interop,
-            None => false,
+            Some(ModuleConfig::Es6) | None => false,
+        };
+        let require_helpers = match module {
+            Some(ModuleConfig::CommonJs(..)) => true,
+            _ => false,
         };
 
         // compat
@@ -137,20 +386,69 @@ impl<'a, 'b, P: Pass> PassBuilder<'a, 'b, P> {
             ))
         };
 
+        let hooks = &self.hooks;
+
         chain!(
             self.pass,
-            compat_pass,
+            phase(hooks, "compat", compat_pass),
             // module / helper
             Optional::new(
-                modules::import_analysis::import_analyzer(),
+                Timed::with_name(
+                    "import-analysis",
+                    modules::import_analysis::import_analyzer()
+                ),
                 need_interop_analysis
             ),
-            helpers::InjectHelpers,
-            ModuleConfig::build(self.cm.clone(), root_mark, module),
+            phase(
+                hooks,
+                "helpers",
+                helpers::inject_helpers_with_config(helpers::InjectConfig {
+                    require: require_helpers
+                })
+            ),
+            phase(
+                hooks,
+                "module",
+                ModuleConfig::build(self.cm.clone(), root_mark, module)
+            ),
+            Optional::new(
+                Timed::with_name("dedup-temp-vars", optimization::dedup_temp_vars()),
+                self.dedup_temp_vars
+            ),
             // hygiene
-            hygiene(),
+            phase(hooks, "hygiene", hygiene()),
             // fixer
-            fixer(),
+            phase(
+                hooks,
+                "fixer",
+                fixer_with_config(FixerConfig {
+                    preserve_parens: self.preserve_parens
+                })
+            ),
         )
     }
 }
+
+/// Surrounds `pass` with custom passes registered for `name`.
+fn phase(
+    hooks: &HashMap<&'static str, Rc<RefCell<Hooks>>>,
+    name: &'static str,
+    pass: impl Pass,
+) -> impl Pass {
+    Phase {
+        pass: Timed::with_name(name, pass),
+        hooks: hooks[name].clone(),
+    }
+}
+
+/// Returns the name in [PHASES] which is equal to `name`.
+fn phase_name(name: &str) -> Result<&'static str, Error> {
+    match PHASES.iter().find(|&&phase| phase == name) {
+        Some(&phase) => Ok(phase),
+        None => bail!(
+            "PassBuilder: unknown pass `{}`; expected one of {:?}",
+            name,
+            PHASES
+        ),
+    }
+}
@@ -1,29 +1,39 @@
-use crate::builder::PassBuilder;
+use crate::builder::{CustomPass, PassBuilder};
 use anyhow::{bail, Context, Error};
 use dashmap::DashMap;
 use once_cell::sync::Lazy;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{HashMap, HashSet},
-    env,
-    path::{Path, PathBuf},
-    sync::Arc,
+    borrow::Cow,
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    convert::TryFrom,
+    env, fmt,
+    hash::Hasher,
+    io,
+    path::{Component, Path, PathBuf},
+    sync::{atomic::AtomicBool, Arc},
     usize,
 };
 use swc_atoms::JsWord;
 pub use swc_common::chain;
-use swc_common::{errors::Handler, FileName, Mark, SourceMap};
+use swc_common::{comments::Comment, errors::Handler, FileName, Mark, SourceMap};
 pub use swc_ecmascript::parser::JscTarget;
 use swc_ecmascript::{
     ast::{Expr, ExprStmt, ModuleItem, Stmt},
+    codegen::{self, NumberFormat, QuoteStyle, TrailingComma},
     parser::{lexer::Lexer, Parser, Session as ParseSess, SourceFileInput, Syntax, TsConfig},
     preset_env,
     transforms::{
-        const_modules, modules,
-        optimization::{simplifier, InlineGlobals, JsonParse},
+        const_modules, global_rename, modules,
+        optimization::{
+            drop_console, drop_debugger, simplifier, simplify, InlineGlobals, JsonParse,
+        },
         pass::{noop, Optional, Pass},
-        proposals::{class_properties, decorators, export, nullish_coalescing, optional_chaining},
+        proposals::{
+            class_properties, class_properties_with_config, decorators, export, import_assertions,
+            nullish_coalescing, optional_chaining,
+        },
         react, resolver_with_mark, typescript,
     },
 };
@@ -87,20 +97,135 @@ pub struct Options {
     #[serde(default)]
     pub source_maps: Option<SourceMapsConfig>,
 
+    /// The name of the file in `sources` of the source map.
+    ///
+    /// The name of the input file is used if this is not specified.
     #[serde(default)]
     pub source_file_name: Option<String>,
 
+    /// Translates the name of a source file to the name in `sources` of the
+    /// source map, e.g. to turn a synthetic module id into a url.
+    ///
+    /// `source_file_name` takes precedence over this for the input file.
+    #[serde(skip)]
+    pub filename_map: Option<Arc<dyn Fn(&FileName) -> String + Send + Sync>>,
+
+    /// Source files matching this are added to `x_google_ignoreList` of the
+    /// source map, so debuggers can skip generated or vendored code.
+    #[serde(skip)]
+    pub source_map_ignore: Option<Arc<dyn Fn(&FileName) -> bool + Send + Sync>>,
+
+    /// Rewrites module specifiers of imports, re-exports and dynamic
+    /// imports, e.g. to turn a relative path into an absolute path.
+    ///
+    /// It's called with the specifier and the name of the importing file,
+    /// and the specifier is replaced if it returns `Some`.
+    #[serde(skip)]
+    pub resolve_import: Option<Arc<dyn Fn(&str, &FileName) -> Option<String> + Send + Sync>>,
+
+    /// Custom passes to run around built-in passes named in [PHASES].
+    ///
+    /// It's called whenever the config is built, as a pass can't be reused.
+    ///
+    /// [PHASES]: crate::PHASES
+    #[serde(skip)]
+    pub custom_passes: Option<Arc<dyn Fn() -> Vec<CustomPass> + Send + Sync>>,
+
     #[serde(default)]
     pub source_root: Option<String>,
 
+    /// Path of the output file.
+    ///
+    /// If this is specified and `source_maps` is `true`, a
+    /// `sourceMappingURL` comment pointing to the source map file is
+    /// appended to the output. The url is relative to the directory of the
+    /// output file.
+    #[serde(default)]
+    pub output_path: Option<PathBuf>,
+
+    /// Path of the source map file, `<output_path>.map` by default.
+    ///
+    /// Ignored if `output_path` is not specified.
+    #[serde(default)]
+    pub source_map_path: Option<PathBuf>,
+
+    /// Embeds the source of input files to `sourcesContent` of the source
+    /// map, so debuggers can show them without access to the files.
+    #[serde(default)]
+    pub sources_content: bool,
+
+    /// Records source files in `sources` of the source map by their paths
+    /// relative to `root`, so the source map doesn't depend on the location
+    /// of the project.
+    ///
+    /// Files outside of `root` are recorded by their absolute paths with a
+    /// warning. `filename_map` takes precedence over this.
+    #[serde(default)]
+    pub relative_sources: bool,
+
     #[serde(default = "default_is_module")]
     pub is_module: bool,
+
+    /// Records time spent by passes to [TransformOutput::pass_timings].
+    ///
+    /// [TransformOutput::pass_timings]: crate::TransformOutput::pass_timings
+    #[serde(default)]
+    pub profile: bool,
+
+    /// Stores the source map to [TransformOutput::map_object] instead of
+    /// [TransformOutput::map], so it's serialized as a json object instead
+    /// of a string.
+    ///
+    /// [TransformOutput::map]: crate::TransformOutput::map
+    /// [TransformOutput::map_object]: crate::TransformOutput::map_object
+    #[serde(default)]
+    pub source_map_object: bool,
+
+    /// Also prints the transformed program without minification to
+    /// [TransformOutput::pretty].
+    ///
+    /// Both outputs are printed from the same transformed program, so passes
+    /// run only once and the cost is a second codegen run. Comments not
+    /// preserved by `minify` are not in the pretty output either.
+    ///
+    /// [TransformOutput::pretty]: crate::TransformOutput::pretty
+    #[serde(default)]
+    pub emit_pretty_alongside: bool,
+
+    /// Stores comments emitted in the output, with their positions in the
+    /// output, to [TransformOutput::comments].
+    ///
+    /// [TransformOutput::comments]: crate::TransformOutput::comments
+    #[serde(default)]
+    pub output_comments: bool,
+
+    /// Makes processing fail if a pass reports a warning.
+    #[serde(default)]
+    pub fail_on_warnings: bool,
 }
 
 fn default_is_module() -> bool {
     true
 }
 
+/// Returns the url of `map_path` relative to the directory of `output_path`.
+pub(crate) fn source_map_url(output_path: &Path, map_path: &Path) -> String {
+    fn components(path: &Path) -> Vec<Component> {
+        path.components()
+            .filter(|c| *c != Component::CurDir)
+            .collect()
+    }
+
+    let from = output_path.parent().map(components).unwrap_or_default();
+    let to = components(map_path);
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+
+    let mut parts = vec![Cow::Borrowed(".."); from.len() - common];
+    parts.extend(to[common..].iter().map(|c| c.as_os_str().to_string_lossy()));
+
+    parts.join("/")
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 #[serde(untagged)]
 pub enum SourceMapsConfig {
@@ -146,19 +271,61 @@ impl Options {
         handler: &Handler,
         is_module: bool,
         config: Option<Config>,
+    ) -> BuiltConfig<impl Pass> {
+        self.build_with_override(cm, handler, is_module, config, None)
+    }
+
+    /// Same as [Options::build], but `override_config` is applied after
+    /// `config` of `self`, so it wins over both `config` and `self`.
+    ///
+    /// See [Override] for how it's applied.
+    pub(crate) fn build_with_override(
+        &self,
+        cm: &Arc<SourceMap>,
+        handler: &Handler,
+        is_module: bool,
+        config: Option<Config>,
+        override_config: Option<&Config>,
     ) -> BuiltConfig<impl Pass> {
         let mut config = config.unwrap_or_else(Default::default);
         if let Some(ref c) = self.config {
             config.merge(c)
         }
+        if let Some(c) = override_config {
+            config.override_with(c)
+        }
+
+        let fingerprint = self.fingerprint(&config, is_module);
+        let codegen = config.codegen_config();
+        let preserve_parens = !codegen.minify
+            && config
+                .jsc
+                .output
+                .as_ref()
+                .map_or(false, |output| output.preserve_parens);
 
         let JscConfig {
             transform,
             syntax,
             external_helpers,
+            skip_helpers_injection,
+            preserve_all_comments,
+            comments,
             target,
             loose,
+            keep_import_assertions,
+            ..
         } = config.jsc;
+        let minify = config.minify.unwrap_or(false);
+        let comments = if preserve_all_comments {
+            CommentsConfig::All
+        } else {
+            comments.unwrap_or(if minify {
+                CommentsConfig::License
+            } else {
+                CommentsConfig::All
+            })
+        };
 
         let syntax = syntax.unwrap_or_default();
         let mut transform = transform.unwrap_or_default();
@@ -168,6 +335,44 @@ impl Options {
         }
         let optimizer = transform.optimizer;
         let enable_optimizer = optimizer.is_some();
+        let drop_debugger_enabled = optimizer.as_ref().map_or(false, |o| o.drop_debugger);
+
+        let drop_console_pass = {
+            let (enabled, methods) = match optimizer.as_ref().and_then(|o| o.drop_console.clone()) {
+                Some(DropConsoleOption::All(enabled)) => (enabled, None),
+                Some(DropConsoleOption::Methods(methods)) => (true, Some(methods)),
+                None => (false, None),
+            };
+
+            Optional::new(drop_console(methods), enabled)
+        };
+
+        let too_deep = Arc::new(AtomicBool::new(false));
+        let simplifier_pass = {
+            let inline = optimizer
+                .as_ref()
+                .and_then(|o| o.inline.clone())
+                .unwrap_or_default();
+            let compress = optimizer
+                .as_ref()
+                .and_then(|o| o.compress)
+                .unwrap_or_default();
+
+            simplifier(simplify::Config {
+                inlining: simplify::inlining::Config {
+                    deny: inline.deny.into_iter().map(JsWord::from).collect(),
+                    allow: inline.allow.into_iter().map(JsWord::from).collect(),
+                    max_depth: inline.max_depth,
+                    too_deep: too_deep.clone(),
+                    ..Default::default()
+                },
+                evaluate: compress.evaluate,
+                inline: compress.inline,
+                dead_code: compress.dead_code,
+                unused: compress.unused,
+                ..Default::default()
+            })
+        };
 
         let const_modules = {
             let enabled = transform.const_modules.is_some();
@@ -190,52 +395,130 @@ impl Options {
             }
         };
 
+        let is_browser_target = config
+            .env
+            .as_ref()
+            .and_then(|env| preset_env::Versions::try_from(env.targets.clone()).ok())
+            .map_or(false, |v| {
+                !v.is_any_target() && v.node.is_none() && v.electron.is_none()
+            });
         let optimization = {
-            let pass =
-                if let Some(opts) = optimizer.map(|o| o.globals.unwrap_or_else(Default::default)) {
-                    opts.build(cm, handler)
-                } else {
-                    GlobalPassOption::default().build(cm, handler)
-                };
+            let pass = if let Some(mut opts) =
+                optimizer.map(|o| o.globals.unwrap_or_else(Default::default))
+            {
+                // `process` is not defined in browsers.
+                if is_browser_target {
+                    opts.typeofs
+                        .entry("process".into())
+                        .or_insert_with(|| "undefined".into());
+                }
+                opts.build(cm, handler)
+            } else {
+                GlobalPassOption::default().build(cm, handler)
+            };
 
             pass
         };
 
+        let import_meta_pass = modules::import_meta::import_meta(
+            transform
+                .import_meta
+                .clone()
+                .map(|opts| opts.build(cm, handler, &config.module))
+                .unwrap_or_default(),
+        );
+
+        let resolve_import_pass = modules::resolve_import::resolve_import(
+            cm.clone(),
+            self.resolve_import
+                .clone()
+                .unwrap_or_else(|| Arc::new(|_: &str, _: &FileName| None)),
+        );
+
         let root_mark = Mark::fresh(Mark::root());
 
-        let pass = chain!(
-            // handle jsx
-            Optional::new(react::react(cm.clone(), transform.react), syntax.jsx()),
-            Optional::new(typescript::strip(), syntax.typescript()),
-            Optional::new(nullish_coalescing(), syntax.nullish_coalescing()),
-            Optional::new(optional_chaining(), syntax.optional_chaining()),
-            resolver_with_mark(root_mark),
-            const_modules,
-            optimization,
-            Optional::new(
-                decorators(decorators::Config {
-                    legacy: transform.legacy_decorator
-                }),
-                syntax.decorators()
-            ),
-            Optional::new(class_properties(), syntax.class_props()),
-            Optional::new(
-                export(),
-                syntax.export_default_from() || syntax.export_namespace_from()
+        // Proposals are preserved if targeting esnext.
+        let downlevel = target < JscTarget::EsNext;
+
+        let mut builder = PassBuilder::new(
+            &cm,
+            &handler,
+            loose,
+            root_mark,
+            chain!(
+                // handle jsx
+                Optional::new(react::react(cm.clone(), transform.react), syntax.jsx()),
+                Optional::new(
+                    decorators::metadata(),
+                    syntax.typescript() && syntax.decorators() && transform.decorator_metadata
+                )
             ),
-            Optional::new(simplifier(Default::default()), enable_optimizer),
-            json_parse_pass
         );
+        if let Some(ref custom_passes) = self.custom_passes {
+            for pass in custom_passes() {
+                builder = builder.custom_pass(pass);
+            }
+        }
 
-        let pass = PassBuilder::new(&cm, &handler, loose, root_mark, pass)
+        let pass = builder
+            .then_known_phase(
+                "typescript",
+                Optional::new(typescript::strip(), syntax.typescript()),
+            )
+            .then(chain!(
+                Optional::new(
+                    nullish_coalescing(),
+                    syntax.nullish_coalescing() && downlevel
+                ),
+                Optional::new(optional_chaining(), syntax.optional_chaining() && downlevel),
+                Optional::new(import_assertions(), downlevel && !keep_import_assertions),
+                Optional::new(modules::cjs_to_esm::cjs_to_esm(), transform.cjs_to_esm),
+                Optional::new(import_meta_pass, transform.import_meta.is_some()),
+                Optional::new(resolve_import_pass, self.resolve_import.is_some())
+            ))
+            .then_known_phase("resolver", resolver_with_mark(root_mark))
+            .then(chain!(
+                global_rename(transform.global_rename),
+                const_modules,
+                optimization,
+                Optional::new(
+                    decorators(decorators::Config {
+                        legacy: transform.legacy_decorator
+                    }),
+                    syntax.decorators()
+                ),
+                Optional::new(
+                    class_properties_with_config(class_properties::Config {
+                        set_public_fields: transform.use_define_for_class_fields == Some(false)
+                    }),
+                    syntax.class_props() && downlevel
+                ),
+                Optional::new(
+                    export(),
+                    (syntax.export_default_from() || syntax.export_namespace_from()) && downlevel
+                ),
+                Optional::new(drop_debugger(), drop_debugger_enabled),
+                drop_console_pass
+            ))
+            .then_known_phase(
+                "optimizer",
+                Optional::new(simplifier_pass, enable_optimizer),
+            )
+            .then(json_parse_pass)
             .target(target)
             .preset_env(config.env)
+            .preserve_parens(preserve_parens)
+            .dedup_temp_vars(transform.dedup_temp_vars)
             .finalize(root_mark, syntax, config.module);
 
         BuiltConfig {
-            minify: config.minify.unwrap_or(false),
+            minify,
+            codegen,
             pass,
             external_helpers,
+            skip_helpers_injection,
+            preserve_all_comments,
+            comments,
             syntax,
             target,
             is_module,
@@ -244,7 +527,108 @@ impl Options {
                 .clone()
                 .unwrap_or(SourceMapsConfig::Bool(false)),
             input_source_map: self.input_source_map.clone(),
+            source_file_name: self.source_file_name.clone(),
+            filename_map: self.filename_map.clone(),
+            source_map_ignore: self.source_map_ignore.clone(),
+            sources_content: self.sources_content,
+            sources_root: if self.relative_sources {
+                Some(self.root.clone().unwrap_or_else(|| {
+                    if cfg!(target_arch = "wasm32") {
+                        PathBuf::new()
+                    } else {
+                        env::current_dir().unwrap()
+                    }
+                }))
+            } else {
+                None
+            },
+            source_map_url: self.output_path.as_ref().map(|output_path| {
+                let map_path = match self.source_map_path {
+                    Some(ref path) => path.clone(),
+                    None => {
+                        let mut path = output_path.clone().into_os_string();
+                        path.push(".map");
+                        path.into()
+                    }
+                };
+
+                source_map_url(output_path, &map_path)
+            }),
+            profile: self.profile,
+            source_map_object: self.source_map_object,
+            emit_pretty_alongside: self.emit_pretty_alongside,
+            output_comments: self.output_comments,
+            fail_on_warnings: self.fail_on_warnings,
+            too_deep,
+            fingerprint,
+        }
+    }
+
+    /// Hashes `config` and options of `self` which affect the output.
+    ///
+    /// Callbacks like `filename_map` can't be hashed, so only whether they are
+    /// specified is hashed.
+    pub(crate) fn fingerprint(&self, config: &Config, is_module: bool) -> u64 {
+        // Hashed as json, as keys of json objects are sorted while the
+        // iteration order of a `HashMap` is not stable.
+        let mut json = serde_json::json!({
+            "jsc": config.jsc,
+            "module": config.module,
+            "minify": config.minify,
+            "isModule": is_module,
+            "sourceMaps": self.source_maps,
+            "inputSourceMap": self.input_source_map,
+            "sourceFileName": self.source_file_name,
+            "sourceRoot": self.source_root,
+            "outputPath": self.output_path,
+            "sourceMapPath": self.source_map_path,
+            "sourcesContent": self.sources_content,
+            "relativeSources": self.relative_sources,
+            "root": self.root,
+            "sourceMapObject": self.source_map_object,
+            "emitPrettyAlongside": self.emit_pretty_alongside,
+            "outputComments": self.output_comments,
+            "failOnWarnings": self.fail_on_warnings,
+            "filenameMap": self.filename_map.is_some(),
+            "sourceMapIgnore": self.source_map_ignore.is_some(),
+            "resolveImport": self.resolve_import.is_some(),
+            "customPasses": self.custom_passes.is_some(),
+        });
+
+        sort_arrays(&mut json);
+
+        let mut hasher = DefaultHasher::new();
+        hasher.write(json.to_string().as_bytes());
+        // `preset_env::Config` is not serializable. Targets are hashed after
+        // resolving them, as the order of a map of targets is not stable and a
+        // browserslist query like the default `""` depends on the installed
+        // browser data.
+        if let Some(ref env) = config.env {
+            match preset_env::Versions::try_from(env.targets.clone()) {
+                Ok(versions) => hasher.write(format!("{:?}", versions).as_bytes()),
+                Err(()) => hasher.write(format!("{:?}", env.targets).as_bytes()),
+            }
+            let env = preset_env::Config {
+                targets: None,
+                ..env.clone()
+            };
+            hasher.write(format!("{:?}", env).as_bytes());
+        }
+        hasher.finish()
+    }
+}
+
+/// Sorts arrays in `value` recursively, as arrays serialized from a
+/// `HashSet` are not ordered. The order of arrays in the config doesn't
+/// affect the output.
+fn sort_arrays(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Array(values) => {
+            values.iter_mut().for_each(sort_arrays);
+            values.sort_by_cached_key(|v| v.to_string());
         }
+        serde_json::Value::Object(map) => map.values_mut().for_each(sort_arrays),
+        _ => {}
     }
 }
 
@@ -271,7 +655,17 @@ const fn default_swcrc() -> bool {
 #[serde(untagged)]
 pub enum ConfigFile {
     Bool(bool),
+    /// Path to a config file.
     Str(String),
+    /// Content of a config file, which is used without touching the file
+    /// system.
+    ///
+    /// Like a config file specified by path, it takes precedence over a
+    /// `.swcrc` file found while walking up from the input file, and
+    /// [Options::config] takes precedence over it.
+    Inline {
+        inline: String,
+    },
 }
 
 impl Default for ConfigFile {
@@ -310,8 +704,13 @@ impl Default for Rc {
                     syntax: Some(Default::default()),
                     transform: None,
                     external_helpers: false,
+                    skip_helpers_injection: false,
+                    preserve_all_comments: false,
+                    comments: None,
                     target: Default::default(),
                     loose: false,
+                    keep_import_assertions: false,
+                    output: None,
                 },
                 module: None,
                 minify: None,
@@ -327,8 +726,13 @@ impl Default for Rc {
                     })),
                     transform: None,
                     external_helpers: false,
+                    skip_helpers_injection: false,
+                    preserve_all_comments: false,
+                    comments: None,
                     target: Default::default(),
                     loose: false,
+                    keep_import_assertions: false,
+                    output: None,
                 },
                 module: None,
                 minify: None,
@@ -344,8 +748,13 @@ impl Default for Rc {
                     })),
                     transform: None,
                     external_helpers: false,
+                    skip_helpers_injection: false,
+                    preserve_all_comments: false,
+                    comments: None,
                     target: Default::default(),
                     loose: false,
+                    keep_import_assertions: false,
+                    output: None,
                 },
                 module: None,
                 minify: None,
@@ -387,6 +796,119 @@ impl Rc {
     }
 }
 
+impl From<Config> for Rc {
+    fn from(c: Config) -> Self {
+        Rc::Single(c)
+    }
+}
+
+/// Looks up `.swcrc` files while resolving the config of a file.
+///
+/// [Compiler::config_for_file] walks up the directory tree of a file and asks
+/// the resolver for `.swcrc` in each directory.
+///
+/// A `HashMap` from the path of a `.swcrc` file to its content can be used as
+/// a resolver for virtual file systems.
+///
+/// [Compiler::config_for_file]: crate::Compiler::config_for_file
+pub trait SwcrcResolver {
+    /// Returns `Ok(None)` if `path` does not exist.
+    fn resolve(&self, path: &Path) -> Result<Option<Rc>, Error>;
+}
+
+/// Reads `.swcrc` files from the file system.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FsSwcrcResolver;
+
+impl SwcrcResolver for FsSwcrcResolver {
+    fn resolve(&self, path: &Path) -> Result<Option<Rc>, Error> {
+        match crate::load_swcrc(path) {
+            Ok(rc) => Ok(Some(rc)),
+            Err(SwcrcError::NotFound { .. }) => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+}
+
+/// Error while loading a config file.
+#[derive(Debug)]
+pub enum SwcrcError {
+    NotFound {
+        path: PathBuf,
+    },
+    PermissionDenied {
+        path: PathBuf,
+        source: io::Error,
+    },
+    /// Other io errors.
+    Read {
+        path: PathBuf,
+        source: io::Error,
+    },
+    Parse {
+        path: PathBuf,
+        source: Error,
+    },
+}
+
+impl SwcrcError {
+    pub(crate) fn from_io(path: &Path, source: io::Error) -> Self {
+        let path = path.to_path_buf();
+
+        match source.kind() {
+            io::ErrorKind::NotFound => SwcrcError::NotFound { path },
+            io::ErrorKind::PermissionDenied => SwcrcError::PermissionDenied { path, source },
+            _ => SwcrcError::Read { path, source },
+        }
+    }
+
+    pub fn path(&self) -> &Path {
+        match *self {
+            SwcrcError::NotFound { ref path }
+            | SwcrcError::PermissionDenied { ref path, .. }
+            | SwcrcError::Read { ref path, .. }
+            | SwcrcError::Parse { ref path, .. } => path,
+        }
+    }
+}
+
+impl fmt::Display for SwcrcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let path = self.path().display();
+
+        match *self {
+            SwcrcError::NotFound { .. } => write!(f, "config file '{}' does not exist", path),
+            SwcrcError::PermissionDenied { .. } => {
+                write!(f, "permission denied while reading config file '{}'", path)
+            }
+            SwcrcError::Read { .. } => write!(f, "failed to read config (.swcrc) file '{}'", path),
+            SwcrcError::Parse { .. } => {
+                write!(f, "failed to parse config (.swcrc) file '{}'", path)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SwcrcError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            SwcrcError::NotFound { .. } => None,
+            SwcrcError::PermissionDenied { ref source, .. }
+            | SwcrcError::Read { ref source, .. } => Some(source),
+            SwcrcError::Parse { ref source, .. } => Some(&**source),
+        }
+    }
+}
+
+impl<C> SwcrcResolver for HashMap<PathBuf, C>
+where
+    C: Clone + Into<Rc>,
+{
+    fn resolve(&self, path: &Path) -> Result<Option<Rc>, Error> {
+        Ok(self.get(path).cloned().map(Into::into))
+    }
+}
+
 /// A single object in the `.swcrc` file
 #[derive(Debug, Default, Clone, Deserialize)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
@@ -473,6 +995,53 @@ impl Config {
 
         Ok(true)
     }
+
+    /// Builds the configuration of the code generator.
+    pub fn codegen_config(&self) -> codegen::Config {
+        let output = self.jsc.output.clone().unwrap_or_default();
+
+        codegen::Config {
+            minify: self.minify.unwrap_or(false),
+            quotes: output.quotes.unwrap_or_default(),
+            max_line_len: output.max_line_len,
+            trailing_comma: output.trailing_comma,
+            target: self.jsc.target,
+            preserved_template_tags: output.preserve_template_tags.unwrap_or_default(),
+            omit_last_semi: output.omit_last_semi,
+            number_format: output.number_format,
+            shorthand: output.shorthand,
+        }
+    }
+
+    /// Returns descriptions of invalid combinations of options.
+    pub(crate) fn validate(&self, is_module: bool) -> Vec<String> {
+        let mut errors = vec![];
+        let syntax = self.jsc.syntax.unwrap_or_default();
+        let transform = self.jsc.transform.clone().unwrap_or_default();
+
+        if transform.legacy_decorator && !syntax.decorators() {
+            errors.push(
+                "`jsc.transform.legacyDecorator` requires `jsc.parser.decorators`".to_string(),
+            );
+        }
+        if transform.decorator_metadata && !(syntax.typescript() && syntax.decorators()) {
+            errors.push(
+                "`jsc.transform.decoratorMetadata` requires typescript syntax with \
+                 `jsc.parser.decorators`"
+                    .to_string(),
+            );
+        }
+        if !is_module && self.module.is_some() {
+            errors.push("`module` cannot be used if `isModule` is false".to_string());
+        }
+        if self.jsc.external_helpers && self.jsc.skip_helpers_injection {
+            errors.push(
+                "`jsc.externalHelpers` cannot be used with `jsc.skipHelpersInjection`".to_string(),
+            );
+        }
+
+        errors
+    }
 }
 
 /// One `BuiltConfig` per a directory with swcrc
@@ -481,10 +1050,38 @@ pub struct BuiltConfig<P: Pass> {
     pub syntax: Syntax,
     pub target: JscTarget,
     pub minify: bool,
+    pub codegen: codegen::Config,
     pub external_helpers: bool,
+    pub skip_helpers_injection: bool,
+    pub preserve_all_comments: bool,
+    pub comments: CommentsConfig,
     pub source_maps: SourceMapsConfig,
     pub input_source_map: InputSourceMap,
+    pub source_file_name: Option<String>,
+    pub filename_map: Option<Arc<dyn Fn(&FileName) -> String + Send + Sync>>,
+    pub source_map_ignore: Option<Arc<dyn Fn(&FileName) -> bool + Send + Sync>>,
+    pub sources_content: bool,
+    /// Paths of source files in the source map are relative to this, if
+    /// [Options::relative_sources] is enabled.
+    pub sources_root: Option<PathBuf>,
+    /// Url of the external source map, relative to the output file.
+    pub source_map_url: Option<String>,
     pub is_module: bool,
+    pub profile: bool,
+    pub source_map_object: bool,
+    pub emit_pretty_alongside: bool,
+    pub output_comments: bool,
+    pub fail_on_warnings: bool,
+    /// Set if the code is nested too deeply for the inlining pass. See
+    /// [InlineOption::max_depth].
+    pub too_deep: Arc<AtomicBool>,
+    /// Hash of the settings which affect the output, e.g. to be used as a
+    /// part of the key of a cache along with the hash of the input file.
+    ///
+    /// The hash is stable across runs, but may change between versions of
+    /// swc. Callbacks of [Options] are not hashed, except whether they are
+    /// specified.
+    pub fingerprint: u64,
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -499,17 +1096,119 @@ pub struct JscConfig {
     #[serde(default)]
     pub external_helpers: bool,
 
+    /// Transforms reference helpers like `_classCallCheck` as globals, but
+    /// neither inline helpers nor an import of them is emitted. Bundlers
+    /// which provide helpers themselves can use this.
+    #[serde(default)]
+    pub skip_helpers_injection: bool,
+
+    /// Keeps all comments, even while minifying. A comment attached to a node
+    /// removed by a transform is moved to the nearest surviving statement
+    /// instead of being dropped.
+    #[serde(default)]
+    pub preserve_all_comments: bool,
+
+    /// Comments to keep in the output. Defaults to `license` while minifying
+    /// and `all` otherwise. `all` is used if `preserveAllComments` is enabled.
+    #[serde(default)]
+    pub comments: Option<CommentsConfig>,
+
     #[serde(default)]
     pub target: JscTarget,
 
+    /// Enables loose mode of downlevel passes, which produces smaller code
+    /// by assuming inputs follow common patterns. This affects
+    ///
+    ///  - es2015 spread, which assumes spread values are arrays
+    ///  - es2015 for-of, which assumes iterated values are arrays
+    ///  - es2015 destructuring, which assumes destructured values are arrays
+    ///
+    /// `env.loose` is used instead if `env` is specified.
     #[serde(default)]
     pub loose: bool,
+
+    /// Keeps `assert { ... }` clauses of import declarations. They are removed
+    /// unless the target is `esnext`, like other proposals.
+    #[serde(default)]
+    pub keep_import_assertions: bool,
+
+    #[serde(default)]
+    pub output: Option<OutputConfig>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CommentsConfig {
+    /// Keep all comments.
+    All,
+    /// Keep only comments starting with `!`, like `/*! license */`.
+    License,
+    /// Keep only leading comments.
+    Leading,
+    /// Drop all comments.
+    None,
+}
+
+impl CommentsConfig {
+    /// Returns true if `comment` should be kept.
+    pub(crate) fn retains(self, comment: &Comment, leading: bool) -> bool {
+        match self {
+            CommentsConfig::All => true,
+            CommentsConfig::License => comment.text.starts_with('!'),
+            CommentsConfig::Leading => leading,
+            CommentsConfig::None => false,
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct OutputConfig {
+    #[serde(default)]
+    pub quotes: Option<QuoteStyle>,
+
+    #[serde(default)]
+    pub max_line_len: Option<usize>,
+
+    #[serde(default)]
+    pub trailing_comma: Option<TrailingComma>,
+
+    /// Keep parens of the input even if they are not required. Ignored while
+    /// minifying.
+    ///
+    /// The parser keeps parens as `ParenExpr` and the code generator prints
+    /// them as-is, so this only stops the fixer pass from removing them.
+    #[serde(default)]
+    pub preserve_parens: bool,
+
+    /// Tags of tagged templates whose contents are emitted verbatim, even
+    /// while minifying. e.g. `["css", "styled"]`
+    #[serde(default)]
+    pub preserve_template_tags: Option<Vec<JsWord>>,
+
+    /// Omits the semicolon at the end of the output. Outputs are still safe to
+    /// concatenate with line breaks between them.
+    #[serde(default)]
+    pub omit_last_semi: bool,
+
+    /// `shortest` or `preserve`. Defaults to `shortest` while minifying and
+    /// decimal numbers otherwise.
+    #[serde(default)]
+    pub number_format: Option<NumberFormat>,
+
+    /// Use shorthand properties and methods. Defaults to `true` while
+    /// minifying and the form of the source otherwise.
+    #[serde(default)]
+    pub shorthand: Option<bool>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
 #[serde(tag = "type")]
 pub enum ModuleConfig {
+    /// Keeps ES modules as is. This is same as not specifying `module`.
+    #[serde(rename = "es6")]
+    Es6,
     #[serde(rename = "commonjs")]
     CommonJs(modules::common_js::Config),
     #[serde(rename = "umd")]
@@ -525,7 +1224,7 @@ impl ModuleConfig {
         config: Option<ModuleConfig>,
     ) -> Box<dyn Pass> {
         match config {
-            None => box noop(),
+            None | Some(ModuleConfig::Es6) => box noop(),
             Some(ModuleConfig::CommonJs(config)) => {
                 box modules::common_js::common_js(root_mark, config)
             }
@@ -549,6 +1248,81 @@ pub struct TransformConfig {
 
     #[serde(default)]
     pub legacy_decorator: bool,
+
+    /// Emits `Reflect.metadata` for decorated classes and members, like
+    /// `emitDecoratorMetadata` of tsc. Effective only for typescript.
+    #[serde(default)]
+    pub decorator_metadata: bool,
+
+    /// Converts `require` calls and assignments to `module.exports` into
+    /// imports and exports before the module transforms run. Modules using
+    /// other CommonJS patterns are left untouched.
+    #[serde(default)]
+    pub cjs_to_esm: bool,
+
+    /// Renames references to global variables, e.g. `Promise` to a polyfill.
+    /// Local bindings with the same name are not renamed.
+    #[serde(default)]
+    pub global_rename: HashMap<JsWord, JsWord>,
+
+    /// Like `useDefineForClassFields` of tsc. If `false`, public class fields
+    /// are initialized with assignments, which invoke setters inherited from
+    /// the super class. Fields are initialized with `Object.defineProperty`
+    /// by default.
+    #[serde(default)]
+    pub use_define_for_class_fields: Option<bool>,
+
+    /// Replaces `import.meta`, which is available only in es modules.
+    #[serde(default)]
+    pub import_meta: Option<ImportMetaOption>,
+
+    /// Merges identical temporary variables created by transforms, like
+    /// `var _this = this` declared by two passes. Variables of the input are
+    /// never touched.
+    #[serde(default)]
+    pub dedup_temp_vars: bool,
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct ImportMetaOption {
+    /// An expression replacing `import.meta.url`.
+    ///
+    /// Defaults to `require('url').pathToFileURL(__filename).toString()` if
+    /// `module.type` is `commonjs`.
+    #[serde(default)]
+    pub url: Option<String>,
+
+    /// An expression replacing other usages of `import.meta`.
+    ///
+    /// Defaults to an object with `url` if `module.type` is `commonjs`.
+    #[serde(default)]
+    pub meta: Option<String>,
+}
+
+impl ImportMetaOption {
+    fn build(
+        self,
+        cm: &SourceMap,
+        handler: &Handler,
+        module: &Option<ModuleConfig>,
+    ) -> modules::import_meta::Config {
+        let (url, meta) = match *module {
+            Some(ModuleConfig::CommonJs(..)) => {
+                let url = self.url.unwrap_or_else(|| {
+                    "require('url').pathToFileURL(__filename).toString()".into()
+                });
+                let meta = self.meta.unwrap_or_else(|| format!("({{ url: {} }})", url));
+                (Some(url), Some(meta))
+            }
+            _ => (self.url, self.meta),
+        };
+
+        modules::import_meta::Config {
+            url: url.map(|src| box parse_option_expr(cm, handler, "import.meta.url", src)),
+            meta: meta.map(|src| box parse_option_expr(cm, handler, "import.meta", src)),
+        }
+    }
 }
 
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
@@ -566,6 +1340,90 @@ pub struct OptimizerConfig {
 
     #[serde(default)]
     pub jsonify: Option<JsonifyOption>,
+
+    /// Removes `debugger` statements.
+    #[serde(default)]
+    pub drop_debugger: bool,
+
+    #[serde(default)]
+    pub drop_console: Option<DropConsoleOption>,
+
+    #[serde(default)]
+    pub inline: Option<InlineOption>,
+
+    #[serde(default)]
+    pub compress: Option<CompressOption>,
+}
+
+/// Passes of the optimizer to run, named after the compress options of
+/// terser. All passes are enabled by default, so one can be disabled to work
+/// around a miscompilation.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct CompressOption {
+    /// Folds constant expressions.
+    #[serde(default = "default_compress_pass")]
+    pub evaluate: bool,
+
+    /// Inlines variables.
+    #[serde(default = "default_compress_pass")]
+    pub inline: bool,
+
+    /// Removes unreachable branches.
+    #[serde(default = "default_compress_pass")]
+    pub dead_code: bool,
+
+    /// Removes unused declarations.
+    #[serde(default = "default_compress_pass")]
+    pub unused: bool,
+}
+
+impl Default for CompressOption {
+    fn default() -> Self {
+        CompressOption {
+            evaluate: true,
+            inline: true,
+            dead_code: true,
+            unused: true,
+        }
+    }
+}
+
+const fn default_compress_pass() -> bool {
+    true
+}
+
+/// Options for inlining variables, like names of bindings to treat specially.
+/// This is useful to debug the optimizer.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields, rename_all = "camelCase")]
+pub struct InlineOption {
+    /// Bindings which are never inlined.
+    #[serde(default)]
+    pub deny: Vec<String>,
+
+    /// Bindings which are inlined as an alias even if there's a call between
+    /// the alias and the aliased variable, as long as the aliased variable is
+    /// initialized with a literal and never reassigned.
+    #[serde(default)]
+    pub allow: Vec<String>,
+
+    /// Maximum nesting depth of expressions and scopes for the inlining pass.
+    /// Deeper code fails to compile instead of overflowing the stack while
+    /// inlining. Other passes are not guarded.
+    #[serde(default)]
+    pub max_depth: Option<usize>,
+}
+
+/// Calls to methods of `console` to remove.
+///
+/// `true` removes calls to all methods, and a list removes calls to the
+/// listed methods.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DropConsoleOption {
+    All(bool),
+    Methods(Vec<JsWord>),
 }
 
 #[derive(Debug, Default, Clone, Copy, Serialize, Deserialize)]
@@ -582,10 +1440,20 @@ fn default_jsonify_min_cost() -> usize {
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 #[serde(deny_unknown_fields, rename_all = "camelCase")]
 pub struct GlobalPassOption {
+    /// Expressions to replace global variables or dotted paths of properties
+    /// like `process.env.NODE_ENV` with. Values are parsed as expressions.
     #[serde(default)]
     pub vars: HashMap<String, String>,
     #[serde(default = "default_envs")]
     pub envs: HashSet<String>,
+
+    /// Results of `typeof` for global variables. For example,
+    /// `{ "process": "undefined" }` removes node-only branches guarded by
+    /// `typeof process`.
+    ///
+    /// If `env` targets only browsers, `process` defaults to `undefined`.
+    #[serde(default)]
+    pub typeofs: HashMap<String, String>,
 }
 
 fn default_envs() -> HashSet<String> {
@@ -595,6 +1463,33 @@ fn default_envs() -> HashSet<String> {
     v
 }
 
+/// Parses an expression given as an option, e.g. a value of a global
+/// variable. `name` is used as the file name of the expression.
+fn parse_option_expr(cm: &SourceMap, handler: &Handler, name: &str, src: String) -> Expr {
+    let src_str = src.clone();
+    let fm = cm.new_source_file(FileName::Custom(name.into()), src);
+    let session = ParseSess { handler };
+    let lexer = Lexer::new(
+        session,
+        Syntax::Es(Default::default()),
+        Default::default(),
+        SourceFileInput::from(&*fm),
+        None,
+    );
+
+    let mut module = Parser::new_from(session, lexer)
+        .parse_module()
+        .map_err(|mut e| {
+            e.emit();
+        })
+        .unwrap_or_else(|()| panic!("failed to parse {}=`{}` as module", name, src_str));
+
+    match module.body.pop() {
+        Some(ModuleItem::Stmt(Stmt::Expr(ExprStmt { box expr, .. }))) => expr,
+        _ => panic!("{} is not a valid expression", src_str),
+    }
+}
+
 impl GlobalPassOption {
     pub fn build(self, cm: &SourceMap, handler: &Handler) -> InlineGlobals {
         fn mk_map(
@@ -611,33 +1506,7 @@ impl GlobalPassOption {
                 } else {
                     (*v).into()
                 };
-                let v_str = v.clone();
-                let fm = cm.new_source_file(FileName::Custom(format!("GLOBAL.{}", k)), v);
-                let session = ParseSess { handler };
-                let lexer = Lexer::new(
-                    session,
-                    Syntax::Es(Default::default()),
-                    Default::default(),
-                    SourceFileInput::from(&*fm),
-                    None,
-                );
-
-                let mut module = Parser::new_from(session, lexer)
-                    .parse_module()
-                    .map_err(|mut e| {
-                        e.emit();
-                    })
-                    .unwrap_or_else(|()| {
-                        panic!(
-                            "failed to parse global variable {}=`{}` as module",
-                            k, v_str
-                        )
-                    });
-
-                let expr = match module.body.pop() {
-                    Some(ModuleItem::Stmt(Stmt::Expr(ExprStmt { box expr, .. }))) => expr,
-                    _ => panic!("{} is not a valid expression", v_str),
-                };
+                let expr = parse_option_expr(cm, handler, &format!("GLOBAL.{}", k), v);
 
                 m.insert((*k).into(), expr);
             }
@@ -659,6 +1528,12 @@ impl GlobalPassOption {
                     true,
                 )
             },
+
+            typeofs: self
+                .typeofs
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
         }
     }
 }
@@ -717,6 +1592,13 @@ impl Merge for JscConfig {
         self.transform.merge(&from.transform);
         self.target.merge(&from.target);
         self.external_helpers.merge(&from.external_helpers);
+        self.skip_helpers_injection.merge(&from.skip_helpers_injection);
+        self.preserve_all_comments.merge(&from.preserve_all_comments);
+        self.comments.merge(&from.comments);
+        self.loose.merge(&from.loose);
+        self.keep_import_assertions
+            .merge(&from.keep_import_assertions);
+        self.output.merge(&from.output);
     }
 }
 
@@ -743,6 +1625,12 @@ impl Merge for bool {
     }
 }
 
+impl Merge for usize {
+    fn merge(&mut self, from: &Self) {
+        *self = *from
+    }
+}
+
 impl Merge for Syntax {
     fn merge(&mut self, from: &Self) {
         *self = *from;
@@ -754,12 +1642,104 @@ impl Merge for TransformConfig {
         self.optimizer.merge(&from.optimizer);
         self.const_modules.merge(&from.const_modules);
         self.react.merge(&from.react);
+        self.legacy_decorator.merge(&from.legacy_decorator);
+        self.decorator_metadata.merge(&from.decorator_metadata);
+        self.cjs_to_esm.merge(&from.cjs_to_esm);
+        self.global_rename.merge(&from.global_rename);
+        if from.use_define_for_class_fields.is_some() {
+            self.use_define_for_class_fields = from.use_define_for_class_fields;
+        }
+        self.import_meta.merge(&from.import_meta);
+        self.dedup_temp_vars.merge(&from.dedup_temp_vars);
+    }
+}
+
+impl Merge for ImportMetaOption {
+    fn merge(&mut self, from: &Self) {
+        if from.url.is_some() {
+            self.url = from.url.clone();
+        }
+        if from.meta.is_some() {
+            self.meta = from.meta.clone();
+        }
+    }
+}
+
+impl Merge for OutputConfig {
+    fn merge(&mut self, from: &Self) {
+        self.quotes.merge(&from.quotes);
+        self.max_line_len.merge(&from.max_line_len);
+        self.trailing_comma.merge(&from.trailing_comma);
+        self.preserve_parens.merge(&from.preserve_parens);
+        self.preserve_template_tags
+            .merge(&from.preserve_template_tags);
+        self.omit_last_semi.merge(&from.omit_last_semi);
+        self.number_format.merge(&from.number_format);
+        self.shorthand.merge(&from.shorthand);
+    }
+}
+
+impl Merge for Vec<JsWord> {
+    fn merge(&mut self, from: &Self) {
+        *self = from.clone();
+    }
+}
+
+impl Merge for HashMap<JsWord, JsWord> {
+    fn merge(&mut self, from: &Self) {
+        self.extend(from.iter().map(|(k, v)| (k.clone(), v.clone())));
+    }
+}
+
+impl Merge for CommentsConfig {
+    fn merge(&mut self, from: &Self) {
+        *self = *from;
+    }
+}
+
+impl Merge for NumberFormat {
+    fn merge(&mut self, from: &Self) {
+        *self = *from;
+    }
+}
+
+impl Merge for QuoteStyle {
+    fn merge(&mut self, from: &Self) {
+        *self = *from;
+    }
+}
+
+impl Merge for TrailingComma {
+    fn merge(&mut self, from: &Self) {
+        *self = *from;
     }
 }
 
 impl Merge for OptimizerConfig {
     fn merge(&mut self, from: &Self) {
-        self.globals.merge(&from.globals)
+        self.globals.merge(&from.globals);
+        self.drop_debugger.merge(&from.drop_debugger);
+        self.drop_console.merge(&from.drop_console);
+        self.inline.merge(&from.inline);
+        self.compress.merge(&from.compress);
+    }
+}
+
+impl Merge for CompressOption {
+    fn merge(&mut self, from: &Self) {
+        *self = *from;
+    }
+}
+
+impl Merge for InlineOption {
+    fn merge(&mut self, from: &Self) {
+        *self = from.clone();
+    }
+}
+
+impl Merge for DropConsoleOption {
+    fn merge(&mut self, from: &Self) {
+        *self = from.clone();
     }
 }
 
@@ -780,3 +1760,140 @@ impl Merge for ConstModulesConfig {
         *self = from.clone()
     }
 }
+
+/// Applies an override on top of a resolved config.
+///
+/// Unlike [Merge], which never turns a `bool` off, values of `from` always
+/// win. Only [None] leaves the value of `self` untouched, so fields which are
+/// not optional, like `jsc.externalHelpers`, are always taken from `from`.
+pub(crate) trait Override {
+    fn override_with(&mut self, from: &Self);
+}
+
+impl<T: Clone> Override for Option<T>
+where
+    T: Override,
+{
+    fn override_with(&mut self, from: &Option<T>) {
+        if let Some(ref from) = *from {
+            match *self {
+                Some(ref mut v) => v.override_with(from),
+                None => *self = Some(from.clone()),
+            }
+        }
+    }
+}
+
+macro_rules! override_by_clone {
+    ($($T:ty),*) => {
+        $(
+            impl Override for $T {
+                fn override_with(&mut self, from: &Self) {
+                    *self = from.clone();
+                }
+            }
+        )*
+    };
+}
+
+override_by_clone!(
+    bool,
+    usize,
+    String,
+    JscTarget,
+    Syntax,
+    ModuleConfig,
+    FileMatcher,
+    preset_env::Config,
+    CommentsConfig,
+    QuoteStyle,
+    TrailingComma,
+    NumberFormat,
+    Vec<JsWord>,
+    HashMap<JsWord, JsWord>,
+    react::Options,
+    ConstModulesConfig,
+    GlobalPassOption,
+    JsonifyOption,
+    DropConsoleOption,
+    InlineOption,
+    CompressOption
+);
+
+impl Override for Config {
+    fn override_with(&mut self, from: &Self) {
+        self.env.override_with(&from.env);
+        self.test.override_with(&from.test);
+        self.exclude.override_with(&from.exclude);
+        self.jsc.override_with(&from.jsc);
+        self.module.override_with(&from.module);
+        self.minify.override_with(&from.minify);
+    }
+}
+
+impl Override for JscConfig {
+    fn override_with(&mut self, from: &Self) {
+        self.syntax.override_with(&from.syntax);
+        self.transform.override_with(&from.transform);
+        self.external_helpers.override_with(&from.external_helpers);
+        self.skip_helpers_injection
+            .override_with(&from.skip_helpers_injection);
+        self.preserve_all_comments
+            .override_with(&from.preserve_all_comments);
+        self.comments.override_with(&from.comments);
+        self.target.override_with(&from.target);
+        self.loose.override_with(&from.loose);
+        self.keep_import_assertions
+            .override_with(&from.keep_import_assertions);
+        self.output.override_with(&from.output);
+    }
+}
+
+impl Override for TransformConfig {
+    fn override_with(&mut self, from: &Self) {
+        self.react.override_with(&from.react);
+        self.const_modules.override_with(&from.const_modules);
+        self.optimizer.override_with(&from.optimizer);
+        self.legacy_decorator.override_with(&from.legacy_decorator);
+        self.decorator_metadata
+            .override_with(&from.decorator_metadata);
+        self.cjs_to_esm.override_with(&from.cjs_to_esm);
+        self.global_rename.override_with(&from.global_rename);
+        self.use_define_for_class_fields
+            .override_with(&from.use_define_for_class_fields);
+        self.import_meta.override_with(&from.import_meta);
+        self.dedup_temp_vars.override_with(&from.dedup_temp_vars);
+    }
+}
+
+impl Override for ImportMetaOption {
+    fn override_with(&mut self, from: &Self) {
+        self.url.override_with(&from.url);
+        self.meta.override_with(&from.meta);
+    }
+}
+
+impl Override for OutputConfig {
+    fn override_with(&mut self, from: &Self) {
+        self.quotes.override_with(&from.quotes);
+        self.max_line_len.override_with(&from.max_line_len);
+        self.trailing_comma.override_with(&from.trailing_comma);
+        self.preserve_parens.override_with(&from.preserve_parens);
+        self.preserve_template_tags
+            .override_with(&from.preserve_template_tags);
+        self.omit_last_semi.override_with(&from.omit_last_semi);
+        self.number_format.override_with(&from.number_format);
+        self.shorthand.override_with(&from.shorthand);
+    }
+}
+
+impl Override for OptimizerConfig {
+    fn override_with(&mut self, from: &Self) {
+        self.globals.override_with(&from.globals);
+        self.jsonify.override_with(&from.jsonify);
+        self.drop_debugger.override_with(&from.drop_debugger);
+        self.drop_console.override_with(&from.drop_console);
+        self.inline.override_with(&from.inline);
+        self.compress.override_with(&from.compress);
+    }
+}
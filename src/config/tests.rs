@@ -1,5 +1,6 @@
-use super::Rc;
+use super::{source_map_url, Config, Options, Rc, SwcrcError};
 use serde_json;
+use std::{io, path::Path};
 
 #[test]
 fn object() {
@@ -10,3 +11,107 @@ fn object() {
 fn array() {
     let _: Rc = serde_json::from_str(include_str!("array.json")).expect("failed to parse");
 }
+
+#[test]
+fn swcrc_error_from_io() {
+    let path = Path::new(".swcrc");
+    let err = |kind| SwcrcError::from_io(path, io::Error::from(kind));
+
+    match err(io::ErrorKind::NotFound) {
+        SwcrcError::NotFound { .. } => {}
+        e => panic!("expected NotFound, got {:?}", e),
+    }
+    match err(io::ErrorKind::PermissionDenied) {
+        SwcrcError::PermissionDenied { .. } => {}
+        e => panic!("expected PermissionDenied, got {:?}", e),
+    }
+    match err(io::ErrorKind::InvalidData) {
+        SwcrcError::Read { .. } => {}
+        e => panic!("expected Read, got {:?}", e),
+    }
+}
+
+#[test]
+fn source_map_url_same_dir() {
+    let url = |output: &str, map: &str| source_map_url(Path::new(output), Path::new(map));
+
+    assert_eq!(url("dist/bundle.js", "dist/bundle.js.map"), "bundle.js.map");
+    assert_eq!(
+        url("./dist/bundle.js", "dist/bundle.js.map"),
+        "bundle.js.map"
+    );
+    assert_eq!(url("bundle.js", "bundle.js.map"), "bundle.js.map");
+    assert_eq!(
+        url("/out/bundle.js", "/out/maps/bundle.js.map"),
+        "maps/bundle.js.map"
+    );
+}
+
+#[test]
+fn source_map_url_cross_dir() {
+    let url = |output: &str, map: &str| source_map_url(Path::new(output), Path::new(map));
+
+    assert_eq!(
+        url("dist/js/bundle.js", "dist/maps/bundle.js.map"),
+        "../maps/bundle.js.map"
+    );
+    assert_eq!(
+        url("/out/a/b/bundle.js", "/maps/bundle.js.map"),
+        "../../../maps/bundle.js.map"
+    );
+    assert_eq!(url("dist/bundle.js", "bundle.js.map"), "../bundle.js.map");
+}
+
+#[test]
+fn fingerprint() {
+    let fingerprint = |target: &str| {
+        let config: Config = serde_json::from_str(&format!(
+            r#"{{
+                "jsc": {{
+                    "target": "{}",
+                    "transform": {{
+                        "optimizer": {{
+                            "globals": {{
+                                "vars": {{ "a": "1", "b": "2", "c": "3", "d": "4" }},
+                                "envs": ["A", "B", "C", "D"]
+                            }}
+                        }}
+                    }}
+                }}
+            }}"#,
+            target
+        ))
+        .expect("failed to parse");
+
+        Options::default().fingerprint(&config, true)
+    };
+
+    assert_eq!(fingerprint("es2015"), fingerprint("es2015"));
+    assert_ne!(fingerprint("es2015"), fingerprint("es2017"));
+}
+
+#[test]
+fn fingerprint_env_targets() {
+    let fingerprint = |targets: &str| {
+        let config: Config = serde_json::from_str(&format!(
+            r#"{{
+                "env": {{
+                    "targets": {}
+                }}
+            }}"#,
+            targets
+        ))
+        .expect("failed to parse");
+
+        Options::default().fingerprint(&config, true)
+    };
+
+    assert_eq!(
+        fingerprint(r#"{ "chrome": "79", "firefox": "72" }"#),
+        fingerprint(r#"{ "firefox": "72", "chrome": "79" }"#)
+    );
+    assert_ne!(
+        fingerprint(r#"{ "chrome": "79", "firefox": "72" }"#),
+        fingerprint(r#"{ "chrome": "80", "firefox": "72" }"#)
+    );
+}
@@ -1,4 +1,4 @@
-#![feature(box_syntax, box_patterns)]
+#![feature(box_syntax, box_patterns, specialization)]
 
 pub use sourcemap;
 pub use swc_atoms as atoms;
@@ -7,22 +7,36 @@ pub use swc_ecmascript as ecmascript;
 
 mod builder;
 pub mod config;
+mod orphan_comments;
+mod reparse;
 
-pub use crate::builder::PassBuilder;
-use crate::config::{
-    BuiltConfig, Config, ConfigFile, InputSourceMap, JscTarget, Merge, Options, Rc, RootMode,
-    SourceMapsConfig,
+pub use crate::{
+    builder::{CustomPass, PassBuilder, PHASES},
+    reparse::TextEdit,
+};
+use crate::{
+    config::{
+        BuiltConfig, CommentsConfig, Config, ConfigFile, FsSwcrcResolver, InputSourceMap,
+        JscTarget, Merge, Options, Rc, RootMode, SourceMapsConfig, SwcrcError, SwcrcResolver,
+    },
+    orphan_comments::relocate_orphan_comments,
 };
 use anyhow::{Context, Error};
 use common::{
-    comments::{Comment, Comments},
-    errors::Handler,
-    BytePos, FileName, FoldWith, Globals, SourceFile, SourceMap, Spanned, GLOBALS,
+    comments::{Comment, CommentKind, Comments},
+    errors::{Diagnostic, DiagnosticBuilder, Emitter as DiagEmitter, Handler},
+    pass::{Timings, TIMINGS},
+    BytePos, FileName, FoldWith, Globals, LineCol, SourceFile, SourceMap, Span, Spanned, Visit,
+    VisitWith, DUMMY_SP, GLOBALS,
 };
 use ecmascript::{
-    ast::Program,
+    ast::{Expr, ExprStmt, Ident, Module, ModuleItem, ParenExpr, Program, Stmt},
     codegen::{self, Emitter},
-    parser::{lexer::Lexer, Parser, Session as ParseSess, Syntax},
+    parser::{
+        lexer::{CommentCollection, Lexer},
+        token::{Keyword, Token, TokenAndSpan, Word},
+        Parser, Session as ParseSess, Syntax,
+    },
     transforms::{
         helpers::{self, Helpers},
         util,
@@ -35,10 +49,18 @@ pub use ecmascript::{
 };
 use serde::Serialize;
 use serde_json::error::Category;
+use sourcemap::SourceMapBuilder;
 use std::{
+    cell::RefCell,
+    collections::{hash_map::DefaultHasher, HashMap, HashSet},
     fs::{read_to_string, File},
+    hash::Hasher,
+    io::{self, Write},
+    mem,
+    ops::Range,
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{atomic::Ordering, Arc, RwLock},
+    time::Duration,
 };
 
 pub struct Compiler {
@@ -50,13 +72,92 @@ pub struct Compiler {
     pub cm: Arc<SourceMap>,
     pub handler: Handler,
     comments: Comments,
+    /// Emitter of `handler`, if the compiler is created by
+    /// [Compiler::with_diagnostics].
+    diagnostics: Option<CollectedDiagnostics>,
 }
 
 #[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
 pub struct TransformOutput {
     pub code: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub map: Option<String>,
+    /// Source map as a json value, if [Options::source_map_object] is
+    /// enabled. `map` is `None` in that case.
+    #[serde(rename = "map", skip_serializing_if = "Option::is_none")]
+    pub map_object: Option<serde_json::Value>,
+    /// Names of helpers used by the transformed code, including ones
+    /// required by other helpers.
+    ///
+    /// See [Helpers::used] for the format of names.
+    ///
+    /// [Helpers::used]: crate::ecmascript::transforms::helpers::Helpers::used
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub used_helpers: Vec<&'static str>,
+    /// Time spent by each pass, if [Options::profile] is enabled.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub pass_timings: Vec<PassTiming>,
+    /// Non-minified code, if [Options::emit_pretty_alongside] is enabled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pretty: Option<String>,
+    /// Comments in the code, sorted by position, if
+    /// [Options::output_comments] is enabled.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub comments: Vec<OutputComment>,
+    /// Warnings reported while processing the file, if the compiler is
+    /// created by [Compiler::with_diagnostics].
+    #[serde(skip)]
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+/// A comment emitted in [TransformOutput::code].
+#[derive(Debug, Clone, Serialize)]
+pub struct OutputComment {
+    /// `"line"` or `"block"`.
+    pub kind: &'static str,
+    /// Text of the comment, without `//` or `/*` and `*/`.
+    pub text: String,
+    /// Zero-based line of the comment in the output.
+    pub line: u32,
+    /// Zero-based column of the comment in the output.
+    pub col: u32,
+}
+
+/// Result of [Compiler::process_expr] and [Compiler::process_stmt].
+#[derive(Debug)]
+pub struct FragmentOutput<T> {
+    /// The transformed fragment.
+    pub node: T,
+    /// Generated code of the fragment.
+    pub code: String,
+}
+
+/// Result of [Compiler::analyze_js_file].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AnalyzeResult {
+    /// True if the output code differs from the source file.
+    pub changed: bool,
+    /// Hash of the output code.
+    ///
+    /// The hash is stable across runs, but may change between versions of
+    /// swc.
+    pub hash: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PassTiming {
+    pub name: String,
+    pub duration: Duration,
+}
+
+/// Result of [Compiler::detect_module_kind].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleKind {
+    Module,
+    CommonJs,
+    /// There's no import, export, `require` or `module.exports`.
+    Unknown,
 }
 
 /// These are **low-level** apis.
@@ -82,15 +183,21 @@ impl Compiler {
     }
 
     /// This method parses a javascript / typescript file
+    ///
+    /// Comments selected by `parse_comments` are stored to
+    /// [Compiler::comments]. `true` and `false` can be used to collect all
+    /// comments and no comment.
     pub fn parse_js(
         &self,
         fm: Arc<SourceFile>,
         target: JscTarget,
         syntax: Syntax,
         is_module: bool,
-        parse_comments: bool,
+        parse_comments: impl Into<CommentCollection>,
         input_source_map: &InputSourceMap,
     ) -> Result<(Program, Option<sourcemap::SourceMap>), Error> {
+        let parse_comments = parse_comments.into();
+
         self.run(|| {
             let orig = (|| {
                 // Load original source map
@@ -154,12 +261,13 @@ impl Compiler {
                 syntax,
                 target,
                 SourceFileInput::from(&*fm),
-                if parse_comments {
-                    Some(&self.comments)
-                } else {
+                if parse_comments == CommentCollection::None {
                     None
+                } else {
+                    Some(&self.comments)
                 },
-            );
+            )
+            .comment_collection(parse_comments);
             let mut parser = Parser::new_from(session, lexer);
             let program = if is_module {
                 parser
@@ -189,49 +297,249 @@ impl Compiler {
         comments: &Comments,
         source_map: SourceMapsConfig,
         orig: Option<&sourcemap::SourceMap>,
-        minify: bool,
+        cfg: codegen::Config,
+    ) -> Result<TransformOutput, Error> {
+        self.print_with_handlers(program, comments, source_map, orig, cfg, box MyHandlers)
+    }
+
+    /// Emits `program` only to build its source map, for code which was
+    /// already emitted but whose source map is lost.
+    ///
+    /// `cfg` should be the config used to emit the code, as the mappings
+    /// depend on the layout of the code.
+    pub fn source_map_only(
+        &self,
+        program: &Program,
+        comments: &Comments,
+        orig: Option<&sourcemap::SourceMap>,
+        cfg: codegen::Config,
+    ) -> Result<String, Error> {
+        let output = self.print(program, comments, SourceMapsConfig::Bool(true), orig, cfg)?;
+
+        output
+            .map
+            .ok_or_else(|| Error::msg("source map was not generated"))
+    }
+
+    /// Composes two source maps, for code processed by another tool before
+    /// swc.
+    ///
+    /// `outer` maps the output to the intermediate code and `inner` maps the
+    /// intermediate code to the original sources. The returned map points
+    /// directly at the original sources. Mappings of `outer` with no
+    /// counterpart in `inner` are dropped.
+    pub fn remap(
+        &self,
+        outer: &sourcemap::SourceMap,
+        inner: &sourcemap::SourceMap,
+    ) -> sourcemap::SourceMap {
+        let mut builder = sourcemap::SourceMapBuilder::new(outer.get_file());
+
+        for token in outer.tokens() {
+            let orig = match inner.lookup_token(token.get_src_line(), token.get_src_col()) {
+                Some(orig) => orig,
+                None => continue,
+            };
+
+            let raw = builder.add(
+                token.get_dst_line(),
+                token.get_dst_col(),
+                orig.get_src_line(),
+                orig.get_src_col(),
+                orig.get_source(),
+                orig.get_name().or_else(|| token.get_name()),
+            );
+            if orig.get_source().is_some() && !builder.has_source_contents(raw.src_id) {
+                builder.set_source_contents(
+                    raw.src_id,
+                    inner.get_source_contents(orig.get_src_id()),
+                );
+            }
+        }
+
+        builder.into_sourcemap()
+    }
+
+    /// Same as `print`, but `handlers` is notified while generating code.
+    pub fn print_with_handlers(
+        &self,
+        program: &Program,
+        comments: &Comments,
+        source_map: SourceMapsConfig,
+        orig: Option<&sourcemap::SourceMap>,
+        cfg: codegen::Config,
+        handlers: Box<dyn codegen::Handlers>,
+    ) -> Result<TransformOutput, Error> {
+        self.print_inner(
+            program,
+            comments,
+            source_map,
+            orig,
+            cfg,
+            handlers,
+            Default::default(),
+        )
+    }
+
+    /// See [EmitOptions] for how the source map and comments of the output
+    /// are built.
+    fn print_inner(
+        &self,
+        program: &Program,
+        comments: &Comments,
+        source_map: SourceMapsConfig,
+        orig: Option<&sourcemap::SourceMap>,
+        cfg: codegen::Config,
+        handlers: Box<dyn codegen::Handlers>,
+        opts: EmitOptions,
     ) -> Result<TransformOutput, Error> {
+        let EmitOptions {
+            source_file_name,
+            filename_map,
+            ignore_source,
+            sources_content,
+            source_map_url,
+            output_comments,
+        } = opts;
+
         self.run(|| {
-            let mut src_map_buf = vec![];
+            // Original names of renamed identifiers are stored to `names` of the
+            // source map.
+            let renamed = if source_map.enabled() {
+                renamed_idents(&self.cm, program)
+            } else {
+                Default::default()
+            };
+            let build_source_map = |buf: &mut Vec<_>| -> Result<String, Error> {
+                let ignored = RefCell::new(HashSet::new());
+                let source_name = |name: &FileName| {
+                    let s = match (source_file_name, filename_map) {
+                        (Some((file, s)), _) if file == name => s.to_string(),
+                        (_, Some(filename_map)) => filename_map(name),
+                        (_, None) => name.to_string(),
+                    };
+                    if ignore_source.map_or(false, |ignore| ignore(name)) {
+                        ignored.borrow_mut().insert(s.clone());
+                    }
+                    s
+                };
+                let mut map = self.cm.build_source_map_with_idents(
+                    buf,
+                    orig,
+                    &source_name,
+                    &|pos| renamed.get(&pos).cloned(),
+                );
+                let ignore_list = {
+                    let ignored = ignored.borrow();
+                    map.sources()
+                        .enumerate()
+                        .filter(|(_, s)| ignored.contains(*s))
+                        .map(|(idx, _)| idx)
+                        .collect::<Vec<_>>()
+                };
+                if !sources_content {
+                    for idx in 0..map.get_source_count() {
+                        map.set_source_contents(idx, None);
+                    }
+                }
+
+                let mut buf = vec![];
+                map.to_writer(&mut buf).context("failed to write source map")?;
+                if ignore_list.is_empty() {
+                    return String::from_utf8(buf).context("source map is not utf-8");
+                }
+
+                // `sourcemap` doesn't support `x_google_ignoreList`, so it's
+                // added to the serialized map.
+                let mut map: serde_json::Value =
+                    serde_json::from_slice(&buf).context("failed to parse source map")?;
+                if let serde_json::Value::Object(ref mut map) = map {
+                    map.insert("x_google_ignoreList".into(), ignore_list.into());
+                }
+                serde_json::to_string(&map).context("failed to write source map")
+            };
 
+            let mut src_map_buf = mappings_buf(&source_map, output_comments);
+            // The emitter takes comments out of `comments`.
+            let all_comments = if output_comments {
+                comments.to_vec()
+            } else {
+                vec![]
+            };
+
+            let err_count = self.handler.err_count();
             let src = {
                 let mut buf = vec![];
                 {
-                    let handlers = box MyHandlers;
+                    let wr = codegen::text_writer::JsWriter::new(
+                        self.cm.clone(),
+                        "\n",
+                        &mut buf,
+                        src_map_buf.as_mut(),
+                    );
+                    let wr: Box<dyn codegen::text_writer::WriteJs + '_> = if cfg.omit_last_semi {
+                        box codegen::text_writer::omit_trailing_semi(wr)
+                    } else {
+                        box wr
+                    };
                     let mut emitter = Emitter {
-                        cfg: codegen::Config { minify },
+                        cfg,
                         comments: Some(&comments),
                         cm: self.cm.clone(),
-                        wr: box codegen::text_writer::JsWriter::new(
-                            self.cm.clone(),
-                            "\n",
-                            &mut buf,
-                            if source_map.enabled() {
-                                Some(&mut src_map_buf)
-                            } else {
-                                None
-                            },
-                        ),
-                        handlers,
+                        wr,
+                        handlers: box ReportUnsupported {
+                            handler: &self.handler,
+                            inner: handlers,
+                        },
                     };
 
                     emitter
                         .emit_program(&program)
                         .context("failed to emit module")?;
                 }
+                if self.handler.err_count() > err_count {
+                    return Err(Error::msg(
+                        "failed to emit module: the target doesn't support some syntax",
+                    ));
+                }
                 // Invalid utf8 is valid in javascript world.
                 unsafe { String::from_utf8_unchecked(buf) }
             };
+
+            let emitted_comments = {
+                let mut positions = HashMap::new();
+                for (pos, lc) in src_map_buf.iter().flatten() {
+                    positions.entry(*pos).or_insert(*lc);
+                }
+
+                let mut comments = all_comments
+                    .into_iter()
+                    .filter_map(|c| {
+                        let lc = positions.get(&c.span.lo())?;
+                        Some(OutputComment {
+                            kind: match c.kind {
+                                CommentKind::Line => "line",
+                                CommentKind::Block => "block",
+                            },
+                            text: c.text,
+                            line: lc.line,
+                            col: lc.col,
+                        })
+                    })
+                    .collect::<Vec<_>>();
+                comments.sort_by_key(|c| (c.line, c.col));
+                comments
+            };
             let (code, map) = match source_map {
                 SourceMapsConfig::Bool(v) => {
                     if v {
-                        let mut buf = vec![];
+                        let map = build_source_map(src_map_buf.get_or_insert_with(Vec::new))?;
 
-                        self.cm
-                            .build_source_map_from(&mut src_map_buf, orig)
-                            .to_writer(&mut buf)
-                            .context("failed to write source map")?;
-                        let map = String::from_utf8(buf).context("source map is not utf-8")?;
+                        let mut src = src;
+                        if let Some(url) = source_map_url {
+                            src.push_str("\n//# sourceMappingURL=");
+                            src.push_str(url);
+                        }
                         (src, Some(map))
                     } else {
                         (src, None)
@@ -240,13 +548,7 @@ impl Compiler {
                 SourceMapsConfig::Str(_) => {
                     let mut src = src;
 
-                    let mut buf = vec![];
-
-                    self.cm
-                        .build_source_map(&mut src_map_buf)
-                        .to_writer(&mut buf)
-                        .context("failed to write source map file")?;
-                    let map = String::from_utf8(buf).context("source map is not utf-8")?;
+                    let map = build_source_map(src_map_buf.get_or_insert_with(Vec::new))?;
 
                     src.push_str("\n//# sourceMappingURL=data:application/json;base64,");
                     base64::encode_config_buf(
@@ -258,11 +560,113 @@ impl Compiler {
                 }
             };
 
-            Ok(TransformOutput { code, map })
+            Ok(TransformOutput {
+                code,
+                map,
+                map_object: None,
+                used_helpers: vec![],
+                pass_timings: vec![],
+                pretty: None,
+                comments: emitted_comments,
+                diagnostics: vec![],
+            })
+        })
+    }
+
+    /// Concatenates emitted files into one file.
+    ///
+    /// Source maps of `outputs` are merged into a source map of the
+    /// concatenated file, and the merged map is `None` if no output has a
+    /// source map. Inline source maps are not merged.
+    pub fn concat(
+        &self,
+        outputs: &[(FileName, TransformOutput)],
+    ) -> Result<TransformOutput, Error> {
+        let mut code = String::new();
+        let mut builder = None;
+        let mut line_offset = 0;
+
+        for (name, output) in outputs {
+            if let Some(ref map) = output.map {
+                let map = sourcemap::SourceMap::from_slice(map.as_bytes())
+                    .with_context(|| format!("failed to parse source map of {}", name))?;
+                let builder = builder.get_or_insert_with(|| SourceMapBuilder::new(None));
+
+                for token in map.tokens() {
+                    builder.add(
+                        token.get_dst_line() + line_offset,
+                        token.get_dst_col(),
+                        token.get_src_line(),
+                        token.get_src_col(),
+                        token.get_source(),
+                        token.get_name(),
+                    );
+                }
+
+                for (idx, source) in map.sources().enumerate() {
+                    if let Some(contents) = map.get_source_contents(idx as u32) {
+                        let src_id = builder.add_source(source);
+                        builder.set_source_contents(src_id, Some(contents));
+                    }
+                }
+            }
+
+            code.push_str(&output.code);
+            if !output.code.ends_with('\n') {
+                code.push('\n');
+                line_offset += 1;
+            }
+            line_offset += output.code.matches('\n').count() as u32;
+        }
+
+        let map = match builder {
+            Some(builder) => {
+                let mut buf = vec![];
+                builder
+                    .into_sourcemap()
+                    .to_writer(&mut buf)
+                    .context("failed to write source map")?;
+                Some(String::from_utf8(buf).context("source map is not utf-8")?)
+            }
+            None => None,
+        };
+
+        let mut used_helpers: Vec<_> = outputs
+            .iter()
+            .flat_map(|(_, output)| output.used_helpers.iter().copied())
+            .collect();
+        used_helpers.sort();
+        used_helpers.dedup();
+
+        Ok(TransformOutput {
+            code,
+            map,
+            map_object: None,
+            used_helpers,
+            pass_timings: vec![],
+            pretty: None,
+            comments: vec![],
+            diagnostics: vec![],
         })
     }
 }
 
+/// Returns the buffer for mappings of the emitted code, which are required to
+/// build a source map and to locate emitted comments.
+///
+/// Nothing is allocated, and the emitter doesn't record mappings at all, if
+/// neither is required.
+fn mappings_buf(
+    source_map: &SourceMapsConfig,
+    output_comments: bool,
+) -> Option<Vec<(BytePos, LineCol)>> {
+    if source_map.enabled() || output_comments {
+        Some(vec![])
+    } else {
+        None
+    }
+}
+
 /// High-level apis.
 impl Compiler {
     pub fn new(cm: Arc<SourceMap>, handler: Handler) -> Self {
@@ -271,6 +675,33 @@ impl Compiler {
             handler,
             globals: Globals::new(),
             comments: Default::default(),
+            diagnostics: None,
+        }
+    }
+
+    /// Creates a compiler which stores diagnostics instead of printing them.
+    ///
+    /// Diagnostics reported while processing a file are returned in
+    /// [TransformOutput::diagnostics]. Errors still make processing fail as
+    /// they do with [Compiler::new]. Diagnostics of a failed call are kept
+    /// until they are taken with [Compiler::take_diagnostics] or returned by
+    /// the next successful call.
+    pub fn with_diagnostics(cm: Arc<SourceMap>) -> Self {
+        let collector = CollectedDiagnostics::default();
+        let handler = Handler::with_emitter(true, false, box collector.clone());
+
+        Compiler {
+            diagnostics: Some(collector),
+            ..Compiler::new(cm, handler)
+        }
+    }
+
+    /// Takes diagnostics stored since the last call, if the compiler is
+    /// created by [Compiler::with_diagnostics].
+    pub fn take_diagnostics(&self) -> Vec<Diagnostic> {
+        match self.diagnostics {
+            Some(ref collector) => collector.take(),
+            None => vec![],
         }
     }
 
@@ -279,8 +710,30 @@ impl Compiler {
         &self,
         opts: &Options,
         name: &FileName,
+    ) -> Result<BuiltConfig<impl Pass>, Error> {
+        self.config_for_file_with_resolver(opts, name, &FsSwcrcResolver)
+    }
+
+    /// Same as [Compiler::config_for_file], but `.swcrc` files are looked up
+    /// using `resolver` instead of the file system.
+    pub fn config_for_file_with_resolver(
+        &self,
+        opts: &Options,
+        name: &FileName,
+        resolver: &dyn SwcrcResolver,
+    ) -> Result<BuiltConfig<impl Pass>, Error> {
+        self.config_for_file_inner(opts, name, resolver, None)
+    }
+
+    fn config_for_file_inner(
+        &self,
+        opts: &Options,
+        name: &FileName,
+        resolver: &dyn SwcrcResolver,
+        override_opts: Option<&Options>,
     ) -> Result<BuiltConfig<impl Pass>, Error> {
         self.run(|| -> Result<_, Error> {
+            let override_config = override_opts.and_then(|o| o.config.as_ref());
             let Options {
                 ref root,
                 root_mode,
@@ -297,10 +750,7 @@ impl Compiler {
                 }
             });
 
-            let config_file = match config_file {
-                Some(ConfigFile::Str(ref s)) => Some(load_swcrc(Path::new(&s))?),
-                _ => None,
-            };
+            let (config_file, config_file_name) = load_config_file(config_file)?;
 
             match name {
                 FileName::Real(ref path) => {
@@ -309,18 +759,34 @@ impl Compiler {
                         while let Some(dir) = parent {
                             let swcrc = dir.join(".swcrc");
 
-                            if swcrc.exists() {
-                                let config = load_swcrc(&swcrc)?;
-
-                                let mut config = config
-                                    .into_config(Some(path))
-                                    .context("failed to process config file")?;
+                            if let Some(config) = resolver.resolve(&swcrc)? {
+                                let mut config =
+                                    config.into_config(Some(path)).with_context(|| {
+                                        format!(
+                                            "failed to process config file '{}'",
+                                            swcrc.display()
+                                        )
+                                    })?;
 
                                 if let Some(config_file) = config_file {
-                                    config.merge(&config_file.into_config(Some(path))?)
+                                    let config_file =
+                                        config_file.into_config(Some(path)).with_context(|| {
+                                            format!(
+                                                "failed to process config file {} to merge into \
+                                                 '{}'",
+                                                config_file_name,
+                                                swcrc.display()
+                                            )
+                                        })?;
+                                    config.merge(&config_file)
                                 }
-                                let built =
-                                    opts.build(&self.cm, &self.handler, *is_module, Some(config));
+                                let built = opts.build_with_override(
+                                    &self.cm,
+                                    &self.handler,
+                                    *is_module,
+                                    Some(config),
+                                    override_config,
+                                );
                                 return Ok(built);
                             }
 
@@ -332,18 +798,19 @@ impl Compiler {
                     }
 
                     let config_file = config_file.unwrap_or_else(|| Rc::default());
-                    let built = opts.build(
+                    let built = opts.build_with_override(
                         &self.cm,
                         &self.handler,
                         *is_module,
                         Some(config_file.into_config(Some(path))?),
+                        override_config,
                     );
                     return Ok(built);
                 }
                 _ => {}
             }
 
-            let built = opts.build(
+            let built = opts.build_with_override(
                 &self.cm,
                 &self.handler,
                 *is_module,
@@ -351,117 +818,1222 @@ impl Compiler {
                     Some(config_file) => Some(config_file.into_config(None)?),
                     None => Some(Rc::default().into_config(None)?),
                 },
+                override_config,
             );
             Ok(built)
         })
         .with_context(|| format!("failed to load config for file '{:?}'", name))
     }
 
-    // TODO: Handle source map
-    pub fn process_js_file(
-        &self,
-        fm: Arc<SourceFile>,
-        opts: &Options,
-    ) -> Result<TransformOutput, Error> {
-        self.run(|| -> Result<_, Error> {
-            let config = self.run(|| self.config_for_file(opts, &fm.name))?;
-            let (program, src_map) = self.parse_js(
-                fm.clone(),
-                config.target,
-                config.syntax,
-                config.is_module,
-                true,
-                &config.input_source_map,
-            )?;
+    /// Checks `opts` without an input file.
+    ///
+    /// The config file specified by `config_file` is loaded, and each config
+    /// in it is merged with `config` of `opts` and checked for invalid
+    /// combinations of options. `.swcrc` files are not looked up.
+    ///
+    /// All problems are reported by a single error.
+    pub fn validate_options(&self, opts: &Options) -> Result<(), Error> {
+        let (config_file, _) = load_config_file(&opts.config_file)?;
 
-            self.process_js_inner(program, src_map, config)
-        })
-        .context("failed to process js file")
+        let configs = match config_file {
+            Some(Rc::Single(config)) => vec![config],
+            Some(Rc::Multi(configs)) => configs,
+            None => vec![Default::default()],
+        };
+
+        let is_multi = configs.len() > 1;
+        let mut errors = vec![];
+        for (idx, mut config) in configs.into_iter().enumerate() {
+            if let Some(ref c) = opts.config {
+                config.merge(c);
+            }
+
+            errors.extend(config.validate(opts.is_module).into_iter().map(|err| {
+                if is_multi {
+                    format!("config {}: {}", idx, err)
+                } else {
+                    err
+                }
+            }));
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::msg(format!(
+                "invalid options:\n{}",
+                errors.join("\n")
+            )))
+        }
     }
 
-    /// You can use custom pass with this method.
+    /// Parses `src` as a single expression.
     ///
-    /// There exists a [PassBuilder] to help building custom passes.
-    pub fn process_js(
-        &self,
-        program: Program,
-        src_map: Option<sourcemap::SourceMap>,
-        opts: &Options,
-    ) -> Result<TransformOutput, Error> {
+    /// `src` is added to [Compiler::cm] as an anonymous file, and parsed with
+    /// the default target. It's an error if `src` contains anything other
+    /// than an expression, except a trailing semicolon.
+    pub fn parse_expr(&self, src: &str, syntax: Syntax) -> Result<Box<Expr>, Error> {
         self.run(|| -> Result<_, Error> {
-            let loc = self.cm.lookup_char_pos(program.span().lo());
-            let fm = loc.file;
-
-            let config = self.run(|| self.config_for_file(opts, &fm.name))?;
+            let fm = self.cm.new_source_file(FileName::Anon, src.into());
 
-            self.process_js_inner(program, src_map, config)
-        })
-        .context("failed to process js module")
-    }
+            let session = ParseSess {
+                handler: &self.handler,
+            };
+            let lexer = Lexer::new(
+                session,
+                syntax,
+                Default::default(),
+                SourceFileInput::from(&*fm),
+                None,
+            );
+            let mut parser = Parser::new_from(session, lexer);
+            let expr = parser.parse_expr().map_err(|mut e| {
+                e.emit();
+                Error::msg("failed to parse expression")
+            })?;
 
-    fn process_js_inner(
-        &self,
-        program: Program,
-        src_map: Option<sourcemap::SourceMap>,
-        config: BuiltConfig<impl Pass>,
-    ) -> Result<TransformOutput, Error> {
-        self.run(|| {
-            if config.minify {
-                let preserve_excl = |_: &BytePos, vc: &mut Vec<Comment>| -> bool {
-                    vc.retain(|c: &Comment| c.text.starts_with("!"));
-                    !vc.is_empty()
-                };
-                self.comments.retain_leading(preserve_excl);
-                self.comments.retain_trailing(preserve_excl);
-            }
-            let mut pass = config.pass;
-            let program = helpers::HELPERS.set(&Helpers::new(config.external_helpers), || {
-                util::HANDLER.set(&self.handler, || {
-                    // Fold module
-                    program.fold_with(&mut pass)
+            let rest = parser.parse_script().map_err(|mut e| {
+                e.emit();
+                Error::msg("failed to parse expression")
+            })?;
+            let extra = rest
+                .body
+                .iter()
+                .filter(|stmt| match stmt {
+                    Stmt::Empty(..) => false,
+                    _ => true,
                 })
-            });
+                .count();
+            if extra != 0 {
+                return Err(Error::msg(format!(
+                    "expected a single expression, but found {} more statement(s)",
+                    extra
+                )));
+            }
 
-            self.print(
-                &program,
-                &self.comments,
-                config.source_maps,
-                src_map.as_ref(),
-                config.minify,
-            )
+            Ok(expr)
         })
     }
-}
 
-struct MyHandlers;
+    /// Parses `src` as a single statement.
+    ///
+    /// `src` is added to [Compiler::cm] as an anonymous file, and parsed as a
+    /// script with the default target. It's an error if `src` contains more
+    /// than one statement.
+    pub fn parse_stmt(&self, src: &str, syntax: Syntax) -> Result<Stmt, Error> {
+        self.run(|| -> Result<_, Error> {
+            let fm = self.cm.new_source_file(FileName::Anon, src.into());
 
-impl ecmascript::codegen::Handlers for MyHandlers {}
+            let session = ParseSess {
+                handler: &self.handler,
+            };
+            let lexer = Lexer::new(
+                session,
+                syntax,
+                Default::default(),
+                SourceFileInput::from(&*fm),
+                None,
+            );
+            let mut parser = Parser::new_from(session, lexer);
+            let mut script = parser.parse_script().map_err(|mut e| {
+                e.emit();
+                Error::msg("failed to parse statement")
+            })?;
 
-fn load_swcrc(path: &Path) -> Result<Rc, Error> {
-    fn convert_json_err(e: serde_json::Error) -> Error {
-        let line = e.line();
-        let column = e.column();
+            if script.body.len() != 1 {
+                return Err(Error::msg(format!(
+                    "expected a single statement, but found {} statements",
+                    script.body.len()
+                )));
+            }
 
-        let msg = match e.classify() {
-            Category::Io => "io error",
-            Category::Syntax => "syntax error",
-            Category::Data => "unmatched data",
-            Category::Eof => "unexpected eof",
-        };
-        Error::new(e).context(format!(
-            "failed to deserialize .swcrc (json) file: {}: {}:{}",
-            msg, line, column
-        ))
+            Ok(script.body.remove(0))
+        })
     }
 
-    let content = read_to_string(path).context("failed to read config (.swcrc) file")?;
+    /// Parses `fm` using the syntax and target derived from `opts`, without
+    /// running any pass or emitting code.
+    ///
+    /// Returns diagnostics reported by the parser. The returned vector is
+    /// empty if the file is syntactically valid.
+    pub fn check_syntax_only(
+        &self,
+        fm: Arc<SourceFile>,
+        opts: &Options,
+    ) -> Result<Vec<Diagnostic>, Error> {
+        self.run(|| -> Result<_, Error> {
+            let config = self.config_for_file(opts, &fm.name)?;
+
+            let collector = CollectedDiagnostics::default();
+            let handler = Handler::with_emitter(true, false, box collector.clone());
+            let session = ParseSess { handler: &handler };
+            let lexer = Lexer::new(
+                session,
+                config.syntax,
+                config.target,
+                SourceFileInput::from(&*fm),
+                None,
+            );
+            let mut parser = Parser::new_from(session, lexer);
+            let res = if config.is_module {
+                parser.parse_module().map(drop)
+            } else {
+                parser.parse_script().map(drop)
+            };
+            if let Err(mut e) = res {
+                e.emit();
+            }
 
-    match serde_json::from_str(&content) {
-        Ok(v) => return Ok(v),
-        Err(..) => {}
+            Ok(collector.into())
+        })
+        .context("failed to check syntax of js file")
     }
 
-    serde_json::from_str::<Config>(&content)
-        .map(Rc::Single)
-        .map_err(convert_json_err)
+    /// Classifies `fm` as an es module or a CommonJS module by scanning tokens,
+    /// without parsing it.
+    ///
+    /// The scan stops at the first of these signals.
+    ///
+    ///  - [ModuleKind::Module]: `import` (except a dynamic `import()`),
+    ///    `export`, or `await` outside of any block.
+    ///  - [ModuleKind::CommonJs]: `require(`, `module.exports` or `exports.`.
+    ///
+    /// If there's no signal, the file may be a script or a module without
+    /// imports and exports, so [ModuleKind::Unknown] is returned.
+    pub fn detect_module_kind(
+        &self,
+        fm: Arc<SourceFile>,
+        syntax: Syntax,
+    ) -> Result<ModuleKind, Error> {
+        self.run(|| {
+            let session = ParseSess {
+                handler: &self.handler,
+            };
+            let lexer = Lexer::new(
+                session,
+                syntax,
+                JscTarget::EsNext,
+                SourceFileInput::from(&*fm),
+                None,
+            );
+
+            let is_ident = |t: &Option<Token>, name: &str| match *t {
+                Some(Token::Word(Word::Ident(ref i))) => &**i == name,
+                _ => false,
+            };
+
+            let mut depth = 0usize;
+            // Nesting level of all brackets, and the levels at which the
+            // expression bodies of arrow functions started.
+            let mut nesting = 0usize;
+            let mut arrow_bodies: Vec<usize> = vec![];
+            let mut prev2 = None;
+            let mut prev = None;
+
+            for TokenAndSpan {
+                token,
+                had_line_break,
+                ..
+            } in lexer
+            {
+                // An expression body ends at a `,` or `;` on its own level, when
+                // its enclosing bracket is closed, or at a new statement on the
+                // next line.
+                while let Some(&level) = arrow_bodies.last() {
+                    let ends = match token {
+                        Token::Semi | Token::Comma => nesting == level,
+                        Token::RParen | Token::RBracket | Token::RBrace => nesting <= level,
+                        Token::Word(..) => {
+                            had_line_break
+                                && nesting == level
+                                && prev.as_ref().map_or(false, |t| !t.before_expr())
+                        }
+                        _ => false,
+                    };
+                    if !ends {
+                        break;
+                    }
+                    arrow_bodies.pop();
+                }
+
+                match token {
+                    Token::LParen | Token::LBracket | Token::LBrace | Token::DollarLBrace => {
+                        nesting += 1
+                    }
+                    Token::RParen | Token::RBracket | Token::RBrace => {
+                        nesting = nesting.saturating_sub(1)
+                    }
+                    _ => {}
+                }
+                if prev == Some(Token::Arrow) && token != Token::LBrace {
+                    arrow_bodies.push(nesting);
+                }
+
+                let after_dot = prev2 == Some(Token::Dot);
+                match token {
+                    Token::Error(e) => {
+                        return Err(Error::msg(format!(
+                            "failed to tokenize {}: {:?}",
+                            fm.name, e.error
+                        )))
+                    }
+                    Token::LBrace | Token::DollarLBrace => depth += 1,
+                    Token::RBrace => depth = depth.saturating_sub(1),
+
+                    Token::Word(Word::Keyword(Keyword::Export)) if prev != Some(Token::Dot) => {
+                        return Ok(ModuleKind::Module)
+                    }
+                    Token::Word(Word::Keyword(Keyword::Await))
+                        if depth == 0 && arrow_bodies.is_empty() && prev != Some(Token::Dot) =>
+                    {
+                        return Ok(ModuleKind::Module)
+                    }
+
+                    Token::LParen if is_ident(&prev, "require") && !after_dot => {
+                        return Ok(ModuleKind::CommonJs)
+                    }
+                    Token::Word(Word::Ident(ref i))
+                        if &**i == "exports"
+                            && prev == Some(Token::Dot)
+                            && is_ident(&prev2, "module") =>
+                    {
+                        return Ok(ModuleKind::CommonJs)
+                    }
+                    Token::Dot if is_ident(&prev, "exports") && !after_dot => {
+                        return Ok(ModuleKind::CommonJs)
+                    }
+                    _ => {}
+                }
+
+                // `import(` is a dynamic import, which is allowed in scripts.
+                if prev == Some(Token::Word(Word::Keyword(Keyword::Import)))
+                    && prev2 != Some(Token::Dot)
+                    && token != Token::LParen
+                {
+                    return Ok(ModuleKind::Module);
+                }
+
+                prev2 = prev;
+                prev = Some(token);
+            }
+
+            Ok(ModuleKind::Unknown)
+        })
+    }
+
+    /// Returns tokens of `fm` without parsing it, e.g. for syntax
+    /// highlighting.
+    ///
+    /// JSX and TypeScript tokens are produced only if they are enabled by
+    /// `syntax`. Comments are not included.
+    pub fn tokenize(
+        &self,
+        fm: Arc<SourceFile>,
+        syntax: Syntax,
+        target: JscTarget,
+    ) -> Result<Vec<TokenAndSpan>, Error> {
+        self.run(|| {
+            let session = ParseSess {
+                handler: &self.handler,
+            };
+            let lexer = Lexer::new(session, syntax, target, SourceFileInput::from(&*fm), None);
+
+            let mut tokens = vec![];
+            for t in lexer {
+                if let Token::Error(e) = t.token {
+                    return Err(Error::msg(format!(
+                        "failed to tokenize {}: {:?}",
+                        fm.name, e.error
+                    )));
+                }
+
+                tokens.push(t);
+            }
+
+            Ok(tokens)
+        })
+    }
+
+    // TODO: Handle source map
+    pub fn process_js_file(
+        &self,
+        fm: Arc<SourceFile>,
+        opts: &Options,
+    ) -> Result<TransformOutput, Error> {
+        self.process_js_file_with_override(fm, opts, None)
+    }
+
+    /// Same as [Compiler::process_js_file], but `config` of `override_opts`
+    /// is applied on top of the resolved config.
+    ///
+    /// Config is merged in the order of `.swcrc`, `config_file` and `config`
+    /// of `opts`, and `config` of `override_opts` is applied last. Unlike
+    /// merging, values of the override always win, so it can turn options
+    /// off. Only fields which are [None] in the override are inherited, so
+    /// fields which are not optional, like `jsc.externalHelpers`, are always
+    /// taken from it. Other fields of `override_opts` are ignored, and `opts`
+    /// still controls how config files are resolved.
+    pub fn process_js_file_with_override(
+        &self,
+        fm: Arc<SourceFile>,
+        opts: &Options,
+        override_opts: Option<&Options>,
+    ) -> Result<TransformOutput, Error> {
+        self.run(|| -> Result<_, Error> {
+            let config = self.run(|| {
+                self.config_for_file_inner(opts, &fm.name, &FsSwcrcResolver, override_opts)
+            })?;
+            let (program, src_map) = self.parse_js(
+                fm.clone(),
+                config.target,
+                config.syntax,
+                config.is_module,
+                true,
+                &config.input_source_map,
+            )?;
+
+            self.process_js_inner(program, src_map, config, None, &self.comments)
+        })
+        .context("failed to process js file")
+    }
+
+    /// Same as [Compiler::process_js_file], but only emits top-level
+    /// statements within `range`.
+    ///
+    /// The whole file is parsed and transformed, so bindings declared outside
+    /// of `range` are resolved correctly. A statement is emitted only if it's
+    /// fully contained in `range`, which means statements split by the range
+    /// are omitted entirely. Statements injected by passes, like helpers, are
+    /// omitted too.
+    ///
+    /// `range` uses positions of the source map of the compiler, i.e. it's
+    /// relative to `fm.start_pos`, not to the start of the file.
+    pub fn process_js_range(
+        &self,
+        fm: Arc<SourceFile>,
+        opts: &Options,
+        range: Range<BytePos>,
+    ) -> Result<TransformOutput, Error> {
+        self.run(|| -> Result<_, Error> {
+            let config = self.run(|| self.config_for_file(opts, &fm.name))?;
+            let (program, src_map) = self.parse_js(
+                fm.clone(),
+                config.target,
+                config.syntax,
+                config.is_module,
+                true,
+                &config.input_source_map,
+            )?;
+
+            self.process_js_inner(program, src_map, config, Some(range), &self.comments)
+        })
+        .context("failed to process range of js file")
+    }
+
+    /// Runs all passes of `opts` on `fm` like [Compiler::process_js_file],
+    /// but doesn't build the output code.
+    ///
+    /// The output is hashed and compared with the source of `fm` while it's
+    /// emitted, so this can be used to check if a file is already formatted
+    /// or minified. Source maps are not generated.
+    pub fn analyze_js_file(
+        &self,
+        fm: Arc<SourceFile>,
+        opts: &Options,
+    ) -> Result<AnalyzeResult, Error> {
+        self.run(|| -> Result<_, Error> {
+            let mut config = self.run(|| self.config_for_file(opts, &fm.name))?;
+            let (program, _) = self.parse_js(
+                fm.clone(),
+                config.target,
+                config.syntax,
+                config.is_module,
+                true,
+                &InputSourceMap::Bool(false),
+            )?;
+            let (program, _, _) = self.run_passes(program, &mut config, &self.comments)?;
+
+            let mut wr = AnalyzeWriter::new(fm.src.as_bytes());
+            {
+                let mut emitter = Emitter {
+                    cfg: config.codegen,
+                    comments: Some(&self.comments),
+                    cm: self.cm.clone(),
+                    wr: box codegen::text_writer::JsWriter::new(
+                        self.cm.clone(),
+                        "\n",
+                        &mut wr,
+                        None,
+                    ),
+                    handlers: box MyHandlers,
+                };
+
+                emitter
+                    .emit_program(&program)
+                    .context("failed to emit module")?;
+            }
+
+            Ok(wr.finish())
+        })
+        .context("failed to analyze js file")
+    }
+
+    /// Parses `fm` and runs all passes of `opts` on it like
+    /// [Compiler::process_js_file], but returns the transformed program
+    /// instead of printing it.
+    ///
+    /// Comments of the program are returned along with it, and they should be
+    /// passed to [Compiler::print] if the program is printed later. An input
+    /// source map of `fm` is not loaded, so composing source maps is the
+    /// responsibility of the caller.
+    pub fn fold_program(
+        &self,
+        fm: Arc<SourceFile>,
+        opts: &Options,
+    ) -> Result<(Program, &Comments), Error> {
+        self.run(|| -> Result<_, Error> {
+            let mut config = self.run(|| self.config_for_file(opts, &fm.name))?;
+            let (program, _) = self.parse_js(
+                fm.clone(),
+                config.target,
+                config.syntax,
+                config.is_module,
+                true,
+                &InputSourceMap::Bool(false),
+            )?;
+            let (program, _, _) = self.run_passes(program, &mut config, &self.comments)?;
+
+            Ok((program, &self.comments))
+        })
+        .context("failed to fold program")
+    }
+
+    /// Transforms each file of `files` with `opts` and concatenates the
+    /// outputs in the given order using [Compiler::concat].
+    ///
+    /// Imports are not resolved, so `files` should be already sorted by the
+    /// caller. Source maps are merged only if `opts` enables non-inline
+    /// source maps.
+    pub fn bundle_concat(
+        &self,
+        files: &[Arc<SourceFile>],
+        opts: &Options,
+    ) -> Result<TransformOutput, Error> {
+        let outputs = files
+            .iter()
+            .map(|fm| {
+                let output = self
+                    .process_js_file(fm.clone(), opts)
+                    .with_context(|| format!("failed to process {}", fm.name))?;
+                Ok((fm.name.clone(), output))
+            })
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        self.concat(&outputs)
+    }
+
+    /// You can use custom pass with this method.
+    ///
+    /// There exists a [PassBuilder] to help building custom passes.
+    pub fn process_js(
+        &self,
+        program: Program,
+        src_map: Option<sourcemap::SourceMap>,
+        opts: &Options,
+    ) -> Result<TransformOutput, Error> {
+        self.run(|| -> Result<_, Error> {
+            let loc = self.cm.lookup_char_pos(program.span().lo());
+            let fm = loc.file;
+
+            let config = self.run(|| self.config_for_file(opts, &fm.name))?;
+
+            self.process_js_inner(program, src_map, config, None, &self.comments)
+        })
+        .context("failed to process js module")
+    }
+
+    /// Same as [Compiler::process_js], but uses `comments` instead of
+    /// [Compiler::comments].
+    ///
+    /// This is useful if comments of `program` were collected or relocated
+    /// by the caller. Passes see `comments` through [util::COMMENTS], and
+    /// comments emitted in the output are taken out of `comments`.
+    pub fn process_js_with_comments(
+        &self,
+        program: Program,
+        src_map: Option<sourcemap::SourceMap>,
+        opts: &Options,
+        comments: &Comments,
+    ) -> Result<TransformOutput, Error> {
+        self.run(|| -> Result<_, Error> {
+            let loc = self.cm.lookup_char_pos(program.span().lo());
+            let fm = loc.file;
+
+            let config = self.run(|| self.config_for_file(opts, &fm.name))?;
+
+            self.process_js_inner(program, src_map, config, None, comments)
+        })
+        .context("failed to process js module")
+    }
+
+    /// Same as [Compiler::process_js], but uses `config` instead of resolving
+    /// config for the file.
+    ///
+    /// `config` is consumed, as passes in it may have state for the file they
+    /// process. To transform many files with the same config without touching
+    /// the file system, resolve [Config] once and call [Options::build] for
+    /// each file. `config` must be built in [Compiler::run] of this compiler,
+    /// as marks of passes are created while building it.
+    ///
+    /// [Config]: crate::config::Config
+    pub fn process_with_config(
+        &self,
+        program: Program,
+        src_map: Option<sourcemap::SourceMap>,
+        config: BuiltConfig<impl Pass>,
+    ) -> Result<TransformOutput, Error> {
+        self.process_js_inner(program, src_map, config, None, &self.comments)
+            .context("failed to process js module")
+    }
+
+    /// Transforms an expression with passes configured by `opts`.
+    ///
+    /// The expression is transformed as an expression statement in a module,
+    /// and the generated code is the one of the statement. If passes convert it
+    /// into something else, e.g. by injecting helpers, an error is returned
+    /// and [Compiler::process_stmt] should be used instead.
+    pub fn process_expr(&self, expr: Expr, opts: &Options) -> Result<FragmentOutput<Expr>, Error> {
+        let stmt = Stmt::Expr(ExprStmt {
+            span: DUMMY_SP,
+            expr: box expr,
+        });
+
+        let mut output = self
+            .process_stmt(stmt, opts)
+            .context("failed to process expression")?;
+
+        match (output.node.len(), output.node.pop()) {
+            (1, Some(ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. })))) => Ok(FragmentOutput {
+                // Parens added by fixer because of the statement position.
+                node: match *expr {
+                    Expr::Paren(ParenExpr { expr, .. }) => *expr,
+                    expr => expr,
+                },
+                code: output.code,
+            }),
+            _ => Err(Error::msg(
+                "failed to process expression: it's transformed into statements",
+            )),
+        }
+    }
+
+    /// Transforms a statement with passes configured by `opts`.
+    ///
+    /// The statement is transformed as the body of a module, and the returned
+    /// body may contain items injected by passes, like helpers.
+    ///
+    /// `.swcrc` is resolved using [Options::filename], if it's not empty.
+    pub fn process_stmt(
+        &self,
+        stmt: Stmt,
+        opts: &Options,
+    ) -> Result<FragmentOutput<Vec<ModuleItem>>, Error> {
+        self.run(|| -> Result<_, Error> {
+            let name = if opts.filename.is_empty() {
+                FileName::Anon
+            } else {
+                FileName::Real(opts.filename.clone().into())
+            };
+            let config = self.config_for_file(opts, &name)?;
+
+            let program = Program::Module(Module {
+                span: DUMMY_SP,
+                body: vec![ModuleItem::Stmt(stmt)],
+                shebang: None,
+            });
+
+            let helpers = new_helpers(&config);
+            let mut pass = config.pass;
+            let program = helpers::HELPERS.set(&helpers, || {
+                util::HANDLER.set(&self.handler, || program.fold_with(&mut pass))
+            });
+
+            // Comments of the compiler belong to source files, not to the fragment.
+            let code = self
+                .print(
+                    &program,
+                    &Default::default(),
+                    SourceMapsConfig::Bool(false),
+                    None,
+                    config.codegen,
+                )?
+                .code;
+
+            let node = match program {
+                Program::Module(module) => module.body,
+                Program::Script(..) => unreachable!("a module is transformed into a script"),
+            };
+
+            Ok(FragmentOutput { node, code })
+        })
+        .context("failed to process statement")
+    }
+
+    fn process_js_inner(
+        &self,
+        program: Program,
+        src_map: Option<sourcemap::SourceMap>,
+        mut config: BuiltConfig<impl Pass>,
+        range: Option<Range<BytePos>>,
+        comments: &Comments,
+    ) -> Result<TransformOutput, Error> {
+        self.run(|| {
+            // `sourceFileName` names the file which is transformed.
+            let input_file = match config.source_file_name {
+                Some(..) if !program.span().is_dummy() => {
+                    Some(self.cm.lookup_source_file(program.span().lo()).name.clone())
+                }
+                _ => None,
+            };
+            let (program, helpers, timings) = self.run_passes(program, &mut config, comments)?;
+
+            let program = match range {
+                Some(range) => retain_range(program, &range),
+                None => program,
+            };
+
+            let filename_map = &config.filename_map;
+            let sources_root = &config.sources_root;
+            let warned = RefCell::new(HashSet::new());
+            let source_name = |name: &FileName| match (filename_map, sources_root, name) {
+                (Some(filename_map), _, _) => (**filename_map)(name),
+                (None, Some(root), FileName::Real(path)) => {
+                    self.relative_source_name(root, path, &mut warned.borrow_mut())
+                }
+                _ => name.to_string(),
+            };
+
+            let mut output = self.print_inner(
+                &program,
+                comments,
+                config.source_maps,
+                src_map.as_ref(),
+                config.codegen.clone(),
+                box MyHandlers,
+                EmitOptions {
+                    source_file_name: input_file
+                        .as_ref()
+                        .and_then(|file| Some((file, config.source_file_name.as_deref()?))),
+                    filename_map: Some(&source_name as &dyn Fn(&FileName) -> String),
+                    ignore_source: config
+                        .source_map_ignore
+                        .as_ref()
+                        .map(|f| &**f as &dyn Fn(&FileName) -> bool),
+                    sources_content: config.sources_content,
+                    source_map_url: config.source_map_url.as_deref(),
+                    output_comments: config.output_comments,
+                },
+            )?;
+            output.used_helpers = helpers.used();
+            output.pass_timings = timings
+                .take()
+                .into_iter()
+                .map(|(name, duration)| PassTiming {
+                    name: name.into_owned(),
+                    duration,
+                })
+                .collect();
+            if config.emit_pretty_alongside {
+                let output_pretty = self.print(
+                    &program,
+                    comments,
+                    SourceMapsConfig::Bool(false),
+                    None,
+                    codegen::Config {
+                        minify: false,
+                        ..config.codegen
+                    },
+                )?;
+                output.pretty = Some(output_pretty.code);
+            }
+            if config.source_map_object {
+                output.map_object = match output.map.take() {
+                    Some(map) => {
+                        Some(serde_json::from_str(&map).context("failed to parse source map")?)
+                    }
+                    None => None,
+                };
+            }
+            output.diagnostics = self.take_diagnostics();
+
+            Ok(output)
+        })
+    }
+
+    /// Returns the path of `path` relative to `root`, joined by `/`.
+    ///
+    /// If `path` is outside of `root`, its absolute path is returned and a
+    /// warning is reported, once per a path in `warned`.
+    fn relative_source_name(
+        &self,
+        root: &Path,
+        path: &Path,
+        warned: &mut HashSet<PathBuf>,
+    ) -> String {
+        let absolute = |path: &Path| match std::env::current_dir() {
+            Ok(cwd) if path.is_relative() => cwd.join(path),
+            _ => path.to_path_buf(),
+        };
+        let path = absolute(path);
+
+        match path.strip_prefix(absolute(root)) {
+            Ok(relative) => relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+            Err(_) => {
+                if warned.insert(path.clone()) {
+                    self.handler.warn(&format!(
+                        "{} is outside of the root {}, so its absolute path is recorded in the \
+                         source map",
+                        path.display(),
+                        root.display()
+                    ));
+                }
+                path.display().to_string()
+            }
+        }
+    }
+
+    /// Runs passes of `config` on `program`, unless it's disabled by
+    /// `@swc-disable`.
+    ///
+    /// Passes see `comments` through [util::COMMENTS]. Comments not retained
+    /// by `jsc.comments` are dropped before running passes.
+    ///
+    /// Fails if a pass reports an error to [util::HANDLER], e.g. for a const
+    /// enum member accessed with a computed key, if the code is nested too
+    /// deeply to be inlined, or if a pass reports a warning and
+    /// [Options::fail_on_warnings] is enabled.
+    fn run_passes(
+        &self,
+        program: Program,
+        config: &mut BuiltConfig<impl Pass>,
+        comments: &Comments,
+    ) -> Result<(Program, Helpers, Timings), Error> {
+        let err_count = self.handler.err_count();
+        let warn_count = self.handler.warn_count();
+        let disabled = is_disabled(&program, comments);
+
+        let policy = config.comments;
+        if policy != CommentsConfig::All {
+            comments.retain_leading(|_: &BytePos, vc: &mut Vec<Comment>| {
+                vc.retain(|c| policy.retains(c, true));
+                !vc.is_empty()
+            });
+            comments.retain_trailing(|_: &BytePos, vc: &mut Vec<Comment>| {
+                vc.retain(|c| policy.retains(c, false));
+                !vc.is_empty()
+            });
+        }
+        let helpers = new_helpers(config);
+        let pass = &mut config.pass;
+        let timings = Timings::default();
+        let program = if disabled {
+            program
+        } else {
+            let fold = || {
+                helpers::HELPERS.set(&helpers, || {
+                    util::HANDLER.set(&self.handler, || {
+                        COMMENTS.set(comments, || {
+                            // Fold module
+                            program.fold_with(pass)
+                        })
+                    })
+                })
+            };
+
+            if config.profile {
+                TIMINGS.set(&timings, fold)
+            } else {
+                fold()
+            }
+        };
+
+        if config.too_deep.load(Ordering::Relaxed) {
+            return Err(Error::msg("the code is nested too deeply to be optimized"));
+        }
+        if self.handler.err_count() > err_count {
+            return Err(Error::msg("failed to transform the file"));
+        }
+        let warnings = self.handler.warn_count() - warn_count;
+        if config.fail_on_warnings && warnings > 0 {
+            return Err(Error::msg(format!(
+                "{} warning(s) were reported while transforming the file",
+                warnings
+            )));
+        }
+        if config.preserve_all_comments {
+            relocate_orphan_comments(&program, comments);
+        }
+
+        Ok((program, helpers, timings))
+    }
+}
+
+/// Returns true if the first comment of `program` is `@swc-disable`, in which
+/// case the file is reprinted without running any pass.
+fn is_disabled(program: &Program, comments: &Comments) -> bool {
+    let comments = match comments.leading_comments(program.span().lo()) {
+        Some(comments) => comments,
+        None => return false,
+    };
+
+    comments.first().map_or(false, |c| {
+        c.text.split_whitespace().next() == Some("@swc-disable")
+    })
+}
+
+/// Removes top-level statements which are not fully contained in `range`.
+fn retain_range(mut program: Program, range: &Range<BytePos>) -> Program {
+    let contains =
+        |span: Span| !span.is_dummy() && range.start <= span.lo() && span.hi() <= range.end;
+
+    match program {
+        Program::Module(ref mut m) => m.body.retain(|item| contains(item.span())),
+        Program::Script(ref mut s) => s.body.retain(|stmt| contains(stmt.span())),
+    }
+
+    program
+}
+
+/// Returns the original names of identifiers whose name differs from the
+/// source, by their start positions.
+fn renamed_idents(cm: &SourceMap, program: &Program) -> HashMap<BytePos, String> {
+    let mut v = RenamedIdentFinder {
+        cm,
+        renamed: Default::default(),
+    };
+    program.visit_with(&mut v);
+
+    v.renamed
+}
+
+struct RenamedIdentFinder<'a> {
+    cm: &'a SourceMap,
+    renamed: HashMap<BytePos, String>,
+}
+
+/// Identifiers are recorded only if their span covers exactly one identifier
+/// name in the source, as transforms may create identifiers with spans of
+/// other nodes, like a temporary variable with the span of the expression it
+/// stores.
+impl Visit<Ident> for RenamedIdentFinder<'_> {
+    fn visit(&mut self, i: &Ident) {
+        if i.span == DUMMY_SP {
+            return;
+        }
+
+        let fm = self.cm.lookup_source_file(i.span.lo());
+        let lo = (i.span.lo() - fm.start_pos).0 as usize;
+        let hi = (i.span.hi() - fm.start_pos).0 as usize;
+        if hi > fm.src.len() || !fm.src.is_char_boundary(lo) || !fm.src.is_char_boundary(hi) {
+            return;
+        }
+
+        // The identifier should not continue before or after the span.
+        let starts_ident = fm.src[..lo]
+            .chars()
+            .next_back()
+            .map_or(true, |c| !is_ident_part(c));
+        if !starts_ident || ident_len(&fm.src[lo..]) != hi - lo {
+            return;
+        }
+
+        let src = &fm.src[lo..hi];
+        if src != &*i.sym {
+            self.renamed.insert(i.span.lo(), src.to_string());
+        }
+    }
+}
+
+/// Returns the length of the identifier name at the start of `s`, or 0 if
+/// there's none. Escape sequences are not supported.
+fn ident_len(s: &str) -> usize {
+    let mut chars = s.char_indices();
+    match chars.next() {
+        Some((_, c)) if c == '$' || c == '_' || c.is_alphabetic() => {}
+        _ => return 0,
+    }
+
+    chars
+        .find(|&(_, c)| !is_ident_part(c))
+        .map_or(s.len(), |(idx, _)| idx)
+}
+
+fn is_ident_part(c: char) -> bool {
+    c == '$' || c == '_' || c == '\u{200c}' || c == '\u{200d}' || c.is_alphanumeric()
+}
+
+fn new_helpers<P: Pass>(config: &BuiltConfig<P>) -> Helpers {
+    if config.skip_helpers_injection {
+        Helpers::global()
+    } else {
+        Helpers::new(config.external_helpers)
+    }
+}
+
+/// Options of [Compiler::print_inner] for the source map and comments of the
+/// output.
+struct EmitOptions<'a> {
+    /// If specified, the given file is named as the string in `sources` of
+    /// the source map.
+    source_file_name: Option<(&'a FileName, &'a str)>,
+
+    /// Names other entries of `sources` of the source map.
+    filename_map: Option<&'a dyn Fn(&FileName) -> String>,
+
+    /// Source files matching this are listed in `x_google_ignoreList` of the
+    /// source map.
+    ignore_source: Option<&'a dyn Fn(&FileName) -> bool>,
+
+    /// `sourcesContent` of the source map is dropped unless this is true.
+    sources_content: bool,
+
+    /// If specified, a `sourceMappingURL` comment is appended to the code of
+    /// an external source map.
+    source_map_url: Option<&'a str>,
+
+    /// If true, emitted comments are located using the mappings collected
+    /// for the source map.
+    output_comments: bool,
+}
+
+impl Default for EmitOptions<'_> {
+    fn default() -> Self {
+        EmitOptions {
+            source_file_name: None,
+            filename_map: None,
+            ignore_source: None,
+            sources_content: true,
+            source_map_url: None,
+            output_comments: false,
+        }
+    }
+}
+
+struct MyHandlers;
+
+/// Reports syntax which is not supported by the target as an error, and
+/// forwards events to `inner`.
+struct ReportUnsupported<'a> {
+    handler: &'a Handler,
+    inner: Box<dyn codegen::Handlers>,
+}
+
+impl codegen::Handlers for ReportUnsupported<'_> {
+    fn on_before_emit_stmt(&mut self, node: &Stmt) {
+        self.inner.on_before_emit_stmt(node)
+    }
+
+    fn on_unsupported_syntax(&mut self, span: Span, msg: &str) {
+        self.handler.struct_span_err(span, msg).emit();
+
+        self.inner.on_unsupported_syntax(span, msg)
+    }
+}
+
+/// Hashes emitted bytes and compares them with `src` instead of storing them.
+struct AnalyzeWriter<'a> {
+    src: &'a [u8],
+    pos: usize,
+    changed: bool,
+    hasher: DefaultHasher,
+}
+
+impl<'a> AnalyzeWriter<'a> {
+    fn new(src: &'a [u8]) -> Self {
+        AnalyzeWriter {
+            src,
+            pos: 0,
+            changed: false,
+            hasher: DefaultHasher::new(),
+        }
+    }
+
+    fn finish(self) -> AnalyzeResult {
+        AnalyzeResult {
+            changed: self.changed || self.pos != self.src.len(),
+            hash: self.hasher.finish(),
+        }
+    }
+}
+
+impl Write for AnalyzeWriter<'_> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.hasher.write(buf);
+
+        if !self.changed {
+            let end = self.pos + buf.len();
+            if self.src.get(self.pos..end) == Some(buf) {
+                self.pos = end;
+            } else {
+                self.changed = true;
+            }
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Stores diagnostics instead of printing them.
+#[derive(Clone, Default)]
+struct CollectedDiagnostics(Arc<RwLock<Vec<Diagnostic>>>);
+
+impl DiagEmitter for CollectedDiagnostics {
+    fn emit(&mut self, db: &DiagnosticBuilder) {
+        self.0.write().unwrap().push((**db).clone());
+    }
+}
+
+impl CollectedDiagnostics {
+    fn take(&self) -> Vec<Diagnostic> {
+        mem::replace(&mut *self.0.write().unwrap(), vec![])
+    }
+}
+
+impl From<CollectedDiagnostics> for Vec<Diagnostic> {
+    fn from(c: CollectedDiagnostics) -> Self {
+        c.0.read().unwrap().clone()
+    }
+}
+
+impl ecmascript::codegen::Handlers for MyHandlers {}
+
+/// Loads the config file specified by `configFile`, and returns it with a
+/// name used in error messages.
+fn load_config_file(config_file: &Option<ConfigFile>) -> Result<(Option<Rc>, String), Error> {
+    Ok(match config_file {
+        Some(ConfigFile::Str(ref s)) => (
+            Some(
+                load_swcrc(Path::new(&s))
+                    .context("failed to load the config file specified by `configFile`")?,
+            ),
+            format!("'{}'", s),
+        ),
+        Some(ConfigFile::Inline { ref inline }) => (
+            Some(parse_swcrc(inline).context("failed to parse inline config")?),
+            "inline config".to_string(),
+        ),
+        _ => (None, String::new()),
+    })
+}
+
+fn load_swcrc(path: &Path) -> Result<Rc, SwcrcError> {
+    let content = read_to_string(path).map_err(|err| SwcrcError::from_io(path, err))?;
+
+    parse_swcrc(&content).map_err(|source| SwcrcError::Parse {
+        path: path.to_path_buf(),
+        source,
+    })
+}
+
+/// Strict json is parsed first, so json files get precise errors. Files which
+/// are not valid json are parsed as json5, which allows comments and trailing
+/// commas.
+fn parse_swcrc(content: &str) -> Result<Rc, Error> {
+    fn convert_json_err(e: serde_json::Error) -> Error {
+        let line = e.line();
+        let column = e.column();
+
+        let msg = match e.classify() {
+            Category::Io => "io error".into(),
+            Category::Syntax => "syntax error".into(),
+            Category::Data => match unknown_field(&e) {
+                Some(key) => format!("unknown key `{}`", key),
+                None => "unmatched data".into(),
+            },
+            Category::Eof => "unexpected eof".into(),
+        };
+        Error::new(e).context(format!(
+            "failed to deserialize .swcrc (json) file: {}: {}:{}",
+            msg, line, column
+        ))
+    }
+
+    /// Extracts the name of the field from an error reported because of
+    /// `deny_unknown_fields`.
+    fn unknown_field(e: &serde_json::Error) -> Option<String> {
+        let msg = e.to_string();
+        let start = msg.find("unknown field `")? + "unknown field `".len();
+        let len = msg[start..].find('`')?;
+
+        Some(msg[start..start + len].to_string())
+    }
+
+    fn convert_json5_err(e: json5::Error) -> Error {
+        let msg = match e {
+            json5::Error::Message {
+                ref msg,
+                location: Some(ref loc),
+            } => format!("{}: {}:{}", msg, loc.line, loc.column),
+            json5::Error::Message { ref msg, .. } => msg.clone(),
+        };
+
+        Error::new(e).context(format!(
+            "failed to deserialize .swcrc (json5) file: {}",
+            msg
+        ))
+    }
+
+    match serde_json::from_str(content) {
+        Ok(v) => return Ok(v),
+        // Not a strict json file, but it may be a json5 file with comments or
+        // trailing commas.
+        Err(ref e) if e.classify() == Category::Syntax || e.classify() == Category::Eof => {
+            if let Ok(v) = json5::from_str(content) {
+                return Ok(v);
+            }
+
+            return if content.trim_start().starts_with('[') {
+                json5::from_str::<Vec<Config>>(content)
+                    .map(Rc::Multi)
+                    .map_err(convert_json5_err)
+            } else {
+                json5::from_str::<Config>(content)
+                    .map(Rc::Single)
+                    .map_err(convert_json5_err)
+            };
+        }
+        Err(..) => {}
+    }
+
+    // Report the error of the variant the file looks like, instead of the
+    // opaque error of the untagged enum.
+    if content.trim_start().starts_with('[') {
+        serde_json::from_str::<Vec<Config>>(content)
+            .map(Rc::Multi)
+            .map_err(convert_json_err)
+    } else {
+        serde_json::from_str::<Config>(content)
+            .map(Rc::Single)
+            .map_err(convert_json_err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn mappings_buf_disabled() {
+        assert!(mappings_buf(&SourceMapsConfig::Bool(false), false).is_none());
+        assert!(mappings_buf(&SourceMapsConfig::Bool(true), false).is_some());
+        assert!(mappings_buf(&SourceMapsConfig::Bool(false), true).is_some());
+    }
 }
@@ -0,0 +1,114 @@
+use crate::{
+    common::{comments::Comments, BytePos, Span, Spanned, Visit, VisitWith, DUMMY_SP},
+    ecmascript::ast::{ModuleItem, Program, Stmt},
+};
+use std::collections::HashSet;
+
+/// Moves comments whose node was removed by a transform to the nearest
+/// surviving statement, so the emitter doesn't drop them.
+///
+/// A leading comment is moved to the first statement which starts after it,
+/// and a trailing comment to the last statement which ends before it. If
+/// there's no such statement, a leading comment becomes a trailing comment of
+/// the last statement and vice versa.
+pub(crate) fn relocate_orphan_comments(program: &Program, comments: &Comments) {
+    let mut v = PosCollector::default();
+    program.visit_with(&mut v);
+    if v.stmts.is_empty() {
+        return;
+    }
+    v.stmts.sort();
+
+    let mut orphans = vec![];
+    comments.retain_leading(|pos, _| {
+        if !v.los.contains(pos) {
+            orphans.push((*pos, true));
+        }
+        true
+    });
+    comments.retain_trailing(|pos, _| {
+        if !v.his.contains(pos) {
+            orphans.push((*pos, false));
+        }
+        true
+    });
+    // Comments are iterated in no particular order. At the same position,
+    // trailing comments come first.
+    orphans.sort();
+
+    // Comments moved before existing ones, in source order.
+    let mut prepends = vec![];
+
+    for (pos, leading) in orphans {
+        let next = v.stmts.iter().find(|s| s.lo() >= pos);
+        let prev = v.stmts.iter().rev().find(|s| s.hi() <= pos);
+
+        match (leading, next, prev) {
+            (true, Some(next), _) => {
+                if let Some(cmts) = comments.take_leading_comments(pos) {
+                    prepends.push((next.lo(), cmts));
+                }
+            }
+            (false, _, Some(prev)) => comments.move_trailing(pos, prev.hi()),
+            (true, None, _) => {
+                let last = v.stmts.iter().map(|s| s.hi()).max().unwrap();
+                for cmt in comments.take_leading_comments(pos).into_iter().flatten() {
+                    comments.add_trailing(last, cmt);
+                }
+            }
+            (false, _, None) => {
+                if let Some(cmts) = comments.take_trailing_comments(pos) {
+                    prepends.push((v.stmts[0].lo(), cmts));
+                }
+            }
+        }
+    }
+
+    for (pos, mut cmts) in prepends.into_iter().rev() {
+        cmts.extend(comments.take_leading_comments(pos).into_iter().flatten());
+        comments.add_leading(pos, cmts);
+    }
+}
+
+/// Collects positions where the emitter looks up comments.
+#[derive(Default)]
+struct PosCollector {
+    los: HashSet<BytePos>,
+    his: HashSet<BytePos>,
+    /// Spans of statements.
+    stmts: Vec<Span>,
+}
+
+impl Visit<Span> for PosCollector {
+    fn visit(&mut self, span: &Span) {
+        if *span == DUMMY_SP {
+            return;
+        }
+        self.los.insert(span.lo());
+        self.his.insert(span.hi());
+    }
+}
+
+impl Visit<Stmt> for PosCollector {
+    fn visit(&mut self, node: &Stmt) {
+        let span = node.span();
+        if span != DUMMY_SP {
+            self.stmts.push(span);
+        }
+
+        node.visit_children(self);
+    }
+}
+
+impl Visit<ModuleItem> for PosCollector {
+    fn visit(&mut self, node: &ModuleItem) {
+        if let ModuleItem::ModuleDecl(ref decl) = *node {
+            let span = decl.span();
+            if span != DUMMY_SP {
+                self.stmts.push(span);
+            }
+        }
+
+        node.visit_children(self);
+    }
+}
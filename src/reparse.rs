@@ -0,0 +1,290 @@
+use crate::{
+    common::{BytePos, Fold, FoldWith, SourceFile, Span, Spanned},
+    config::{InputSourceMap, JscTarget},
+    ecmascript::{
+        ast::{Module, Program, Script},
+        parser::{lexer::Lexer, Parser, Session as ParseSess, SourceFileInput, Syntax},
+    },
+    Compiler,
+};
+use anyhow::Error;
+use std::{ops::Range, sync::Arc};
+
+/// An edit of a source file, used by [Compiler::reparse].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    /// Replaced range of the old source, in bytes from the start of the file.
+    pub range: Range<usize>,
+    /// Length of the inserted text in bytes.
+    pub new_len: usize,
+}
+
+impl Compiler {
+    /// Parses `fm`, which is the source of `prev` with `edit` applied, reusing
+    /// top-level statements of `prev` which are not touched by `edit`.
+    ///
+    /// Statements touched by the edit are parsed again along with their
+    /// neighbors, and spans of reused statements are moved to `fm`. `prev`
+    /// should be parsed from a file of [Compiler::cm] and not transformed.
+    /// Comments are not collected.
+    ///
+    /// The whole file is parsed again if
+    ///
+    ///  - `edit` doesn't match the lengths of the old and the new file.
+    ///  - `prev` contains a statement with a dummy span.
+    ///  - the reparsed statements fail to parse.
+    ///  - a neighbor is parsed differently, e.g. because the edit removed a
+    ///    semicolon and it's merged with a touched statement, or the edit
+    ///    started a comment.
+    pub fn reparse(
+        &self,
+        prev: &Program,
+        fm: Arc<SourceFile>,
+        edit: TextEdit,
+        target: JscTarget,
+        syntax: Syntax,
+    ) -> Result<Program, Error> {
+        if let Some(program) = self.run(|| self.try_reparse(prev, &fm, &edit, target, syntax)) {
+            return Ok(program);
+        }
+
+        let is_module = match *prev {
+            Program::Module(..) => true,
+            Program::Script(..) => false,
+        };
+        self.parse_js(
+            fm,
+            target,
+            syntax,
+            is_module,
+            false,
+            &InputSourceMap::Bool(false),
+        )
+        .map(|(program, _)| program)
+    }
+
+    fn try_reparse(
+        &self,
+        prev: &Program,
+        fm: &SourceFile,
+        edit: &TextEdit,
+        target: JscTarget,
+        syntax: Syntax,
+    ) -> Option<Program> {
+        if prev.span().is_dummy() {
+            return None;
+        }
+        let old = self.cm.lookup_source_file(prev.span().lo());
+
+        let session = ParseSess {
+            handler: &self.handler,
+        };
+
+        match *prev {
+            Program::Module(ref m) => {
+                let plan = Plan::new(&m.body, &old, fm, edit)?;
+                let lexer = Lexer::new(
+                    session,
+                    syntax,
+                    target,
+                    SourceFileInput::with_range(fm, plan.region.clone()),
+                    None,
+                );
+                let reparsed = Parser::new_from(session, lexer)
+                    .parse_module()
+                    .map_err(|mut e| e.cancel())
+                    .ok()?;
+
+                Some(Program::Module(Module {
+                    span: plan.span(m.span, reparsed.span),
+                    shebang: if plan.before == 0 {
+                        reparsed.shebang
+                    } else {
+                        m.shebang.clone()
+                    },
+                    body: plan.merge(&m.body, reparsed.body)?,
+                }))
+            }
+            Program::Script(ref s) => {
+                let plan = Plan::new(&s.body, &old, fm, edit)?;
+                let lexer = Lexer::new(
+                    session,
+                    syntax,
+                    target,
+                    SourceFileInput::with_range(fm, plan.region.clone()),
+                    None,
+                );
+                let reparsed = Parser::new_from(session, lexer)
+                    .parse_script()
+                    .map_err(|mut e| e.cancel())
+                    .ok()?;
+
+                Some(Program::Script(Script {
+                    span: plan.span(s.span, reparsed.span),
+                    shebang: if plan.before == 0 {
+                        reparsed.shebang
+                    } else {
+                        s.shebang.clone()
+                    },
+                    body: plan.merge(&s.body, reparsed.body)?,
+                }))
+            }
+        }
+    }
+}
+
+/// Top-level items to reparse.
+///
+/// Items in `before..after` are parsed again. Those are items touched by the
+/// edit, and the last untouched item before them and the first one after
+/// them.
+struct Plan {
+    before: usize,
+    after: usize,
+    /// Number of items before the edit.
+    touched_start: usize,
+    /// Index of the first item after the edit.
+    touched_end: usize,
+    len: usize,
+    /// Range of the new file to parse.
+    region: Range<BytePos>,
+    /// Offset of items before the edit.
+    shift_before: i64,
+    /// Offset of items after the edit.
+    shift_after: i64,
+}
+
+impl Plan {
+    fn new<T: Spanned>(
+        items: &[T],
+        old: &SourceFile,
+        new: &SourceFile,
+        edit: &TextEdit,
+    ) -> Option<Self> {
+        let old_len = old.src.len();
+        let new_len = new.src.len();
+        if edit.range.start > edit.range.end
+            || edit.range.end > old_len
+            || old_len - edit.range.len() + edit.new_len != new_len
+        {
+            return None;
+        }
+        if items.iter().any(|item| item.span().is_dummy()) {
+            return None;
+        }
+
+        let offset = |pos: BytePos| (pos - old.start_pos).0 as usize;
+        let touched_start = items
+            .iter()
+            .take_while(|item| offset(item.span().hi()) < edit.range.start)
+            .count();
+        let touched_end = touched_start
+            + items[touched_start..]
+                .iter()
+                .take_while(|item| offset(item.span().lo()) <= edit.range.end)
+                .count();
+
+        let before = touched_start.saturating_sub(1);
+        let after = (touched_end + 1).min(items.len());
+
+        // The region starts at the start of the file if there's no item to
+        // reuse before it, so a shebang is parsed again.
+        let start = if before == 0 {
+            0
+        } else {
+            offset(items[before].span().lo())
+        };
+        let end = if after == items.len() {
+            new_len
+        } else {
+            offset(items[after - 1].span().hi()) + edit.new_len - edit.range.len()
+        };
+
+        let shift_before = new.start_pos.0 as i64 - old.start_pos.0 as i64;
+        Some(Plan {
+            before,
+            after,
+            touched_start,
+            touched_end,
+            len: items.len(),
+            region: new.start_pos + BytePos(start as u32)..new.start_pos + BytePos(end as u32),
+            shift_before,
+            shift_after: shift_before + edit.new_len as i64 - edit.range.len() as i64,
+        })
+    }
+
+    fn span(&self, prev: Span, reparsed: Span) -> Span {
+        let lo = if self.before == 0 {
+            reparsed.lo()
+        } else {
+            shift(prev.lo(), self.shift_before)
+        };
+        let hi = if self.after == self.len {
+            reparsed.hi()
+        } else {
+            shift(prev.hi(), self.shift_after)
+        };
+
+        Span::new(lo, hi, prev.ctxt())
+    }
+
+    /// Returns `None` if the neighbors of touched items are parsed
+    /// differently.
+    fn merge<T>(&self, prev: &[T], reparsed: Vec<T>) -> Option<Vec<T>>
+    where
+        T: Clone + Spanned + FoldWith<SpanShifter>,
+    {
+        let same = |a: Span, b: Span| a.lo() == b.lo() && a.hi() == b.hi();
+
+        if self.touched_start != 0 {
+            let expected = shift_span(prev[self.before].span(), self.shift_before);
+            if !same(reparsed.first()?.span(), expected) {
+                return None;
+            }
+        }
+        if self.touched_end != self.len {
+            let expected = shift_span(prev[self.touched_end].span(), self.shift_after);
+            if !same(reparsed.last()?.span(), expected) {
+                return None;
+            }
+        }
+
+        let mut items = Vec::with_capacity(self.before + reparsed.len() + self.len - self.after);
+        items.extend(prev[..self.before].iter().cloned().map(|item| {
+            item.fold_with(&mut SpanShifter {
+                delta: self.shift_before,
+            })
+        }));
+        items.extend(reparsed);
+        items.extend(prev[self.after..].iter().cloned().map(|item| {
+            item.fold_with(&mut SpanShifter {
+                delta: self.shift_after,
+            })
+        }));
+
+        Some(items)
+    }
+}
+
+fn shift(pos: BytePos, delta: i64) -> BytePos {
+    BytePos((pos.0 as i64 + delta) as u32)
+}
+
+fn shift_span(span: Span, delta: i64) -> Span {
+    Span::new(shift(span.lo(), delta), shift(span.hi(), delta), span.ctxt())
+}
+
+/// Moves spans by `delta` bytes.
+struct SpanShifter {
+    delta: i64,
+}
+
+impl Fold<Span> for SpanShifter {
+    fn fold(&mut self, span: Span) -> Span {
+        if span.is_dummy() {
+            return span;
+        }
+
+        shift_span(span, self.delta)
+    }
+}
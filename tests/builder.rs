@@ -0,0 +1,219 @@
+#![feature(specialization)]
+
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+    sync::Arc,
+};
+use swc::{
+    common::{FileName, Fold, FoldWith, Mark},
+    config::{InputSourceMap, JscTarget, Options},
+    ecmascript::{
+        ast::{ArrowExpr, Ident, Program},
+        transforms::{
+            helpers::{self, Helpers},
+            pass::noop,
+        },
+    },
+    Compiler, CustomPass, PassBuilder,
+};
+use testing::Tester;
+
+/// Records `name` when it runs.
+struct Log(Rc<RefCell<Vec<&'static str>>>, &'static str);
+
+impl Fold<Program> for Log {
+    fn fold(&mut self, node: Program) -> Program {
+        self.0.borrow_mut().push(self.1);
+
+        node
+    }
+}
+
+/// Counts arrow functions.
+struct CountArrows(Rc<Cell<usize>>);
+
+impl Fold<ArrowExpr> for CountArrows {
+    fn fold(&mut self, node: ArrowExpr) -> ArrowExpr {
+        self.0.set(self.0.get() + 1);
+
+        node.fold_children(self)
+    }
+}
+
+/// Renames `foo` to `bar`.
+struct RenameFoo;
+
+impl Fold<Ident> for RenameFoo {
+    fn fold(&mut self, i: Ident) -> Ident {
+        if &*i.sym == "foo" {
+            return Ident {
+                sym: "bar".into(),
+                ..i
+            };
+        }
+
+        i
+    }
+}
+
+#[test]
+fn before_and_after_pass() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm.new_source_file(FileName::Anon, "const f = () => 1;".into());
+            let (program, _) = c
+                .parse_js(
+                    fm,
+                    JscTarget::Es5,
+                    Default::default(),
+                    true,
+                    false,
+                    &InputSourceMap::Bool(false),
+                )
+                .expect("failed to parse");
+
+            let before = Rc::new(Cell::new(0));
+            let after = Rc::new(Cell::new(0));
+
+            c.run(|| {
+                let root_mark = Mark::fresh(Mark::root());
+                let mut pass = PassBuilder::new(&cm, &c.handler, false, root_mark, noop())
+                    .before_pass("compat", CountArrows(before.clone()))
+                    .unwrap()
+                    .after_pass("compat", CountArrows(after.clone()))
+                    .unwrap()
+                    .finalize(root_mark, Default::default(), None);
+
+                helpers::HELPERS.set(&Helpers::new(false), || program.fold_with(&mut pass));
+            });
+
+            assert_eq!(before.get(), 1);
+            assert_eq!(after.get(), 0);
+
+            Ok(())
+        })
+        .unwrap();
+}
+
+#[test]
+fn then_phase() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm.new_source_file(FileName::Anon, "foo();".into());
+            let (program, _) = c
+                .parse_js(
+                    fm,
+                    JscTarget::Es5,
+                    Default::default(),
+                    true,
+                    false,
+                    &InputSourceMap::Bool(false),
+                )
+                .expect("failed to parse");
+
+            let log = Rc::new(RefCell::new(vec![]));
+
+            c.run(|| {
+                let root_mark = Mark::fresh(Mark::root());
+                let mut pass = PassBuilder::new(&cm, &c.handler, false, root_mark, noop())
+                    .then_phase("resolver", Log(log.clone(), "resolver"))
+                    .unwrap()
+                    .then_phase("optimizer", Log(log.clone(), "optimizer"))
+                    .unwrap()
+                    // Passes can be registered after the phase is added.
+                    .after_pass("resolver", Log(log.clone(), "custom"))
+                    .unwrap()
+                    .finalize(root_mark, Default::default(), None);
+
+                helpers::HELPERS.set(&Helpers::new(false), || program.fold_with(&mut pass));
+            });
+
+            assert_eq!(*log.borrow(), vec!["resolver", "custom", "optimizer"]);
+
+            Ok(())
+        })
+        .unwrap();
+}
+
+#[test]
+fn unknown_pass() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let err = PassBuilder::new(&cm, &c.handler, false, Mark::root(), noop())
+                .after_pass("inlining", noop())
+                .err()
+                .expect("`inlining` should not be a known pass");
+            assert!(err.to_string().contains("unknown pass `inlining`"));
+
+            Ok(())
+        })
+        .unwrap();
+}
+
+#[test]
+fn unknown_phase() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let err = PassBuilder::new(&cm, &c.handler, false, Mark::root(), noop())
+                .then_phase("inlining", noop())
+                .err()
+                .expect("`inlining` should not be a known phase");
+            assert!(err.to_string().contains("unknown pass `inlining`"));
+
+            Ok(())
+        })
+        .unwrap();
+}
+
+#[test]
+#[should_panic(expected = "custom passes are registered for `optimizer`")]
+fn custom_pass_of_missing_phase() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let root_mark = Mark::fresh(Mark::root());
+            PassBuilder::new(&cm, &c.handler, false, root_mark, noop())
+                .after_pass("optimizer", noop())
+                .unwrap()
+                .finalize(root_mark, Default::default(), None);
+
+            Ok(())
+        })
+        .unwrap();
+}
+
+#[test]
+fn custom_passes_of_options() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm.new_source_file(FileName::Anon, "foo();".into());
+            let output = c
+                .process_js_file(
+                    fm,
+                    &Options {
+                        custom_passes: Some(Arc::new(|| {
+                            vec![CustomPass::after("resolver", RenameFoo).unwrap()]
+                        })),
+                        ..Default::default()
+                    },
+                )
+                .expect("failed to process js file");
+
+            assert_eq!(output.code, "bar();\n");
+
+            Ok(())
+        })
+        .unwrap();
+}
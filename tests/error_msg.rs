@@ -1,21 +1,28 @@
 use std::path::Path;
-use swc::{config::Options, Compiler};
+use swc::{
+    config::{ConfigFile, Options, SwcrcError},
+    Compiler,
+};
 use testing::{NormalizedOutput, Tester};
 
 fn file(f: &str) -> NormalizedOutput {
+    file_with_opt(
+        f,
+        Options {
+            swcrc: true,
+            is_module: true,
+            ..Default::default()
+        },
+    )
+}
+
+fn file_with_opt(f: &str, opts: Options) -> NormalizedOutput {
     Tester::new()
         .print_errors(|cm, handler| -> Result<NormalizedOutput, _> {
             let c = Compiler::new(cm.clone(), handler);
 
             let fm = cm.load_file(Path::new(f)).expect("failed to load file");
-            let s = c.process_js_file(
-                fm,
-                &Options {
-                    swcrc: true,
-                    is_module: true,
-                    ..Default::default()
-                },
-            );
+            let s = c.process_js_file(fm, &opts);
             if let Err(e) = s {
                 return Ok(format!("{:?}", e).into());
             }
@@ -29,4 +36,177 @@ fn file(f: &str) -> NormalizedOutput {
 fn swcrc_simple() {
     let f = file("tests/swcrc_errors/simple/foo.js");
     println!("{}", f);
+
+    assert!(f.contains("unknown key `js`"));
+}
+
+#[test]
+fn swcrc_unknown_key_in_array() {
+    let f = file("tests/swcrc_errors/multi/foo.js");
+    println!("{}", f);
+
+    assert!(f.contains("unknown key `jsx`"));
+}
+
+#[test]
+fn swcrc_path_in_error() {
+    let f = file("tests/swcrc_errors/syntax/foo.js");
+    println!("{}", f);
+
+    assert!(f.contains("tests/swcrc_errors/syntax/.swcrc"));
+}
+
+#[test]
+fn swcrc_json5_syntax() {
+    let f = file("tests/swcrc_errors/json5/foo.js");
+    println!("{}", f);
+
+    assert!(f.contains("failed to deserialize .swcrc (json5) file"));
+    assert!(f.contains(": 4:"));
+}
+
+#[test]
+fn swcrc_merged_config_file_in_error() {
+    let f = file_with_opt(
+        "tests/swcrc_errors/merge/foo.js",
+        Options {
+            swcrc: true,
+            is_module: true,
+            config_file: Some(ConfigFile::Str("tests/swcrc_errors/merge/ts.swcrc".into())),
+            ..Default::default()
+        },
+    );
+    println!("{}", f);
+
+    assert!(f.contains("tests/swcrc_errors/merge/ts.swcrc"));
+    assert!(f.contains("tests/swcrc_errors/merge/.swcrc"));
+}
+
+fn swcrc_error(f: &str, opts: Options, check: impl FnOnce(&SwcrcError)) {
+    Tester::new()
+        .print_errors(|cm, handler| -> Result<(), _> {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm.load_file(Path::new(f)).expect("failed to load file");
+            let err = c
+                .process_js_file(fm, &opts)
+                .expect_err("invalid swcrc should abort build");
+            println!("{:?}", err);
+
+            check(
+                err.chain()
+                    .find_map(|e| e.downcast_ref::<SwcrcError>())
+                    .expect("SwcrcError should be in the error chain"),
+            );
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn swcrc_error_not_found() {
+    swcrc_error(
+        "tests/swcrc_errors/simple/foo.js",
+        Options {
+            swcrc: false,
+            config_file: Some(ConfigFile::Str(
+                "tests/swcrc_errors/simple/missing.swcrc".into(),
+            )),
+            ..Default::default()
+        },
+        |err| match err {
+            SwcrcError::NotFound { path } => {
+                assert_eq!(path, Path::new("tests/swcrc_errors/simple/missing.swcrc"))
+            }
+            _ => panic!("expected NotFound, got {:?}", err),
+        },
+    );
+}
+
+#[test]
+fn swcrc_error_read() {
+    swcrc_error(
+        "tests/swcrc_errors/read/foo.js",
+        Options {
+            swcrc: true,
+            ..Default::default()
+        },
+        |err| match err {
+            SwcrcError::Read { path, .. } => {
+                assert_eq!(path, Path::new("tests/swcrc_errors/read/.swcrc"))
+            }
+            _ => panic!("expected Read, got {:?}", err),
+        },
+    );
+}
+
+#[test]
+fn swcrc_error_parse() {
+    swcrc_error(
+        "tests/swcrc_errors/syntax/foo.js",
+        Options {
+            swcrc: true,
+            ..Default::default()
+        },
+        |err| match err {
+            SwcrcError::Parse { path, .. } => {
+                assert_eq!(path, Path::new("tests/swcrc_errors/syntax/.swcrc"))
+            }
+            _ => panic!("expected Parse, got {:?}", err),
+        },
+    );
+}
+
+fn validate(inline: &str) -> Result<(), String> {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm, handler);
+
+            Ok(c.validate_options(&Options {
+                swcrc: false,
+                config_file: Some(ConfigFile::Inline {
+                    inline: inline.into(),
+                }),
+                ..Default::default()
+            })
+            .map_err(|err| format!("{:?}", err)))
+        })
+        .unwrap()
+}
+
+#[test]
+fn validate_options_valid() {
+    validate(
+        r#"{
+            "jsc": {
+                "parser": { "syntax": "typescript", "decorators": true },
+                "transform": { "decoratorMetadata": true },
+                "target": "es2018"
+            }
+        }"#,
+    )
+    .expect("options should be valid");
+}
+
+#[test]
+fn validate_options_decorators_without_syntax() {
+    let err = validate(
+        r#"[
+            { "jsc": { "parser": { "syntax": "ecmascript", "decorators": true } } },
+            {
+                "jsc": {
+                    "parser": { "syntax": "ecmascript" },
+                    "transform": { "legacyDecorator": true, "decoratorMetadata": true },
+                    "target": "es5"
+                }
+            }
+        ]"#,
+    )
+    .expect_err("options should be invalid");
+    println!("{}", err);
+
+    assert!(err.contains("config 1: `jsc.transform.legacyDecorator` requires"));
+    assert!(err.contains("config 1: `jsc.transform.decoratorMetadata` requires"));
+    assert!(!err.contains("config 0"));
 }
@@ -1,8 +1,34 @@
+#![feature(box_syntax)]
+#![feature(box_patterns)]
+
 use rayon::prelude::*;
-use std::path::Path;
+use std::{
+    cell::Cell,
+    collections::HashMap,
+    path::{Path, PathBuf},
+    rc::Rc,
+    sync::Arc,
+};
 use swc::{
-    config::{Config, Options, SourceMapsConfig},
-    Compiler,
+    common::{
+        comments::{Comment, CommentKind, Comments},
+        chain, BytePos, FileName, FoldWith, Span, Spanned, DUMMY_SP,
+    },
+    config::{
+        CommentsConfig, Config, ConfigFile, InlineOption, InputSourceMap, JscConfig, JscTarget,
+        OptimizerConfig, Options, SourceMapsConfig, TransformConfig,
+    },
+    ecmascript::{
+        ast::*,
+        codegen::{self, Handlers},
+        parser::{
+            lexer::CommentCollection,
+            token::{BinOpToken, Keyword, Token, Word},
+            EsConfig, Syntax, TsConfig,
+        },
+        transforms::{optimization, resolver},
+    },
+    sourcemap, Compiler, ModuleKind, TextEdit, TransformOutput,
 };
 use swc_ecmascript::preset_env;
 use testing::{NormalizedOutput, StdErr, Tester};
@@ -254,6 +280,17 @@ fn issue_468() {
     file("tests/projects/issue-468/input.ts").expect("failed to parse typescript");
 }
 
+/// Members of a `const enum` should be inlined with the accessed name.
+#[test]
+fn const_enum() {
+    let f = file("tests/projects/const-enum/input.ts").unwrap();
+    println!("{}", f);
+
+    assert!(!f.contains("Direction"));
+    assert!(f.contains("2 /* Direction.Down */"));
+    assert!(f.contains("'red' /* Color.Red */"));
+}
+
 #[test]
 fn issue_528() {
     let f = file("tests/projects/issue-528/input.js")
@@ -494,3 +531,1806 @@ fn issue_783_core_js_3() {
         "import of `core-js` should be transformed"
     );
 }
+
+fn check_syntax(src: &str) -> Vec<String> {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm.new_source_file(FileName::Custom("input.js".into()), src.into());
+            let diagnostics = c
+                .check_syntax_only(
+                    fm,
+                    &Options {
+                        is_module: true,
+                        ..Default::default()
+                    },
+                )
+                .expect("failed to check syntax");
+
+            // Parser diagnostics should not leak into the compiler's handler
+            assert!(!c.handler.has_errors());
+
+            Ok(diagnostics.iter().map(|d| d.message()).collect())
+        })
+        .unwrap()
+}
+
+#[test]
+fn check_syntax_only_valid() {
+    assert_eq!(
+        check_syntax("import foo from 'foo'; export const a = foo();"),
+        Vec::<String>::new()
+    );
+}
+
+#[test]
+fn check_syntax_only_invalid() {
+    let diagnostics = check_syntax("const a = ;");
+    assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+}
+
+#[test]
+fn config_file_inline() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm, handler);
+
+            let config = c
+                .config_for_file(
+                    &Options {
+                        swcrc: false,
+                        config_file: Some(ConfigFile::Inline {
+                            inline: r#"{
+                                "jsc": {
+                                    "parser": { "syntax": "typescript" },
+                                    "target": "es2018"
+                                }
+                            }"#
+                            .into(),
+                        }),
+                        ..Default::default()
+                    },
+                    &FileName::Custom("input.ts".into()),
+                )
+                .expect("failed to build config");
+
+            assert!(config.syntax.typescript());
+            assert_eq!(config.target, JscTarget::Es2018);
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn drop_debugger() {
+    let f = file("tests/projects/drop-debugger/input.js").unwrap();
+    println!("{}", f);
+
+    assert!(!f.contains("debugger"));
+    assert!(f.contains("console.log(a)"));
+    assert!(f.contains("return a"));
+}
+
+#[test]
+fn swc_disable() {
+    let f = file("tests/projects/swc-disable/disabled/input.js").unwrap();
+    println!("{}", f);
+
+    assert!(f.contains("@swc-disable"));
+    assert!(f.contains("=>"));
+    assert!(f.contains('`'));
+}
+
+#[test]
+fn swc_disable_not_first_comment() {
+    let f = file("tests/projects/swc-disable/enabled/input.js").unwrap();
+    println!("{}", f);
+
+    assert!(!f.contains("=>"));
+    assert!(!f.contains('`'));
+}
+
+#[test]
+fn module_type_es6() {
+    let f = file("tests/projects/module-type/es6/input.js").unwrap();
+    println!("{}", f);
+
+    assert!(f.contains("import foo from './foo'"));
+    assert!(f.contains("export var bar"));
+    assert!(!f.contains("require("));
+}
+
+#[test]
+fn module_type_commonjs() {
+    let f = file("tests/projects/module-type/commonjs/input.js").unwrap();
+    println!("{}", f);
+
+    assert!(f.contains("require('./foo')"));
+    assert!(f.contains("exports.bar = bar"));
+    assert!(!f.contains("import "));
+    assert!(!f.contains("export "));
+}
+
+#[test]
+fn preserve_parens() {
+    let f = file("tests/projects/preserve-parens/input.js").unwrap();
+    println!("{}", f);
+
+    assert!(f.contains("var y = (a + b);"));
+    assert!(f.contains("foo((a));"));
+    assert!(f.contains("var z = (a * ((b + c)));"));
+}
+
+#[test]
+fn preserve_parens_disabled() {
+    let f = file_with_opt(
+        "tests/projects/preserve-parens/input.js",
+        Options {
+            swcrc: false,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    println!("{}", f);
+
+    assert!(f.contains("var y = a + b;"));
+    assert!(f.contains("foo(a);"));
+    assert!(f.contains("var z = a * (b + c);"));
+}
+
+#[test]
+fn loose() {
+    fn transform(loose: bool) -> NormalizedOutput {
+        file_with_opt(
+            "tests/projects/loose/input.js",
+            Options {
+                swcrc: false,
+                config: Some(Config {
+                    jsc: JscConfig {
+                        loose,
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    }
+
+    let strict = transform(false);
+    println!("{}", strict);
+    assert!(strict.contains("_toConsumableArray(b)"));
+
+    let loose = transform(true);
+    println!("{}", loose);
+    assert!(!loose.contains("_toConsumableArray"));
+    assert!(loose.contains("concat(b)"));
+}
+
+#[test]
+fn decorator_metadata() {
+    fn transform(decorator_metadata: bool) -> NormalizedOutput {
+        file_with_opt(
+            "tests/projects/decorator-metadata/input.ts",
+            Options {
+                swcrc: false,
+                config: Some(Config {
+                    jsc: JscConfig {
+                        syntax: Some(Syntax::Typescript(TsConfig {
+                            decorators: true,
+                            ..Default::default()
+                        })),
+                        transform: Some(TransformConfig {
+                            legacy_decorator: true,
+                            decorator_metadata,
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    }
+
+    let without = transform(false);
+    println!("{}", without);
+    assert!(without.contains("dec"));
+    assert!(!without.contains("Reflect.metadata"));
+
+    let with = transform(true);
+    println!("{}", with);
+    assert!(with.contains("Reflect.metadata('design:type', Function)"));
+    assert!(with.contains("Reflect.metadata('design:paramtypes', ["));
+    assert!(with.contains("String,"));
+    assert!(with.contains("typeof Bar === 'undefined' ? Object : Bar"));
+    assert!(with.contains("Reflect.metadata('design:returntype', Number)"));
+    // Members of a class nested in an undecorated method.
+    assert_eq!(
+        with.matches("Reflect.metadata('design:type', Function)")
+            .count(),
+        2
+    );
+    assert!(with.contains("Boolean"));
+}
+
+#[test]
+fn decorator_modes() {
+    fn transform(legacy_decorator: bool) -> NormalizedOutput {
+        file_with_opt(
+            "tests/projects/decorator-modes/input.js",
+            Options {
+                swcrc: false,
+                config: Some(Config {
+                    jsc: JscConfig {
+                        syntax: Some(Syntax::Es(EsConfig {
+                            decorators: true,
+                            ..Default::default()
+                        })),
+                        transform: Some(TransformConfig {
+                            legacy_decorator,
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+        )
+        .unwrap()
+    }
+
+    let legacy = transform(true);
+    println!("{}", legacy);
+    let proposal = transform(false);
+    println!("{}", proposal);
+
+    assert_ne!(legacy, proposal);
+    assert!(legacy.contains("_applyDecoratedDescriptor("));
+    assert!(legacy.contains("'method'"));
+    assert!(!legacy.contains("_decorate("));
+
+    assert!(proposal.contains("_decorate("));
+    assert!(proposal.contains("key: 'method'"));
+    assert!(!proposal.contains("_applyDecoratedDescriptor("));
+}
+
+#[test]
+fn config_for_file_with_resolver() {
+    fn config(target: JscTarget) -> Config {
+        Config {
+            jsc: JscConfig {
+                target,
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm, handler);
+
+            let mut swcrc = HashMap::new();
+            swcrc.insert(PathBuf::from("/virtual/.swcrc"), config(JscTarget::Es2017));
+            swcrc.insert(
+                PathBuf::from("/virtual/es2018/.swcrc"),
+                config(JscTarget::Es2018),
+            );
+
+            let target_of = |path: &str| {
+                c.config_for_file_with_resolver(
+                    &Options {
+                        root: Some("/virtual".into()),
+                        swcrc: true,
+                        ..Default::default()
+                    },
+                    &FileName::Real(path.into()),
+                    &swcrc,
+                )
+                .expect("failed to build config")
+                .target
+            };
+
+            assert_eq!(target_of("/virtual/src/a.js"), JscTarget::Es2017);
+            assert_eq!(target_of("/virtual/es2018/src/a.js"), JscTarget::Es2018);
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn drop_console_all() {
+    let f = file("tests/projects/drop-console/all/input.js").unwrap();
+    println!("{}", f);
+
+    assert!(!f.contains("console"));
+    assert!(f.contains("return a"));
+}
+
+#[test]
+fn drop_console_selected() {
+    let f = file("tests/projects/drop-console/selected/input.js").unwrap();
+    println!("{}", f);
+
+    assert!(!f.contains("console.log"));
+    assert!(!f.contains("console.debug"));
+    assert!(f.contains("console.error(a)"));
+}
+
+#[test]
+fn used_helpers() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm.new_source_file(
+                FileName::Custom("input.js".into()),
+                "class Foo {}
+foo(...bar);"
+                    .into(),
+            );
+            let output = c
+                .process_js_file(fm, &Default::default())
+                .expect("failed to process js file");
+
+            assert_eq!(
+                output.used_helpers,
+                vec![
+                    "array_without_holes",
+                    "class_call_check",
+                    "iterable_to_array",
+                    "non_iterable_spread",
+                    "to_consumable_array",
+                ]
+            );
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn esnext() {
+    let f = file("tests/projects/esnext/input.js").unwrap();
+    println!("{}", f);
+
+    assert!(f.contains("bar = 1;"));
+    assert!(f.contains("static baz = 2;"));
+    assert!(f.contains("await load()"));
+    assert!(f.contains("data?.value ?? 0"));
+    assert!(f.contains("async (...args)=>"));
+}
+
+/// should preserve directives while minifying
+#[test]
+fn directives() {
+    let s = file("tests/projects/directives/input.js").unwrap();
+    println!("{}", s);
+
+    assert!(s.starts_with("'use client';"));
+    assert!(s.contains("{'use strict';return 1"));
+}
+
+/// should keep exported bindings which are not used in the module while
+/// minifying
+#[test]
+fn keep_exports() {
+    let s = file("tests/projects/keep-exports/input.js").unwrap();
+    println!("{}", s);
+
+    assert!(s.contains("internal='internal'"));
+    assert!(s.contains("VERSION='1.0.0'"));
+    assert!(s.contains("state=init()"));
+}
+
+/// should not inline a variable into the body of a `with` statement
+#[test]
+fn with_stmt() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm
+                .load_file(Path::new("tests/projects/with-stmt/input.js"))
+                .expect("failed to load file");
+            let output = c
+                .process_js_file(
+                    fm,
+                    &Options {
+                        swcrc: true,
+                        is_module: false,
+                        ..Default::default()
+                    },
+                )
+                .expect("failed to process js file");
+            println!("{}", output.code);
+
+            assert!(output.code.contains("console.log(x);"));
+            assert!(!output.code.contains("console.log(1"));
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+/// should keep comments of removed code with jsc.preserveAllComments
+#[test]
+fn preserve_all_comments() {
+    let s = file("tests/projects/preserve-all-comments/input.js").unwrap();
+    println!("{}", s);
+
+    // Relocated comments should stay in the order of the source.
+    let mut last = 0;
+    for text in &[
+        "// header",
+        "/* the answer */",
+        "/* before dead code */",
+        "// inside dead code",
+        "Returns the answer.",
+        "// body",
+        "// trailing",
+    ] {
+        let pos = s
+            .find(text)
+            .unwrap_or_else(|| panic!("{} should be preserved", text));
+        assert!(
+            pos >= last,
+            "{} should not be moved before other comments",
+            text
+        );
+        last = pos;
+    }
+    assert!(!s.contains("'dead'"));
+}
+
+fn comments_policy(comments: CommentsConfig) -> String {
+    file_with_opt(
+        "tests/projects/comments-policy/input.js",
+        Options {
+            swcrc: false,
+            config: Some(Config {
+                jsc: JscConfig {
+                    comments: Some(comments),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }),
+            ..Default::default()
+        },
+    )
+    .unwrap()
+    .to_string()
+}
+
+#[test]
+fn comments_none() {
+    let s = comments_policy(CommentsConfig::None);
+    println!("{}", s);
+
+    assert!(!s.contains("license"));
+    assert!(!s.contains("// leading"));
+    assert!(!s.contains("// trailing"));
+    assert!(s.contains("const a = 1;"));
+}
+
+#[test]
+fn comments_leading() {
+    let s = comments_policy(CommentsConfig::Leading);
+    println!("{}", s);
+
+    assert!(s.contains("/*! license */"));
+    assert!(s.contains("// leading"));
+    assert!(!s.contains("// trailing"));
+}
+
+#[test]
+fn resolve_import() {
+    let s = file_with_opt(
+        "tests/projects/resolve-import/input.js",
+        Options {
+            swcrc: true,
+            resolve_import: Some(Arc::new(|src: &str, importer: &FileName| {
+                let dir = match importer {
+                    FileName::Real(path) => path.parent().unwrap().to_path_buf(),
+                    _ => return None,
+                };
+                if !src.starts_with("./") {
+                    return None;
+                }
+
+                Some(format!("/abs/{}/{}.js", dir.display(), &src[2..]))
+            })),
+            ..Default::default()
+        },
+    )
+    .unwrap();
+    println!("{}", s);
+
+    assert!(s.contains("from '/abs/tests/projects/resolve-import/a.js'"));
+    assert!(s.contains("from '/abs/tests/projects/resolve-import/b.js'"));
+    assert!(s.contains("from 'c'"));
+    assert!(s.contains("import('/abs/tests/projects/resolve-import/d.js')"));
+}
+
+/// should not inline a variable annotated with @__NOINLINE__
+#[test]
+fn noinline_annotation() {
+    let s = file("tests/projects/noinline/input.js").unwrap();
+    println!("{}", s);
+
+    assert!(s.contains("use(a, 2)"));
+}
+
+#[test]
+fn inline_deny() {
+    let s = file("tests/projects/inline-deny/input.js").unwrap();
+    println!("{}", s);
+
+    assert!(s.contains("use(a, 2)"));
+}
+
+#[test]
+fn compress_no_inline() {
+    let s = file("tests/projects/compress-no-inline/input.js").unwrap();
+    println!("{}", s);
+
+    assert!(s.contains("use(a, b, 3)"));
+}
+
+#[test]
+fn globals_member() {
+    let s = file("tests/projects/globals-member/input.js").unwrap();
+    println!("{}", s);
+
+    assert!(!s.contains("development"));
+    assert!(s.contains("always"));
+}
+
+#[test]
+fn globals_typeof() {
+    let s = file("tests/projects/globals-typeof/input.js").unwrap();
+    println!("{}", s);
+
+    assert!(s.contains("browser"));
+    assert!(!s.contains("server"));
+}
+
+#[test]
+fn globals_typeof_node() {
+    let s = file("tests/projects/globals-typeof-node/input.js").unwrap();
+    println!("{}", s);
+
+    assert!(s.contains("browser"));
+    assert!(s.contains("server"));
+}
+
+#[test]
+fn class_fields_define() {
+    let s = file("tests/projects/class-fields-define/input.js").unwrap();
+    println!("{}", s);
+
+    assert!(s.contains("_defineProperty(this, 'foo', 1)"));
+    assert!(s.contains("_defineProperty(Foo, 'qux', 3)"));
+}
+
+#[test]
+fn class_fields_set() {
+    let s = file("tests/projects/class-fields-set/input.js").unwrap();
+    println!("{}", s);
+
+    assert!(!s.contains("_defineProperty"));
+    assert!(s.contains("this.foo = 1"));
+    assert!(s.contains("this['bar-baz'] = 2"));
+    assert!(s.contains("Foo.qux = 3"));
+}
+
+#[test]
+fn external_helpers_cjs() {
+    let s = file("tests/projects/external-helpers-cjs/input.js").unwrap();
+    println!("{}", s);
+
+    assert!(s.contains("var swcHelpers = require('@swc/helpers')"));
+    assert!(!s.contains("import"));
+    assert!(!s.contains("_interopRequireWildcard"));
+    assert!(s.contains("swcHelpers.classCallCheck(this, Foo)"));
+}
+
+#[test]
+fn external_helpers_esm() {
+    let s = file("tests/projects/external-helpers-esm/input.js").unwrap();
+    println!("{}", s);
+
+    assert!(s.contains("import * as swcHelpers from '@swc/helpers'"));
+    assert!(!s.contains("require"));
+    assert!(s.contains("swcHelpers.classCallCheck(this, Foo)"));
+}
+
+#[test]
+fn skip_helpers_injection() {
+    let s = file("tests/projects/skip-helpers-injection/input.js").unwrap();
+    println!("{}", s);
+
+    assert!(s.contains("_classCallCheck(this, Foo)"));
+    assert!(!s.contains("function _classCallCheck"));
+    assert!(!s.contains("@swc/helpers"));
+}
+
+#[test]
+fn global_rename() {
+    let s = file("tests/projects/global-rename/input.js").unwrap();
+    println!("{}", s);
+
+    assert!(s.contains("SomePolyfill.resolve(1)"));
+    assert!(s.contains("return Promise"));
+}
+
+#[test]
+fn import_meta() {
+    let s = file("tests/projects/import-meta/input.js").unwrap();
+    println!("{}", s);
+
+    assert!(s.contains("export const url = document.currentScript.src;"));
+    assert!(s.contains("export const meta = import.meta;"));
+}
+
+#[test]
+fn inline_allow() {
+    let s = file("tests/projects/inline-allow/input.js").unwrap();
+    println!("{}", s);
+
+    assert!(s.contains("use(1)"));
+}
+
+/// should allow comments and trailing commas in .swcrc
+#[test]
+fn swcrc_json5() {
+    let s = file("tests/projects/swcrc-json5/input.js").unwrap();
+    println!("{}", s);
+
+    assert!(s.contains("function(a)"));
+}
+
+#[test]
+fn source_mapping_url() {
+    let code = Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm
+                .load_file(Path::new("tests/projects/loose/input.js"))
+                .expect("failed to load file");
+            Ok(c.process_js_file(
+                fm,
+                &Options {
+                    swcrc: false,
+                    source_maps: Some(SourceMapsConfig::Bool(true)),
+                    output_path: Some("dist/js/bundle.js".into()),
+                    source_map_path: Some("dist/maps/bundle.js.map".into()),
+                    ..Default::default()
+                },
+            )
+            .expect("failed to process js file")
+            .code)
+        })
+        .unwrap();
+    println!("{}", code);
+
+    assert!(code.ends_with("\n//# sourceMappingURL=../maps/bundle.js.map"));
+}
+
+#[test]
+fn source_map_object() {
+    fn transform(source_map_object: bool) -> TransformOutput {
+        Tester::new()
+            .print_errors(|cm, handler| {
+                let c = Compiler::new(cm.clone(), handler);
+
+                let fm = cm
+                    .load_file(Path::new("tests/projects/loose/input.js"))
+                    .expect("failed to load file");
+                Ok(c.process_js_file(
+                    fm,
+                    &Options {
+                        swcrc: false,
+                        source_maps: Some(SourceMapsConfig::Bool(true)),
+                        source_map_object,
+                        ..Default::default()
+                    },
+                )
+                .expect("failed to process js file"))
+            })
+            .unwrap()
+    }
+
+    fn tokens(map: &sourcemap::SourceMap) -> Vec<(u32, u32, u32, u32)> {
+        map.tokens()
+            .map(|t| {
+                (
+                    t.get_dst_line(),
+                    t.get_dst_col(),
+                    t.get_src_line(),
+                    t.get_src_col(),
+                )
+            })
+            .collect()
+    }
+
+    let string = transform(false);
+    assert!(string.map_object.is_none());
+    let string_map = sourcemap::SourceMap::from_slice(string.map.unwrap().as_bytes()).unwrap();
+
+    let object = transform(true);
+    assert!(object.map.is_none());
+    let value = object.map_object.clone().unwrap();
+    assert!(value.is_object());
+    let serialized = serde_json::to_value(&object).unwrap();
+    assert_eq!(serialized["map"], value);
+
+    let object_map = sourcemap::SourceMap::from_slice(value.to_string().as_bytes()).unwrap();
+    assert_eq!(tokens(&string_map), tokens(&object_map));
+    assert!(!tokens(&object_map).is_empty());
+}
+
+#[test]
+fn profile() {
+    fn pass_names(profile: bool) -> Vec<String> {
+        Tester::new()
+            .print_errors(|cm, handler| {
+                let c = Compiler::new(cm.clone(), handler);
+
+                let fm = cm
+                    .load_file(Path::new("tests/projects/drop-debugger/input.js"))
+                    .expect("failed to load file");
+                let output = c
+                    .process_js_file(
+                        fm,
+                        &Options {
+                            swcrc: true,
+                            is_module: true,
+                            profile,
+                            ..Default::default()
+                        },
+                    )
+                    .expect("failed to process js file");
+
+                Ok(output
+                    .pass_timings
+                    .into_iter()
+                    .map(|timing| timing.name)
+                    .collect())
+            })
+            .unwrap()
+    }
+
+    let names = pass_names(true);
+    for pass in &[
+        "resolver", "inlining", "compat", "module", "hygiene", "fixer",
+    ] {
+        assert!(names.iter().any(|name| name == pass), "{:?}", names);
+    }
+    assert_eq!(pass_names(false), Vec::<String>::new());
+}
+
+#[test]
+fn emit_pretty_alongside() {
+    let output = Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm
+                .load_file(Path::new("tests/projects/pretty-alongside/input.js"))
+                .expect("failed to load file");
+            Ok(c.process_js_file(
+                fm,
+                &Options {
+                    swcrc: false,
+                    config: Some(Config {
+                        minify: Some(true),
+                        ..Default::default()
+                    }),
+                    emit_pretty_alongside: true,
+                    ..Default::default()
+                },
+            )
+            .expect("failed to process js file"))
+        })
+        .unwrap();
+
+    println!("{}\n{:?}", output.code, output.pretty);
+    let pretty = output.pretty.expect("pretty output should be emitted");
+    assert!(output.code.contains("return a+b"));
+    assert!(pretty.contains("\n"));
+    assert!(pretty.contains("return a + b;"));
+}
+
+#[test]
+fn process_expr() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm, handler);
+
+            let a = Ident::new("a".into(), DUMMY_SP);
+            let arrow = Expr::Arrow(ArrowExpr {
+                span: DUMMY_SP,
+                params: vec![Pat::Ident(a.clone())],
+                body: BlockStmtOrExpr::Expr(box Expr::Ident(a)),
+                is_async: false,
+                is_generator: false,
+                type_params: None,
+                return_type: None,
+            });
+
+            let output = c
+                .process_expr(arrow, &Default::default())
+                .expect("failed to process expression");
+            println!("{}", output.code);
+
+            match output.node {
+                Expr::Fn(..) => {}
+                _ => panic!(
+                    "arrow should be transformed to a function: {:?}",
+                    output.node
+                ),
+            }
+            assert!(output.code.contains("function(a)"));
+            assert!(output.code.contains("return a"));
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn process_with_config() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let opts = Options {
+                swcrc: false,
+                ..Default::default()
+            };
+            let config = Config {
+                jsc: JscConfig {
+                    target: JscTarget::Es5,
+                    ..Default::default()
+                },
+                ..Default::default()
+            };
+
+            for src in &["export const f = (a) => a;", "export const g = (b) => b;"] {
+                let fm = cm.new_source_file(FileName::Anon, src.to_string());
+                let (program, _) = c
+                    .parse_js(
+                        fm,
+                        JscTarget::Es5,
+                        Default::default(),
+                        true,
+                        false,
+                        &InputSourceMap::Bool(false),
+                    )
+                    .expect("failed to parse");
+
+                let built = c.run(|| opts.build(&c.cm, &c.handler, true, Some(config.clone())));
+                let output = c
+                    .process_with_config(program, None, built)
+                    .expect("failed to process js");
+                println!("{}", output.code);
+
+                assert!(output.code.contains("function("));
+                assert!(!output.code.contains("=>"));
+            }
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn comment_collection() {
+    fn collected(collection: CommentCollection) -> (bool, bool) {
+        Tester::new()
+            .print_errors(|cm, handler| {
+                let c = Compiler::new(cm.clone(), handler);
+
+                let fm = cm.new_source_file(FileName::Anon, "// leading\nfoo(); // trailing".into());
+                let start = fm.start_pos;
+                c.parse_js(
+                    fm,
+                    JscTarget::Es5,
+                    Default::default(),
+                    true,
+                    collection,
+                    &InputSourceMap::Bool(false),
+                )
+                .expect("failed to parse");
+
+                Ok((
+                    c.comments().leading_comments(start + BytePos(11)).is_some(),
+                    c.comments().trailing_comments(start + BytePos(17)).is_some(),
+                ))
+            })
+            .unwrap()
+    }
+
+    assert_eq!(collected(CommentCollection::None), (false, false));
+    assert_eq!(collected(CommentCollection::Leading), (true, false));
+    assert_eq!(collected(CommentCollection::Trailing), (false, true));
+    assert_eq!(collected(CommentCollection::All), (true, true));
+    assert_eq!(collected(true.into()), (true, true));
+}
+
+#[test]
+fn detect_module_kind() {
+    fn detect(src: &str) -> ModuleKind {
+        Tester::new()
+            .print_errors(|cm, handler| {
+                let c = Compiler::new(cm.clone(), handler);
+
+                let fm = cm.new_source_file(FileName::Anon, src.into());
+                Ok(c.detect_module_kind(fm, Default::default())
+                    .expect("failed to detect module kind"))
+            })
+            .unwrap()
+    }
+
+    assert_eq!(detect("import foo from 'foo';"), ModuleKind::Module);
+    assert_eq!(detect("foo();\nexport const a = 1;"), ModuleKind::Module);
+    assert_eq!(detect("console.log(import.meta.url);"), ModuleKind::Module);
+    assert_eq!(detect("await foo();"), ModuleKind::Module);
+    assert_eq!(
+        detect("const f = async () => await foo();\nawait f();"),
+        ModuleKind::Module
+    );
+    assert_eq!(detect("run(async x => x, await y);"), ModuleKind::Module);
+
+    assert_eq!(detect("const foo = require('foo');"), ModuleKind::CommonJs);
+    assert_eq!(detect("module.exports = 1;"), ModuleKind::CommonJs);
+    assert_eq!(detect("exports.foo = 1;"), ModuleKind::CommonJs);
+
+    assert_eq!(detect("import('foo').then(run);"), ModuleKind::Unknown);
+    assert_eq!(detect("async function f() { await foo(); }"), ModuleKind::Unknown);
+    assert_eq!(detect("const f = async () => await foo();"), ModuleKind::Unknown);
+    assert_eq!(detect("run(async x => await x, 1);"), ModuleKind::Unknown);
+    assert_eq!(detect("foo.require('bar'); a.exports.b = 1;"), ModuleKind::Unknown);
+    assert_eq!(detect("var a = 1;"), ModuleKind::Unknown);
+}
+
+#[test]
+fn tokenize() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm.new_source_file(FileName::Anon, "let a: Foo<string> = b as any;".into());
+            let tokens = c
+                .tokenize(
+                    fm.clone(),
+                    Syntax::Typescript(Default::default()),
+                    JscTarget::Es2019,
+                )
+                .expect("failed to tokenize");
+
+            let ident = |s: &str| Token::Word(Word::Ident(s.into()));
+            assert_eq!(
+                tokens.iter().map(|t| t.token.clone()).collect::<Vec<_>>(),
+                vec![
+                    Token::Word(Word::Keyword(Keyword::Let)),
+                    ident("a"),
+                    Token::Colon,
+                    ident("Foo"),
+                    Token::BinOp(BinOpToken::Lt),
+                    ident("string"),
+                    Token::BinOp(BinOpToken::Gt),
+                    Token::AssignOp(AssignOp::Assign),
+                    ident("b"),
+                    ident("as"),
+                    ident("any"),
+                    Token::Semi,
+                ]
+            );
+            assert_eq!(tokens[0].span.lo, fm.start_pos);
+            assert_eq!(tokens[8].span.lo, fm.start_pos + BytePos(21));
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn output_comments() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm.new_source_file(
+                FileName::Anon,
+                "const a = 1;\n\n/** Adds one. */\nfunction addOne(x) {\n    return x + 1;\n}\n"
+                    .into(),
+            );
+            let output = c
+                .process_js_file(
+                    fm,
+                    &Options {
+                        output_comments: true,
+                        ..Default::default()
+                    },
+                )
+                .expect("failed to process js file");
+            println!("{}", output.code);
+
+            assert_eq!(output.comments.len(), 1);
+            let comment = &output.comments[0];
+            assert_eq!(comment.kind, "block");
+            assert_eq!(comment.text, "* Adds one. ");
+
+            let lines = output.code.lines().collect::<Vec<_>>();
+            assert!(lines[comment.line as usize][comment.col as usize..].starts_with("/**"));
+            assert!(lines[comment.line as usize + 1].starts_with("function addOne"));
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn analyze_js_file() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+            let opts = Options {
+                swcrc: false,
+                config: Some(Config {
+                    minify: Some(true),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+
+            let fm = cm.new_source_file(
+                FileName::Anon,
+                "function add(a, b) {\n    return a + b;\n}\nadd(1, 2);\n".into(),
+            );
+            let output = c
+                .process_js_file(fm.clone(), &opts)
+                .expect("failed to process js file");
+            println!("{}", output.code);
+
+            let result = c
+                .analyze_js_file(fm, &opts)
+                .expect("failed to analyze js file");
+            assert!(result.changed);
+
+            let minified = cm.new_source_file(FileName::Anon, output.code);
+            let minified_result = c
+                .analyze_js_file(minified, &opts)
+                .expect("failed to analyze minified js file");
+            assert!(!minified_result.changed);
+            assert_eq!(minified_result.hash, result.hash);
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn inline_max_depth() {
+    let opts = |max_depth| Options {
+        swcrc: false,
+        config: Some(Config {
+            jsc: JscConfig {
+                transform: Some(TransformConfig {
+                    optimizer: Some(OptimizerConfig {
+                        inline: Some(InlineOption {
+                            max_depth,
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+
+    // The parser and other passes recurse as deep as the inlining pass, so a
+    // large stack is required to reach it.
+    std::thread::Builder::new()
+        .stack_size(512 * 1024 * 1024)
+        .spawn(move || {
+            Tester::new()
+                .print_errors(|cm, handler| {
+                    let c = Compiler::new(cm.clone(), handler);
+                    let src = format!(
+                        "var a = 1;\nfoo({}a{});",
+                        "foo(".repeat(2000),
+                        ")".repeat(2000)
+                    );
+
+                    let fm = cm.new_source_file(FileName::Anon, src.clone());
+                    let err = c
+                        .process_js_file(fm, &opts(None))
+                        .expect_err("deeply nested code should fail to compile");
+                    assert!(
+                        format!("{:?}", err).contains("nested too deeply"),
+                        "{:?}",
+                        err
+                    );
+
+                    let fm = cm.new_source_file(FileName::Anon, src);
+                    c.process_js_file(fm, &opts(Some(10000)))
+                        .expect("failed to process code within the limit");
+
+                    Ok(())
+                })
+                .unwrap();
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn process_js_with_comments() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm.new_source_file(FileName::Anon, "foo();\n// original\nbar();".into());
+            let (program, _) = c
+                .parse_js(
+                    fm,
+                    JscTarget::Es5,
+                    Default::default(),
+                    true,
+                    true,
+                    &InputSourceMap::Bool(false),
+                )
+                .expect("failed to parse js file");
+            let bar = match program {
+                Program::Module(ref m) => m.body[1].span(),
+                Program::Script(..) => unreachable!(),
+            };
+
+            let comments = Comments::default();
+            comments.add_leading(
+                bar.lo(),
+                vec![Comment {
+                    kind: CommentKind::Block,
+                    span: DUMMY_SP,
+                    text: " relocated ".into(),
+                }],
+            );
+
+            let output = c
+                .process_js_with_comments(
+                    program,
+                    None,
+                    &Options {
+                        swcrc: false,
+                        ..Default::default()
+                    },
+                    &comments,
+                )
+                .expect("failed to process js module");
+            println!("{}", output.code);
+
+            assert!(output.code.contains("/* relocated */"));
+            assert!(!output.code.contains("original"));
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn parse_expr_and_stmt() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let expr = c
+                .parse_expr("{ a: 1 }", Default::default())
+                .expect("failed to parse expression");
+            match *expr {
+                Expr::Object(..) => {}
+                _ => panic!("expected an object literal, got {:?}", expr),
+            }
+            c.parse_expr("a, b;", Default::default())
+                .expect("a sequence expression is a single expression");
+            c.parse_expr("a; b", Default::default())
+                .expect_err("should fail for multiple expressions");
+
+            let stmt = c
+                .parse_stmt("if (a) b();", Default::default())
+                .expect("failed to parse statement");
+            match stmt {
+                Stmt::If(..) => {}
+                _ => panic!("expected an if statement, got {:?}", stmt),
+            }
+            c.parse_stmt("a; b;", Default::default())
+                .expect_err("should fail for multiple statements");
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn print_with_handlers() {
+    struct StmtCounter(Rc<Cell<usize>>);
+
+    impl Handlers for StmtCounter {
+        fn on_before_emit_stmt(&mut self, _: &Stmt) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    let count = Rc::new(Cell::new(0));
+
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm.new_source_file(FileName::Anon, "foo(); if (bar) { baz(); }".into());
+            let (program, _) = c
+                .parse_js(
+                    fm,
+                    JscTarget::Es5,
+                    Default::default(),
+                    false,
+                    false,
+                    &InputSourceMap::Bool(false),
+                )
+                .expect("failed to parse");
+
+            c.print_with_handlers(
+                &program,
+                c.comments(),
+                SourceMapsConfig::Bool(false),
+                None,
+                Default::default(),
+                box StmtCounter(count.clone()),
+            )
+            .expect("failed to print");
+
+            Ok(())
+        })
+        .unwrap();
+
+    assert_eq!(count.get(), 4);
+}
+
+#[test]
+fn print_big_int_for_target() {
+    struct UnsupportedCounter(Rc<Cell<usize>>);
+
+    impl Handlers for UnsupportedCounter {
+        fn on_unsupported_syntax(&mut self, _: Span, _: &str) {
+            self.0.set(self.0.get() + 1);
+        }
+    }
+
+    let count = Rc::new(Cell::new(0));
+    let print = |target: JscTarget| {
+        Tester::new().print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm.new_source_file(FileName::Anon, "const a = 10n;".into());
+            let (program, _) = c
+                .parse_js(
+                    fm,
+                    JscTarget::EsNext,
+                    Default::default(),
+                    true,
+                    false,
+                    &InputSourceMap::Bool(false),
+                )
+                .expect("failed to parse");
+
+            c.print_with_handlers(
+                &program,
+                c.comments(),
+                SourceMapsConfig::Bool(false),
+                None,
+                codegen::Config {
+                    target,
+                    ..Default::default()
+                },
+                box UnsupportedCounter(count.clone()),
+            )
+            .map(|output| output.code)
+            .map_err(|_| ())
+        })
+    };
+
+    let err = print(JscTarget::Es2019).expect_err("BigInt should not be emitted for es2019");
+    assert!(err.contains("BigInt literals require the esnext target"));
+    assert_eq!(count.get(), 1);
+
+    let code = print(JscTarget::EsNext).expect("failed to print");
+    assert!(code.contains("10n"));
+    assert_eq!(count.get(), 1);
+}
+
+#[test]
+fn process_stmt_with_helpers() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm.new_source_file(FileName::Anon, "class Foo {}".into());
+            let (program, _) = c
+                .parse_js(
+                    fm,
+                    JscTarget::Es5,
+                    Default::default(),
+                    false,
+                    false,
+                    &InputSourceMap::Bool(false),
+                )
+                .expect("failed to parse");
+            let stmt = match program {
+                Program::Script(mut script) => script.body.remove(0),
+                _ => unreachable!(),
+            };
+
+            let output = c
+                .process_stmt(stmt.clone(), &Default::default())
+                .expect("failed to process statement");
+            println!("{}", output.code);
+
+            assert!(output.node.len() > 1);
+            assert!(output.code.contains("_classCallCheck"));
+
+            let err = c
+                .process_expr(
+                    Expr::Class(ClassExpr {
+                        ident: None,
+                        class: match stmt {
+                            Stmt::Decl(Decl::Class(decl)) => decl.class,
+                            _ => unreachable!(),
+                        },
+                    }),
+                    &Default::default(),
+                )
+                .expect_err("helpers should not be dropped");
+            println!("{:?}", err);
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn process_js_range() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let src = "const a = 1;\nconst f = () => a;\nclass Foo {}\n";
+            let fm = cm.new_source_file(FileName::Anon, src.into());
+
+            let start = src.find("const f").unwrap() as u32;
+            let end = src.find("class Foo {}").unwrap() as u32 + 5;
+            let range = fm.start_pos + BytePos(start)..fm.start_pos + BytePos(end);
+
+            let output = c
+                .process_js_range(fm, &Default::default(), range)
+                .expect("failed to process range");
+            println!("{}", output.code);
+
+            assert!(output.code.contains("var f = function()"));
+            assert!(output.code.contains("return a"));
+            assert!(!output.code.contains("var a"));
+            // Split by the range.
+            assert!(!output.code.contains("Foo"));
+            // Helper for the class.
+            assert!(!output.code.contains("_classCallCheck"));
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn process_js_file_with_override() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm
+                .load_file(Path::new("tests/projects/override-minify/input.js"))
+                .expect("failed to load file");
+            let opts = Options {
+                swcrc: true,
+                ..Default::default()
+            };
+
+            let output = c
+                .process_js_file_with_override(fm.clone(), &opts, None)
+                .expect("failed to process js file");
+            assert!(output.code.contains("function foo(a){"));
+
+            let output = c
+                .process_js_file_with_override(
+                    fm,
+                    &opts,
+                    Some(&Options {
+                        config: Some(Config {
+                            minify: Some(false),
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }),
+                )
+                .expect("failed to process js file");
+            println!("{}", output.code);
+
+            assert!(output.code.contains("function foo(a) {\n"));
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn process_js_file_with_override_turns_off_bool() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm
+                .load_file(Path::new("tests/projects/override-drop-debugger/input.js"))
+                .expect("failed to load file");
+            let opts = Options {
+                swcrc: true,
+                ..Default::default()
+            };
+
+            let output = c
+                .process_js_file_with_override(fm.clone(), &opts, None)
+                .expect("failed to process js file");
+            assert!(!output.code.contains("debugger"));
+
+            let output = c
+                .process_js_file_with_override(
+                    fm,
+                    &opts,
+                    Some(&Options {
+                        config: Some(Config {
+                            jsc: JscConfig {
+                                transform: Some(TransformConfig {
+                                    optimizer: Some(OptimizerConfig {
+                                        drop_debugger: false,
+                                        ..Default::default()
+                                    }),
+                                    ..Default::default()
+                                }),
+                                ..Default::default()
+                            },
+                            ..Default::default()
+                        }),
+                        ..Default::default()
+                    }),
+                )
+                .expect("failed to process js file");
+
+            assert!(output.code.contains("debugger"));
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn reparse() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm.new_source_file(FileName::Anon, "a();\nb();\nbar(1);\nc();\nd();".into());
+            let (prev, _) = c
+                .parse_js(
+                    fm,
+                    JscTarget::Es5,
+                    Default::default(),
+                    true,
+                    false,
+                    &InputSourceMap::Bool(false),
+                )
+                .expect("failed to parse js file");
+
+            let fm = cm.new_source_file(FileName::Anon, "a();\nb();\nbar(123);\nc();\nd();".into());
+            let program = c
+                .reparse(
+                    &prev,
+                    fm.clone(),
+                    TextEdit {
+                        range: 14..15,
+                        new_len: 3,
+                    },
+                    JscTarget::Es5,
+                    Default::default(),
+                )
+                .expect("failed to reparse js file");
+            let body = match program {
+                Program::Module(m) => m.body,
+                Program::Script(..) => unreachable!(),
+            };
+
+            assert_eq!(body.len(), 5);
+            assert_eq!(body[0].span().lo(), fm.start_pos);
+            assert_eq!(body[4].span().lo(), fm.start_pos + BytePos(25));
+            match body[2] {
+                ModuleItem::Stmt(Stmt::Expr(ExprStmt {
+                    expr: box Expr::Call(ref call),
+                    ..
+                })) => match *call.args[0].expr {
+                    Expr::Lit(Lit::Num(Number { value, .. })) => assert_eq!(value, 123.0),
+                    ref e => panic!("expected a number, got {:?}", e),
+                },
+                ref item => panic!("expected a call, got {:?}", item),
+            }
+
+            // Removing the semicolon merges `bar(1)` with `(c)()`.
+            let fm = cm.new_source_file(
+                FileName::Anon,
+                "a();\nb();\nbar(1)\n(c)();\nd();".into(),
+            );
+            let program = c
+                .reparse(
+                    &prev,
+                    fm,
+                    TextEdit {
+                        range: 16..22,
+                        new_len: 7,
+                    },
+                    JscTarget::Es5,
+                    Default::default(),
+                )
+                .expect("failed to reparse js file");
+            match program {
+                Program::Module(m) => assert_eq!(m.body.len(), 4),
+                Program::Script(..) => unreachable!(),
+            }
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn rename_top_level() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let mut outputs = vec![];
+            for (f, reserved) in vec![("a.js", vec![]), ("b.js", vec!["x", "log"])] {
+                let fm = cm
+                    .load_file(&Path::new("tests/projects/rename-top-level").join(f))
+                    .expect("failed to load file");
+                let (program, _) = c
+                    .parse_js(
+                        fm,
+                        JscTarget::Es5,
+                        Default::default(),
+                        false,
+                        false,
+                        &InputSourceMap::Bool(false),
+                    )
+                    .expect("failed to parse js file");
+
+                let program = c.run(|| {
+                    program.fold_with(&mut chain!(
+                        resolver(),
+                        optimization::rename_top_level(optimization::rename_top_level::Config {
+                            reserved: reserved.into_iter().map(From::from).collect(),
+                            ..Default::default()
+                        })
+                    ))
+                });
+                let output = c
+                    .print(
+                        &program,
+                        &Default::default(),
+                        SourceMapsConfig::Bool(false),
+                        None,
+                        Default::default(),
+                    )
+                    .expect("failed to print");
+                println!("{}", output.code);
+                outputs.push(output.code);
+            }
+
+            assert!(outputs[0].contains("var x = 1;"));
+            assert!(outputs[0].contains("console.log(x);"));
+
+            assert!(outputs[1].contains("var x1 = 2;"));
+            assert!(outputs[1].contains("x1 += 1;"));
+            assert!(outputs[1].contains("x: x1"));
+            assert!(outputs[1].contains("inc().x === 3"));
+            assert!(outputs[1].contains("var y = x1;"));
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn diagnostics() {
+    Tester::new()
+        .print_errors(|cm, _| {
+            let c = Compiler::with_diagnostics(cm.clone());
+            let opts = Options {
+                swcrc: false,
+                config: Some(Config {
+                    jsc: JscConfig {
+                        syntax: Some(Syntax::Typescript(Default::default())),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                }),
+                ..Default::default()
+            };
+
+            let fm = cm.new_source_file(
+                FileName::Custom("input.ts".into()),
+                "const enum E { A = 1 }\nconst key = 'A';\nconsole.log(E[key]);".into(),
+            );
+            assert!(c.process_js_file(fm, &opts).is_err());
+
+            let diagnostics = c.take_diagnostics();
+            assert_eq!(diagnostics.len(), 1);
+            assert!(diagnostics[0]
+                .message()
+                .contains("const enum member can only be accessed"));
+
+            let fm = cm.new_source_file(FileName::Custom("ok.ts".into()), "const a = 1;".into());
+            let output = c
+                .process_js_file(fm, &opts)
+                .expect("failed to process js file");
+            assert!(output.diagnostics.is_empty());
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn fail_on_warnings() {
+    let s = file("tests/projects/fail-on-warnings/input.js").unwrap();
+    println!("{}", s);
+    assert!(s.contains("process.env.NODE_ENV = 'test'"));
+    assert!(s.contains("console.log('production')"));
+
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm
+                .load_file(Path::new("tests/projects/fail-on-warnings/input.js"))
+                .expect("failed to load file");
+            let res = c.process_js_file(
+                fm,
+                &Options {
+                    swcrc: true,
+                    fail_on_warnings: true,
+                    ..Default::default()
+                },
+            );
+
+            let err = res.expect_err("a warning should make processing fail");
+            assert!(format!("{:?}", err).contains("1 warning(s)"), "{:?}", err);
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn self_assign() {
+    let s = file("tests/projects/self-assign/input.js").unwrap();
+    println!("{}", s);
+
+    assert!(!s.contains("x = x"));
+    assert!(s.contains("y = y++"));
+}
+
+#[test]
+fn import_assertions() {
+    let s = file("tests/projects/import-assertions/input.js").unwrap();
+    println!("{}", s);
+
+    assert!(s.contains("import data from './data.json' assert {"));
+    assert!(s.contains("type: 'json'"));
+    assert!(s.contains("import './style.css' assert {"));
+}
+
+#[test]
+fn import_assertions_minify() {
+    let s = file("tests/projects/import-assertions-minify/input.js").unwrap();
+    println!("{}", s);
+
+    assert!(s.contains("assert{type:"));
+}
+
+#[test]
+fn import_assertions_keep() {
+    let s = file("tests/projects/import-assertions-keep/input.js").unwrap();
+    println!("{}", s);
+
+    assert!(s.contains("import data from './data.json' assert {"));
+    assert!(s.contains("import './style.css' assert {"));
+}
+
+#[test]
+fn import_assertions_removed_by_default() {
+    let s = file("tests/projects/import-assertions-es2015/input.js").unwrap();
+    println!("{}", s);
+
+    assert!(s.contains("import data from './data.json';"));
+    assert!(!s.contains("assert"));
+}
+
+#[test]
+fn fold_program() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm
+                .load_file(Path::new("tests/projects/const-enum/input.ts"))
+                .expect("failed to load file");
+            let (program, comments) = c
+                .fold_program(
+                    fm,
+                    &Options {
+                        swcrc: true,
+                        ..Default::default()
+                    },
+                )
+                .expect("failed to fold program");
+
+            match program {
+                Program::Script(ref s) => assert_eq!(s.body.len(), 1),
+                Program::Module(_) => panic!("expected a script"),
+            }
+
+            let output = c
+                .print(
+                    &program,
+                    comments,
+                    SourceMapsConfig::Bool(false),
+                    None,
+                    Default::default(),
+                )
+                .expect("failed to print");
+            println!("{}", output.code);
+            assert!(output.code.contains("2 /* Direction.Down */"));
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn shorthand() {
+    let s = file("tests/projects/shorthand/input.js").unwrap();
+    println!("{}", s);
+
+    assert!(s.contains("a: a"));
+    assert!(s.contains("foo: function()"));
+}
@@ -0,0 +1,5 @@
+class Foo {
+    foo = 1;
+    "bar-baz" = 2;
+    static qux = 3;
+}
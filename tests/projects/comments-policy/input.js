@@ -0,0 +1,5 @@
+/*! license */
+// leading
+const a = 1; // trailing
+
+export default a;
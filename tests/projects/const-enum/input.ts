@@ -0,0 +1,10 @@
+const enum Direction {
+  Up = 1,
+  Down,
+}
+
+const enum Color {
+  Red = "red",
+}
+
+console.log(Direction.Down, Color.Red);
@@ -0,0 +1,15 @@
+function dec(target: any, key: string, descriptor: any) {}
+
+export class Foo {
+    @dec
+    method(a: string, b: Bar): number {
+        return 1;
+    }
+
+    factory() {
+        return class {
+            @dec
+            inner(c: boolean) {}
+        };
+    }
+}
@@ -0,0 +1,8 @@
+function dec(target, key, descriptor) {}
+
+export class Foo {
+    @dec
+    method() {
+        return 1;
+    }
+}
@@ -0,0 +1,6 @@
+"use client";
+
+export function f() {
+    "use strict";
+    return 1;
+}
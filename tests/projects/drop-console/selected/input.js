@@ -0,0 +1,6 @@
+export function foo(a) {
+    console.log(a);
+    console.debug(a);
+    console.error(a);
+    return a;
+}
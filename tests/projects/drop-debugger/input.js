@@ -0,0 +1,6 @@
+export function foo(a) {
+    debugger;
+    if (a) debugger;
+    console.log(a);
+    return a;
+}
@@ -0,0 +1,9 @@
+export class Foo {
+    bar = 1;
+    static baz = 2;
+}
+const data = await load();
+export const value = data?.value ?? 0;
+export const fn = async (...args)=>({
+        ...args
+    });
@@ -0,0 +1,3 @@
+process.env.NODE_ENV = "test";
+
+console.log(process.env.NODE_ENV);
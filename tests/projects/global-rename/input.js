@@ -0,0 +1,5 @@
+Promise.resolve(1);
+
+function foo(Promise) {
+    return Promise;
+}
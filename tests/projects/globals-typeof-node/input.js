@@ -0,0 +1,5 @@
+if (typeof process === "undefined") {
+    console.log("browser");
+} else {
+    console.log("server");
+}
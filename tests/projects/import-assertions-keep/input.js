@@ -0,0 +1,4 @@
+import data from "./data.json" assert { type: "json" };
+import "./style.css" assert { type: "css" };
+
+console.log(data);
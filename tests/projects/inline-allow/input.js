@@ -0,0 +1,6 @@
+export function foo() {
+    var a = 1;
+    bar();
+    var b = a;
+    use(b);
+}
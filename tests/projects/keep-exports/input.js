@@ -0,0 +1,12 @@
+const internal = 'internal';
+export { internal };
+
+export const VERSION = '1.0.0';
+
+export let state = init();
+
+function init() {
+    return {};
+}
+
+console.log(state);
@@ -0,0 +1,3 @@
+function foo(a) {
+    return a + 1;
+}
@@ -0,0 +1,17 @@
+// header
+/* the answer */
+const a = 42;
+
+/* before dead code */
+if (false) {
+    // inside dead code
+    console.log('dead');
+}
+
+/**
+ * Returns the answer.
+ */
+export function f() {
+    // body
+    return a; // trailing
+}
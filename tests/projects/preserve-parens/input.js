@@ -0,0 +1,3 @@
+export var y = (a + b);
+foo((a));
+export var z = (a * ((b + c)));
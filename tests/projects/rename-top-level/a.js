@@ -0,0 +1,4 @@
+var x = 1;
+function log() {
+    console.log(x);
+}
@@ -0,0 +1,8 @@
+var x = 2;
+function inc() {
+    x += 1;
+    return { x };
+}
+if (inc().x === 3) {
+    var y = x;
+}
@@ -0,0 +1,5 @@
+import { a } from './a';
+export * from './b';
+export { c } from 'c';
+
+import('./d').then(() => a);
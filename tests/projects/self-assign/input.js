@@ -0,0 +1,7 @@
+var x = f();
+x = x;
+use(x);
+
+var y = f();
+y = y++;
+use(y);
@@ -0,0 +1,8 @@
+const a = 1;
+
+export const obj = {
+  a,
+  foo() {
+    return a;
+  },
+};
@@ -0,0 +1,2 @@
+// @swc-disable generated file
+export const foo = (a)=>`${a}`;
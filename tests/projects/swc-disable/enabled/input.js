@@ -0,0 +1,3 @@
+// generated file
+// @swc-disable
+export const foo = (a)=>`${a}`;
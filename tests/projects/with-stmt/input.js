@@ -0,0 +1,7 @@
+var x = 1;
+
+with (obj) {
+    console.log(x);
+}
+
+console.log(x);
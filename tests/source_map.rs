@@ -1,6 +1,9 @@
-use std::{fs::canonicalize, process::Command};
+use std::{fs::canonicalize, path::Path, process::Command, sync::Arc};
 use swc::{
-    config::{Options, SourceMapsConfig},
+    common::{BytePos, FileName},
+    config::{InputSourceMap, JscTarget, Options, SourceMapsConfig},
+    ecmascript::ast::{Expr, ExprStmt, Ident, Module, ModuleItem, Program, Stmt},
+    sourcemap::{SourceMap, SourceMapBuilder},
     Compiler,
 };
 use testing::{StdErr, Tester};
@@ -19,6 +22,7 @@ fn file(f: &str) -> Result<(), StdErr> {
                     swcrc: true,
                     is_module: true,
                     source_maps: Some(SourceMapsConfig::Bool(true)),
+                    sources_content: true,
                     ..Default::default()
                 },
             )
@@ -69,6 +73,7 @@ fn inline(f: &str) -> Result<(), StdErr> {
                     swcrc: true,
                     is_module: true,
                     source_maps: Some(SourceMapsConfig::Str(String::from("inline"))),
+                    sources_content: true,
                     ..Default::default()
                 },
             )
@@ -99,3 +104,619 @@ fn inline(f: &str) -> Result<(), StdErr> {
 fn issue_706() {
     inline("tests/srcmap/issue-706/index.js").unwrap();
 }
+
+/// Printing a file without any transform should produce an identity mapping.
+#[test]
+fn identity() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm
+                .load_file(Path::new("tests/srcmap/identity/index.js"))
+                .expect("failed to load file");
+            let (program, _) = c
+                .parse_js(
+                    fm.clone(),
+                    Default::default(),
+                    Default::default(),
+                    true,
+                    true,
+                    &InputSourceMap::Bool(false),
+                )
+                .expect("failed to parse");
+            let output = c
+                .print(
+                    &program,
+                    c.comments(),
+                    SourceMapsConfig::Bool(true),
+                    None,
+                    Default::default(),
+                )
+                .expect("failed to print");
+
+            // The input is formatted like the output of codegen.
+            assert_eq!(output.code, *fm.src);
+
+            let map = SourceMap::from_slice(output.map.unwrap().as_bytes())
+                .expect("failed to parse source map");
+            assert_ne!(map.get_token_count(), 0);
+
+            for token in map.tokens() {
+                assert_eq!(
+                    (token.get_src_line(), token.get_src_col()),
+                    (token.get_dst_line(), token.get_dst_col()),
+                    "{} maps to a wrong position",
+                    token
+                );
+                assert_eq!(token.get_source(), Some(&*fm.name.to_string()));
+            }
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn concat() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let outputs = vec![
+                ("a.js", "const a = 1;\nconsole.log(a);"),
+                ("b.js", "\n\nconst b = 2;\n"),
+            ]
+            .into_iter()
+            .map(|(name, src)| {
+                let name = FileName::Custom(name.into());
+                let fm = cm.new_source_file(name.clone(), src.into());
+                let output = c
+                    .process_js_file(
+                        fm,
+                        &Options {
+                            source_maps: Some(SourceMapsConfig::Bool(true)),
+                            input_source_map: InputSourceMap::Bool(false),
+                            ..Default::default()
+                        },
+                    )
+                    .expect("failed to process js file");
+
+                (name, output)
+            })
+            .collect::<Vec<_>>();
+
+            let output = c.concat(&outputs).expect("failed to concat");
+            println!("{}", output.code);
+
+            let line = output
+                .code
+                .lines()
+                .position(|l| l.contains("b = 2"))
+                .expect("b.js should be emitted") as u32;
+            assert_ne!(line, 0);
+
+            let map = SourceMap::from_slice(output.map.unwrap().as_bytes())
+                .expect("failed to parse source map");
+            let token = map.lookup_token(line, 4).expect("failed to find a token");
+
+            assert_eq!(token.get_source(), Some(&*outputs[1].0.to_string()));
+            assert_eq!(token.get_src_line(), 2);
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn bundle_concat() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let files = vec![
+                ("a.js", "const a = 1;\nconsole.log(a);"),
+                ("b.js", "\n\nconst b = 2;\n"),
+            ]
+            .into_iter()
+            .map(|(name, src)| cm.new_source_file(FileName::Custom(name.into()), src.into()))
+            .collect::<Vec<_>>();
+
+            let output = c
+                .bundle_concat(
+                    &files,
+                    &Options {
+                        source_maps: Some(SourceMapsConfig::Bool(true)),
+                        input_source_map: InputSourceMap::Bool(false),
+                        ..Default::default()
+                    },
+                )
+                .expect("failed to bundle");
+            println!("{}", output.code);
+
+            assert!(output.code.contains("console.log(a)"));
+            let line = output
+                .code
+                .lines()
+                .position(|l| l.contains("b = 2"))
+                .expect("b.js should be emitted") as u32;
+            assert_ne!(line, 0);
+
+            let map = SourceMap::from_slice(output.map.unwrap().as_bytes())
+                .expect("failed to parse source map");
+            let token = map.lookup_token(line, 4).expect("failed to find a token");
+
+            assert_eq!(token.get_source(), Some(&*files[1].name.to_string()));
+            assert_eq!(token.get_src_line(), 2);
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn source_file_name() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+            let name = FileName::Custom("generated".into());
+            let opts = Options {
+                source_maps: Some(SourceMapsConfig::Bool(true)),
+                input_source_map: InputSourceMap::Bool(false),
+                source_file_name: Some("src/foo.js".into()),
+                ..Default::default()
+            };
+
+            let fm = cm.new_source_file(name.clone(), "const a = 1;".into());
+            let output = c
+                .process_js_file(fm, &opts)
+                .expect("failed to process js file");
+
+            let map = SourceMap::from_slice(output.map.unwrap().as_bytes())
+                .expect("failed to parse source map");
+            assert_eq!(map.sources().collect::<Vec<_>>(), vec!["src/foo.js"]);
+
+            // Diagnostics should use the name of the file.
+            let fm = cm.new_source_file(name.clone(), "const a = ;".into());
+            let diagnostics = c
+                .check_syntax_only(fm, &opts)
+                .expect("failed to check syntax");
+            let span = diagnostics[0].span.primary_span().unwrap();
+            assert_eq!(cm.span_to_filename(span), name);
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn filename_map() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+            let opts = Options {
+                source_maps: Some(SourceMapsConfig::Bool(true)),
+                input_source_map: InputSourceMap::Bool(false),
+                filename_map: Some(Arc::new(|name: &FileName| match name {
+                    FileName::Custom(id) => format!("webpack:///{}", id),
+                    _ => name.to_string(),
+                })),
+                ..Default::default()
+            };
+
+            let fm = cm.new_source_file(FileName::Custom("42".into()), "const a = 1;".into());
+            let output = c
+                .process_js_file(fm, &opts)
+                .expect("failed to process js file");
+
+            let map = SourceMap::from_slice(output.map.unwrap().as_bytes())
+                .expect("failed to parse source map");
+            assert_eq!(map.sources().collect::<Vec<_>>(), vec!["webpack:///42"]);
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn source_map_ignore() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+            let opts = Options {
+                source_maps: Some(SourceMapsConfig::Bool(true)),
+                input_source_map: InputSourceMap::Bool(false),
+                source_map_ignore: Some(Arc::new(|name: &FileName| match name {
+                    FileName::Custom(id) => id.starts_with("vendor/"),
+                    _ => false,
+                })),
+                ..Default::default()
+            };
+
+            let fm = cm.new_source_file(
+                FileName::Custom("vendor/lib.js".into()),
+                "const a = 1;".into(),
+            );
+            let output = c
+                .process_js_file(fm, &opts)
+                .expect("failed to process js file");
+
+            let map: serde_json::Value =
+                serde_json::from_str(&output.map.unwrap()).expect("failed to parse source map");
+            assert_eq!(map["x_google_ignoreList"], serde_json::json!([0]));
+
+            let fm = cm.new_source_file(FileName::Custom("app.js".into()), "const b = 2;".into());
+            let output = c
+                .process_js_file(fm, &opts)
+                .expect("failed to process js file");
+
+            let map: serde_json::Value =
+                serde_json::from_str(&output.map.unwrap()).expect("failed to parse source map");
+            assert_eq!(map.get("x_google_ignoreList"), None);
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+/// Original names of identifiers renamed by transforms should be recorded.
+#[test]
+fn names_of_renamed_idents() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm.new_source_file(
+                FileName::Custom("input.js".into()),
+                "var a = 1;\n{\n    let a = 2;\n    console.log(a);\n}".into(),
+            );
+            let output = c
+                .process_js_file(
+                    fm,
+                    &Options {
+                        source_maps: Some(SourceMapsConfig::Bool(true)),
+                        input_source_map: InputSourceMap::Bool(false),
+                        ..Default::default()
+                    },
+                )
+                .expect("failed to process js file");
+            let map = SourceMap::from_slice(output.map.unwrap().as_bytes())
+                .expect("failed to parse source map");
+            assert!(map.tokens().any(|token| token.get_name() == Some("a")));
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+/// Identifiers with spans of other nodes are not renamed identifiers.
+#[test]
+fn names_of_idents_with_other_spans() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm =
+                cm.new_source_file(FileName::Custom("input.js".into()), "foo.bar;\nbaz;".into());
+            let mut module = match c.parse_js(
+                fm,
+                JscTarget::Es5,
+                Default::default(),
+                true,
+                true,
+                &InputSourceMap::Bool(false),
+            ) {
+                Ok((Program::Module(m), _)) => m,
+                _ => unreachable!(),
+            };
+            for item in &mut module.body {
+                if let ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. })) = item {
+                    // Replace `foo.bar` and the `az` of `baz`.
+                    let span = match **expr {
+                        Expr::Member(ref e) => e.span,
+                        Expr::Ident(ref i) => i.span.with_lo(i.span.lo() + BytePos(1)),
+                        _ => unreachable!(),
+                    };
+                    *expr = Box::new(Expr::Ident(Ident::new("_tmp".into(), span)));
+                }
+            }
+
+            let output = c
+                .print(
+                    &Program::Module(module),
+                    &Default::default(),
+                    SourceMapsConfig::Bool(true),
+                    None,
+                    Default::default(),
+                )
+                .expect("failed to print");
+            let map = SourceMap::from_slice(output.map.unwrap().as_bytes())
+                .expect("failed to parse source map");
+            assert_eq!(map.names().count(), 0);
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+/// An inline source map should be composed with the input source map.
+#[test]
+fn inline_with_input_source_map() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm.new_source_file(
+                FileName::Custom("original.js".into()),
+                "const a = 1;\n\n\nconsole.log(a);".into(),
+            );
+            let first = c
+                .process_js_file(
+                    fm,
+                    &Options {
+                        source_maps: Some(SourceMapsConfig::Bool(true)),
+                        input_source_map: InputSourceMap::Bool(false),
+                        ..Default::default()
+                    },
+                )
+                .expect("failed to process original file");
+
+            let fm = cm.new_source_file(
+                FileName::Custom("intermediate.js".into()),
+                first.code.clone(),
+            );
+            let second = c
+                .process_js_file(
+                    fm,
+                    &Options {
+                        source_maps: Some(SourceMapsConfig::Str(String::from("inline"))),
+                        input_source_map: InputSourceMap::Str(first.map.unwrap()),
+                        ..Default::default()
+                    },
+                )
+                .expect("failed to process intermediate file");
+            println!("{}", second.code);
+
+            let prefix = "sourceMappingURL=data:application/json;base64,";
+            let idx = second
+                .code
+                .rfind(prefix)
+                .expect("source map should be inlined");
+            let map = base64::decode(second.code[idx + prefix.len()..].trim().as_bytes())
+                .expect("failed to decode source map");
+            let map = SourceMap::from_slice(&map).expect("failed to parse source map");
+
+            let line = second
+                .code
+                .lines()
+                .position(|l| l.contains("console.log"))
+                .expect("console.log should be emitted") as u32;
+            let token = map.lookup_token(line, 0).expect("failed to find a token");
+
+            assert_eq!(token.get_source(), Some("original.js"));
+            assert_eq!(token.get_src_line(), 3);
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn source_map_only() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let fm = cm.new_source_file(
+                FileName::Custom("input.js".into()),
+                "const a = 1;\n\nfunction foo() {\n    return a;\n}".into(),
+            );
+            let (program, _) = c
+                .parse_js(
+                    fm,
+                    Default::default(),
+                    Default::default(),
+                    true,
+                    true,
+                    &InputSourceMap::Bool(false),
+                )
+                .expect("failed to parse");
+
+            let output = c
+                .print(
+                    &program,
+                    c.comments(),
+                    SourceMapsConfig::Bool(true),
+                    None,
+                    Default::default(),
+                )
+                .expect("failed to print");
+            let map = c
+                .source_map_only(&program, c.comments(), None, Default::default())
+                .expect("failed to build source map");
+
+            assert_eq!(Some(map), output.map);
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn remap() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+
+            let mut inner = SourceMapBuilder::new(Some("intermediate.js"));
+            let token = inner.add(0, 0, 2, 4, Some("original.ts"), Some("foo"));
+            inner.set_source_contents(token.src_id, Some("\n\n    foo();"));
+            let inner = inner.into_sourcemap();
+
+            let mut outer = SourceMapBuilder::new(Some("output.js"));
+            outer.add(1, 2, 0, 0, Some("intermediate.js"), None);
+            let outer = outer.into_sourcemap();
+
+            let map = c.remap(&outer, &inner);
+            let token = map.lookup_token(1, 2).expect("failed to find a token");
+
+            assert_eq!(token.get_source(), Some("original.ts"));
+            assert_eq!(token.get_src_line(), 2);
+            assert_eq!(token.get_src_col(), 4);
+            assert_eq!(token.get_name(), Some("foo"));
+            assert_eq!(
+                map.get_source_contents(token.get_src_id()),
+                Some("\n\n    foo();")
+            );
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn sources_content() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+            let src = "const a = 1;\nconsole.log(a);";
+
+            let process = |source_maps: SourceMapsConfig, sources_content: bool| {
+                let fm = cm.new_source_file(FileName::Custom("input.js".into()), src.into());
+                let output = c
+                    .process_js_file(
+                        fm,
+                        &Options {
+                            source_maps: Some(source_maps),
+                            input_source_map: InputSourceMap::Bool(false),
+                            sources_content,
+                            ..Default::default()
+                        },
+                    )
+                    .expect("failed to process js file");
+
+                let map = match output.map {
+                    Some(map) => map.into_bytes(),
+                    None => {
+                        let prefix = "sourceMappingURL=data:application/json;base64,";
+                        let idx = output
+                            .code
+                            .rfind(prefix)
+                            .expect("source map should be inlined");
+                        base64::decode_config(
+                            output.code[idx + prefix.len()..].trim().as_bytes(),
+                            base64::URL_SAFE,
+                        )
+                        .expect("failed to decode source map")
+                    }
+                };
+                let map = SourceMap::from_slice(&map).expect("failed to parse source map");
+                map.get_source_contents(0).map(String::from)
+            };
+
+            let inline = || SourceMapsConfig::Str(String::from("inline"));
+            assert_eq!(process(SourceMapsConfig::Bool(true), false), None);
+            assert_eq!(
+                process(SourceMapsConfig::Bool(true), true).as_deref(),
+                Some(src)
+            );
+            assert_eq!(process(inline(), false), None);
+            assert_eq!(process(inline(), true).as_deref(), Some(src));
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn relative_sources() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+            let cwd = std::env::current_dir().unwrap();
+
+            let sources = |root: &str| {
+                let fm = cm
+                    .load_file(Path::new("tests/srcmap/identity/index.js"))
+                    .expect("failed to load file");
+                let output = c
+                    .process_js_file(
+                        fm,
+                        &Options {
+                            source_maps: Some(SourceMapsConfig::Bool(true)),
+                            input_source_map: InputSourceMap::Bool(false),
+                            root: Some(cwd.join(root)),
+                            relative_sources: true,
+                            ..Default::default()
+                        },
+                    )
+                    .expect("failed to process js file");
+
+                let map = SourceMap::from_slice(output.map.unwrap().as_bytes())
+                    .expect("failed to parse source map");
+                map.sources().map(String::from).collect::<Vec<_>>()
+            };
+
+            assert_eq!(sources("tests"), vec!["srcmap/identity/index.js"]);
+            assert_eq!(c.handler.warn_count(), 0);
+
+            let absolute = cwd.join("tests/srcmap/identity/index.js");
+            assert_eq!(
+                sources("tests/projects"),
+                vec![absolute.display().to_string()]
+            );
+            assert_eq!(c.handler.warn_count(), 1);
+
+            Ok(())
+        })
+        .unwrap()
+}
+
+#[test]
+fn source_file_name_of_input_file() {
+    Tester::new()
+        .print_errors(|cm, handler| {
+            let c = Compiler::new(cm.clone(), handler);
+            let opts = Options {
+                source_maps: Some(SourceMapsConfig::Bool(true)),
+                input_source_map: InputSourceMap::Bool(false),
+                source_file_name: Some("src/foo.js".into()),
+                ..Default::default()
+            };
+            let parse = |name: &str, src: &str| {
+                let fm = cm.new_source_file(FileName::Custom(name.into()), src.into());
+                match c.parse_js(
+                    fm,
+                    JscTarget::Es5,
+                    Default::default(),
+                    true,
+                    true,
+                    &InputSourceMap::Bool(false),
+                ) {
+                    Ok((Program::Module(m), _)) => m,
+                    _ => unreachable!(),
+                }
+            };
+
+            // Code injected from another file is emitted before the input file.
+            let input = parse("generated", "foo();");
+            let injected = parse("injected", "bar();");
+            let program = Program::Module(Module {
+                body: injected.body.into_iter().chain(input.body).collect(),
+                ..input
+            });
+
+            let output = c
+                .process_js(program, None, &opts)
+                .expect("failed to process js");
+            let map = SourceMap::from_slice(output.map.unwrap().as_bytes())
+                .expect("failed to parse source map");
+            assert_eq!(
+                map.sources().collect::<Vec<_>>(),
+                vec!["injected", "src/foo.js"]
+            );
+
+            Ok(())
+        })
+        .unwrap()
+}
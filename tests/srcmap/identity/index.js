@@ -0,0 +1,29 @@
+import foo, { bar as baz } from 'foo';
+// comment
+const a = 1.5, b = /ab+c/gi, c = `x ${a} y`;
+let obj = {
+    a,
+    b: 2,
+    [c]: 'ü',
+    m () {
+        return this.a; /* trailing */
+    }
+};
+class Foo extends Bar {
+    constructor(){
+        super();
+        this.x = new Baz(a, ...b);
+    }
+    get y() {
+        return 1;
+    }
+}
+for(let i = 0; i < 10; i++){
+    if (i % 2) continue;
+    else {
+        obj[i] = i in obj;
+    }
+}
+export function f(x, y) {
+    return x ? y : !x;
+}